@@ -0,0 +1,106 @@
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+// `![alt](path)` image references in entry text
+pub struct ImageRef<'a> {
+    // Byte range of the whole reference in the source text
+    pub start: usize,
+    pub end: usize,
+    pub alt: &'a str,
+    pub target: &'a str,
+}
+
+pub fn find_images(text: &str) -> Vec<ImageRef<'_>> {
+    let mut images = Vec::new();
+    let mut offset = 0;
+    while let Some(open) = text[offset..].find("![") {
+        let start = offset + open;
+        let alt_start = start + 2;
+        let parsed = text[alt_start..].find("](").and_then(|close| {
+            let target_start = alt_start + close + 2;
+            let target_len = text[target_start..].find(')')?;
+            Some((close, target_start, target_len))
+        });
+        let Some((close, target_start, target_len)) = parsed else {
+            break;
+        };
+        let alt = &text[alt_start..alt_start + close];
+        // An optional title follows the path: ![alt](path "title")
+        let raw_target = &text[target_start..target_start + target_len];
+        let target = raw_target.split(" \"").next().unwrap_or("").trim();
+        let target = target.strip_prefix('<').and_then(|t| t.strip_suffix('>')).unwrap_or(target);
+        let end = target_start + target_len + 1;
+        
+        if alt.contains('\n') || raw_target.contains('\n') || target.is_empty() {
+            offset = alt_start;
+            continue;
+        }
+        images.push(ImageRef { start, end, alt, target });
+        offset = end;
+    }
+    images
+}
+
+// The short form shown instead of the markdown, e.g. "🖼 image: beach.jpg"
+pub fn placeholder(image: &ImageRef) -> String {
+    let name = image.target.trim_end_matches('/').rsplit(['/', '\\']).next().unwrap_or(image.target);
+    format!("🖼 image: {}", name)
+}
+
+// `text` with every image reference replaced by its placeholder
+pub fn replace_images(text: &str) -> String {
+    let mut replaced = String::new();
+    let mut last = 0;
+    for image in find_images(text) {
+        replaced.push_str(&text[last..image.start]);
+        replaced.push_str(&placeholder(&image));
+        last = image.end;
+    }
+    replaced.push_str(&text[last..]);
+    replaced
+}
+
+pub fn is_remote(target: &str) -> bool {
+    target.starts_with("http://") || target.starts_with("https://")
+}
+
+// Where a local attachment lives: absolute and `~/` paths as written, anything else
+// relative to the journal directory so attachments can sit next to the entries
+pub fn resolve(target: &str, journal_dir: &Path) -> PathBuf {
+    let target = target.strip_prefix("file://").unwrap_or(target);
+    if let Some(rest) = target.strip_prefix("~/")
+        && let Some(home) = dirs::home_dir()
+    {
+        return home.join(rest);
+    }
+    let path = Path::new(target);
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        journal_dir.join(path)
+    }
+}
+
+// Hands a file or URL to the desktop's default viewer without waiting for it
+pub fn open_external(target: &str) -> Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(target_os = "windows") {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    let mut child = command
+        .arg(target)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to open {}", target))?;
+    // Reap the launcher once it hands off to the viewer
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
\ No newline at end of file
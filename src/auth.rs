@@ -1,7 +1,40 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use std::process::{Command, Stdio};
+
+use journal_tui::config::AuthConfig;
+
+// A configured `[auth] command` replaces the platform check: exit status 0 means authenticated
+pub fn authenticate(config: &AuthConfig) -> Result<bool> {
+    match &config.command {
+        Some(command) => run_command(command),
+        None => platform_authenticate(),
+    }
+}
+
+// Runs through the shell so pipes and arguments work as typed. Output is discarded so it
+// can't scribble over the animation; prompts should use their own window or /dev/tty.
+fn run_command(command: &str) -> Result<bool> {
+    let mut shell = if cfg!(target_os = "windows") {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    let status = shell
+        .arg(command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .with_context(|| format!("Failed to run auth command '{}'", command))?;
+    Ok(status.success())
+}
 
 #[cfg(target_os = "macos")]
-pub fn authenticate() -> Result<bool> {
+fn platform_authenticate() -> Result<bool> {
     use localauthentication_rs::{LocalAuthentication, LAPolicy};
     
     let auth = LocalAuthentication::new();
@@ -25,8 +58,24 @@ pub fn authenticate() -> Result<bool> {
     }
 }
 
-#[cfg(not(target_os = "macos"))]
-pub fn authenticate() -> Result<bool> {
+#[cfg(target_os = "windows")]
+fn platform_authenticate() -> Result<bool> {
+    use windows::core::HSTRING;
+    use windows::Security::Credentials::UI::{
+        UserConsentVerificationResult, UserConsentVerifier, UserConsentVerifierAvailability,
+    };
+    
+    // Without Windows Hello set up there's nothing to verify against, as on other platforms
+    if UserConsentVerifier::CheckAvailabilityAsync()?.get()? != UserConsentVerifierAvailability::Available {
+        return Ok(true);
+    }
+    
+    let result = UserConsentVerifier::RequestVerificationAsync(&HSTRING::from("Access your private journal entries"))?.get()?;
+    Ok(result == UserConsentVerificationResult::Verified)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn platform_authenticate() -> Result<bool> {
     // On non-macOS systems, just return true (no authentication)
     Ok(true)
 }
\ No newline at end of file
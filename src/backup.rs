@@ -0,0 +1,26 @@
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::Path;
+
+use crate::{config, crypto};
+
+// Copy `file` into `dir` under the same name, as stored: per-entry encryption carries over.
+// A plain entry is refused unless `allow_plaintext`, since it would sit unencrypted outside
+// the vault. Returns false when the copy there is already identical. Old copies are never
+// removed, so a renamed entry leaves its old name behind.
+pub fn mirror(file: &Path, dir: &Path, allow_plaintext: bool) -> Result<bool> {
+    let dir = config::expand_home(dir);
+    let name = file.file_name().with_context(|| format!("{} has no file name", file.display()))?;
+    let target = dir.join(name);
+    
+    let data = fs::read(file).with_context(|| format!("Failed to read {}", file.display()))?;
+    if !allow_plaintext && !crypto::is_encrypted(&data) {
+        bail!("{} isn't encrypted, so it wasn't copied out of the vault; enable [encryption], or set [backup] allow_plaintext = true", name.to_string_lossy());
+    }
+    if fs::read(&target).is_ok_and(|existing| existing == data) {
+        return Ok(false);
+    }
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    fs::write(&target, data).with_context(|| format!("Failed to write {}", target.display()))?;
+    Ok(true)
+}
\ No newline at end of file
@@ -0,0 +1,127 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+// Alt-1 through Alt-9
+pub const SLOTS: usize = 9;
+
+// Entry filenames kept on numbered slots, saved beside the entries they point at so they
+// follow the journal folder (and stay inside the vault)
+pub struct Bookmarks {
+    path: PathBuf,
+    slots: [Option<String>; SLOTS],
+}
+
+impl Bookmarks {
+    // The bookmarks for the journal in `dir`; none yet when the file doesn't exist
+    pub fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(".bookmarks");
+        let mut slots: [Option<String>; SLOTS] = Default::default();
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        // One "<slot> <filename>" per line; anything else is skipped
+        for line in text.lines() {
+            if let Some((slot, name)) = line.split_once(' ')
+                && let Ok(slot) = slot.parse::<usize>()
+                && (1..=SLOTS).contains(&slot)
+                && !name.trim().is_empty()
+            {
+                slots[slot - 1] = Some(name.trim().to_string());
+            }
+        }
+        Ok(Self { path, slots })
+    }
+    
+    // Filename on a slot, 1-based
+    pub fn get(&self, slot: usize) -> Option<&str> {
+        self.slots.get(slot.checked_sub(1)?)?.as_deref()
+    }
+    
+    // Assigned slots in order, as (slot, filename)
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &str)> {
+        self.slots.iter().enumerate().filter_map(|(i, name)| Some((i + 1, name.as_deref()?)))
+    }
+    
+    // Puts `name` on `slot`, taking it off any other slot so an entry has at most one
+    pub fn set(&mut self, slot: usize, name: &str) -> Result<()> {
+        self.remove(name);
+        self.slots[slot - 1] = Some(name.to_string());
+        self.save()
+    }
+    
+    // Takes `name` off its slot; false if it had none
+    pub fn clear(&mut self, name: &str) -> Result<bool> {
+        if !self.remove(name) {
+            return Ok(false);
+        }
+        self.save()?;
+        Ok(true)
+    }
+    
+    // Keeps a bookmark on an entry the app renamed or moved; nothing to do if it had none
+    pub fn rename(&mut self, from: &str, to: &str) -> Result<()> {
+        let Some(slot) = self.slots.iter_mut().find(|s| s.as_deref() == Some(from)) else {
+            return Ok(());
+        };
+        *slot = Some(to.to_string());
+        self.save()
+    }
+    
+    fn remove(&mut self, name: &str) -> bool {
+        let mut removed = false;
+        for slot in self.slots.iter_mut().filter(|s| s.as_deref() == Some(name)) {
+            *slot = None;
+            removed = true;
+        }
+        removed
+    }
+    
+    fn save(&self) -> Result<()> {
+        let text: String = self.iter().map(|(slot, name)| format!("{} {}\n", slot, name)).collect();
+        fs::write(&self.path, text).with_context(|| format!("Failed to save {}", self.path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn slots_survive_a_reload_and_bad_lines_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(".bookmarks"), "2 b.md\n0 zero.md\n10 ten.md\nnot a line\n4  \n").unwrap();
+        let mut bookmarks = Bookmarks::load(dir.path()).unwrap();
+        assert_eq!(bookmarks.iter().collect::<Vec<_>>(), [(2, "b.md")]);
+        
+        bookmarks.set(5, "e.md").unwrap();
+        let reloaded = Bookmarks::load(dir.path()).unwrap();
+        assert_eq!(reloaded.iter().collect::<Vec<_>>(), [(2, "b.md"), (5, "e.md")]);
+    }
+    
+    #[test]
+    fn an_entry_moves_between_slots_rather_than_taking_two() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut bookmarks = Bookmarks::load(dir.path()).unwrap();
+        bookmarks.set(1, "a.md").unwrap();
+        bookmarks.set(3, "a.md").unwrap();
+        assert_eq!((bookmarks.get(1), bookmarks.get(3)), (None, Some("a.md")));
+        assert!(bookmarks.clear("a.md").unwrap());
+        assert!(!bookmarks.clear("a.md").unwrap());
+    }
+    
+    #[test]
+    fn renamed_entries_keep_their_slot() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut bookmarks = Bookmarks::load(dir.path()).unwrap();
+        bookmarks.set(7, "20240101_120000_Draft.md").unwrap();
+        bookmarks.rename("20240101_120000_Draft.md", "20240101_120000_Final.md").unwrap();
+        bookmarks.rename("unbookmarked.md", "other.md").unwrap();
+        
+        let reloaded = Bookmarks::load(dir.path()).unwrap();
+        assert_eq!(reloaded.iter().collect::<Vec<_>>(), [(7, "20240101_120000_Final.md")]);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,136 @@
+use anyhow::{anyhow, bail, Result};
+use std::path::PathBuf;
+
+use journal_tui::listing::OutputFormat;
+
+pub const USAGE: &str = "\
+journal-tui - an encrypted terminal journal
+
+USAGE:
+    journal-tui [OPTIONS]
+
+OPTIONS:
+        --list             Print all entries and exit instead of opening the TUI
+        --format <FORMAT>  Output format for --list: json or plain (default: plain)
+        --change-password  Set a new vault password (stored in the keychain) and exit
+        --new-from-stdin <TITLE>
+                           Create an entry titled TITLE with piped stdin as its body and exit
+        --export-html <PATH>
+                           Write the newest entry as a styled, self-contained HTML page and exit
+        --entry <TITLE>    Export this entry instead of the newest (exact title, or a unique match)
+        --import <FILE>    Create entries from a JSON export (an array of {date, title, text}
+                           objects, as from Day One) and exit; unusable records are skipped
+        --check            Report empty, untitled, misnamed and unreadable entry files and exit
+        --fix              With --check, trash empty files, add missing titles and rename
+                           misnamed files (unreadable files are only reported)
+        --accessible       High-contrast colors on the terminal's own background
+                           (same as theme.preset = \"accessible\"; NO_COLOR is also honored)
+    -h, --help             Print this help and exit
+    -V, --version          Print the version and exit";
+
+#[derive(Debug)]
+pub enum Action {
+    // Launch the interactive journal
+    Run,
+    Help,
+    Version,
+    // Print entries for scripts without starting the TUI
+    List(OutputFormat),
+    // Maintenance: re-key the vault image
+    ChangePassword,
+    // Write piped input as a new entry with this title
+    NewFromStdin(String),
+    // Render one entry (newest unless a title is given) to an HTML file
+    ExportHtml { path: PathBuf, entry: Option<String> },
+    // Create entries from another app's JSON export
+    Import(PathBuf),
+    // Audit the journal directory, repairing what it safely can when `fix` is set
+    Check { fix: bool },
+}
+
+#[derive(Debug)]
+pub struct Cli {
+    pub action: Action,
+    // Overrides the configured theme for this run only
+    pub accessible: bool,
+}
+
+impl Cli {
+    // Parsed before any authentication so informational flags never unlock the vault
+    pub fn parse() -> Result<Self> {
+        Self::parse_from(std::env::args().skip(1))
+    }
+    
+    pub fn parse_from<I: IntoIterator<Item = String>>(args: I) -> Result<Self> {
+        let mut action = Action::Run;
+        let mut list = false;
+        let mut format = None;
+        let mut accessible = false;
+        let mut entry = None;
+        let mut fix = false;
+        
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => action = Action::Help,
+                "-V" | "--version" => action = Action::Version,
+                "--list" => list = true,
+                "--accessible" => accessible = true,
+                "--change-password" => action = Action::ChangePassword,
+                "--check" => action = Action::Check { fix: false },
+                "--fix" => fix = true,
+                "--new-from-stdin" => {
+                    let title = args.next().ok_or_else(|| anyhow!("--new-from-stdin needs a title\n\n{}", USAGE))?;
+                    if title.trim().is_empty() {
+                        bail!("--new-from-stdin needs a non-empty title");
+                    }
+                    action = Action::NewFromStdin(title);
+                }
+                "--import" => {
+                    let path = args.next().ok_or_else(|| anyhow!("--import needs a JSON file\n\n{}", USAGE))?;
+                    action = Action::Import(PathBuf::from(path));
+                }
+                "--export-html" => {
+                    let path = args.next().ok_or_else(|| anyhow!("--export-html needs an output path\n\n{}", USAGE))?;
+                    action = Action::ExportHtml { path: PathBuf::from(path), entry: None };
+                }
+                "--entry" => {
+                    entry = Some(args.next().ok_or_else(|| anyhow!("--entry needs a title\n\n{}", USAGE))?);
+                }
+                "--format" => {
+                    let value = args.next().ok_or_else(|| anyhow!("--format needs a value\n\n{}", USAGE))?;
+                    format = Some(parse_format(&value)?);
+                }
+                other => match other.strip_prefix("--format=") {
+                    Some(value) => format = Some(parse_format(value)?),
+                    None => bail!("Unknown argument: {}\n\n{}", other, USAGE),
+                },
+            }
+        }
+        
+        if format.is_some() && !list {
+            bail!("--format only applies to --list\n\n{}", USAGE);
+        }
+        match (&mut action, entry) {
+            (Action::ExportHtml { entry, .. }, Some(title)) => *entry = Some(title),
+            (_, Some(_)) => bail!("--entry only applies to --export-html\n\n{}", USAGE),
+            _ => {}
+        }
+        match &mut action {
+            Action::Check { fix: slot } => *slot = fix,
+            _ if fix => bail!("--fix only applies to --check\n\n{}", USAGE),
+            _ => {}
+        }
+        // Informational flags win over listing
+        if list && matches!(action, Action::Run) {
+            action = Action::List(format.unwrap_or(OutputFormat::Plain));
+        }
+        
+        Ok(Self { action, accessible })
+    }
+}
+
+fn parse_format(value: &str) -> Result<OutputFormat> {
+    OutputFormat::parse(value)
+        .ok_or_else(|| anyhow!("Unknown format '{}'; expected json or plain", value))
+}
\ No newline at end of file
@@ -0,0 +1,594 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{format, volume};
+use crate::keymap::KeySpec;
+use crate::theme::{Theme, ThemeConfig};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Every section rejects keys it doesn't know, so a misspelled setting is reported rather than
+// quietly left at its default
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub encryption: EncryptionConfig,
+    pub display: DisplayConfig,
+    pub entries: EntriesConfig,
+    pub cli: CliConfig,
+    pub animation: AnimationConfig,
+    pub retention: RetentionConfig,
+    pub vault: VaultConfig,
+    pub theme: ThemeConfig,
+    pub git: GitConfig,
+    pub backup: BackupConfig,
+    pub auth: AuthConfig,
+    // Per-notebook overrides of the [entries] editor and template, keyed by notebook name
+    pub notebooks: BTreeMap<String, NotebookConfig>,
+    // Action name to key spec(s), e.g. `delete = "x"` or `down = ["j", "ctrl-n"]`
+    pub keybindings: BTreeMap<String, KeySpec>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EncryptionConfig {
+    // Encrypt each entry file on top of the encrypted volume
+    pub enabled: bool,
+    // Prefer JOURNAL_TUI_PASSPHRASE or the keychain over storing it here
+    pub passphrase: Option<String>,
+}
+
+// Bounds of `display.list_width`, leaving both panes usable
+pub const MIN_LIST_WIDTH: u16 = 20;
+pub const MAX_LIST_WIDTH: u16 = 80;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DisplayConfig {
+    // Show "3h ago" style timestamps in the list instead of absolute dates
+    pub relative_timestamps: bool,
+    // One row per entry in the list (toggle with m)
+    pub compact_list: bool,
+    // Width of the entry list as a percentage of the screen, MIN_LIST_WIDTH to MAX_LIST_WIDTH
+    pub list_width: u16,
+    pub show_preview: bool,
+    // chrono strftime format for timestamps in the entry list
+    pub display_date_format: String,
+    // Ask before `q` locks the vault and exits; force quit (Q / Ctrl-Q) never asks
+    pub confirm_quit: bool,
+    // Seconds before an unanswered delete or purge confirmation cancels itself; off unless set
+    pub confirm_timeout_secs: Option<u64>,
+    // Below this many columns and rows the UI is replaced by a "too small" notice
+    pub min_width: u16,
+    pub min_height: u16,
+    // Most lines of an entry the preview shows; fewer when the pane is shorter
+    pub preview_lines: usize,
+    // What leads each list row and the preview header: "number" ([3]), "date" or "none"
+    pub list_prefix: ListPrefix,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ListPrefix {
+    // Position in the list, which shifts as entries come and go
+    #[default]
+    Number,
+    // The entry's creation date
+    Date,
+    None,
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        Self {
+            relative_timestamps: false,
+            compact_list: false,
+            list_width: 40,
+            show_preview: true,
+            display_date_format: "%Y-%m-%d %H:%M".to_string(),
+            confirm_quit: false,
+            confirm_timeout_secs: None,
+            min_width: 40,
+            min_height: 15,
+            preview_lines: 20,
+            list_prefix: ListPrefix::Number,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EntriesConfig {
+    // chrono strftime format for the timestamp that prefixes new entry filenames
+    pub filename_date_format: String,
+    // Editor command, arguments allowed (e.g. "code --wait"); falls back to $EDITOR, then vim.
+    // `{file}` and `{line}` place the entry's path and starting line, e.g. "hx {file}:{line}";
+    // without `{file}` the path goes last.
+    pub editor: Option<String>,
+    // Add the wait flag GUI editors (code, subl, zed, ...) need to block until the file is
+    // closed; without it they return at once and the edit isn't seen
+    pub editor_wait: bool,
+    // File whose contents start the body of every new entry; a `{cursor}` in it marks where editing starts
+    pub template: Option<PathBuf>,
+    // Which [notebooks.<name>] overrides apply
+    pub notebook: Option<String>,
+    // Leave the `# title` heading (and frontmatter) out of text copied with Y
+    pub copy_without_title: bool,
+    // After editing, rename the file to match a changed title, keeping its timestamp prefix
+    pub rename_on_title_change: bool,
+    // File extensions loaded as entries, without the dot; new entries use the first
+    pub extensions: Vec<String>,
+    // Write in the built-in full-screen editor instead of launching `editor`
+    pub builtin_editor: bool,
+    // Keep new filenames ASCII ("Café" -> "Cafe"); the `# ` heading keeps the title as typed
+    pub ascii_filenames: bool,
+    // Date entries by the timestamp in their filename, which survives copying and syncing;
+    // false uses the file's creation time. Files without a timestamp always use the latter.
+    pub date_from_filename: bool,
+    // Notes kept elsewhere, listed alongside the journal's own entries, e.g. ["~/notes/todo.md"].
+    // They're edited in place and never renamed, archived or moved to the trash.
+    pub external: Vec<PathBuf>,
+}
+
+impl Default for EntriesConfig {
+    fn default() -> Self {
+        Self {
+            filename_date_format: "%Y%m%d_%H%M%S".to_string(),
+            editor: None,
+            editor_wait: true,
+            template: None,
+            notebook: None,
+            copy_without_title: false,
+            rename_on_title_change: false,
+            extensions: vec!["md".to_string()],
+            builtin_editor: false,
+            ascii_filenames: false,
+            date_from_filename: true,
+            external: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotebookConfig {
+    pub editor: Option<String>,
+    pub template: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CliConfig {
+    // Require Touch ID / password before `--list` prints entry titles
+    pub list_requires_auth: bool,
+}
+
+impl Default for CliConfig {
+    fn default() -> Self {
+        Self {
+            list_requires_auth: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AnimationConfig {
+    // Fraction of terminal columns with falling rain, 0.0-1.0; lower is lighter on slow terminals
+    pub rain_density: f32,
+    // Multiplier on how fast the rain falls
+    pub rain_speed: f32,
+    // Unscramble entry text when it's opened in the viewer
+    pub viewer_reveal: bool,
+    // The "encrypting" screen shown on exit, its length and what it types out
+    pub exit_animation: bool,
+    pub exit_duration_ms: u64,
+    pub exit_message: String,
+    // Rain over the list after this many seconds without input; off unless set
+    pub screensaver_after_secs: Option<u64>,
+    // List the newest few entry titles as they're "decrypted" after unlocking; turn off if
+    // the unlock screen can be seen by others. Not shown with [encryption] enabled.
+    pub decode_titles: bool,
+}
+
+impl Default for AnimationConfig {
+    fn default() -> Self {
+        Self {
+            rain_density: 1.0,
+            rain_speed: 1.0,
+            viewer_reveal: true,
+            exit_animation: true,
+            exit_duration_ms: 2000,
+            exit_message: "ENCRYPTING VAULT - SECURING MEMORIES".to_string(),
+            screensaver_after_secs: None,
+            decode_titles: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetentionConfig {
+    // Move entries older than this many months to the trash at startup. Off unless set.
+    pub prune_after_months: Option<u32>,
+    // Permanently delete trashed entries this many days after they were trashed; 0 keeps them
+    // until they're purged by hand
+    pub purge_trash_after_days: u64,
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            prune_after_months: None,
+            purge_trash_after_days: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthConfig {
+    // Shell command run instead of Touch ID / Windows Hello; exit status 0 unlocks,
+    // e.g. "pass otp journal" or a YubiKey script
+    pub command: Option<String>,
+    // Give up on a prompt that hasn't answered after this long
+    pub timeout_secs: u64,
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self {
+            command: None,
+            timeout_secs: 60,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GitConfig {
+    // Commit each entry after it's created or edited, when the journal is in a git work tree
+    pub autocommit: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BackupConfig {
+    // Copy each entry here after it's created or edited, e.g. a cloud-synced folder.
+    // Files are copied as stored, so they're only encrypted with [encryption] enabled.
+    pub dir: Option<PathBuf>,
+    // Without [encryption], entries are plain text once copied out of the vault. They're
+    // refused unless this is set, to make that a deliberate choice.
+    pub allow_plaintext: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct VaultConfig {
+    // Disk image holding the journal; defaults to ~/.journal/vault.dmg. Without disk images
+    // (anywhere but macOS) the vault is the folder of the same name, ~/.journal/vault.
+    pub dmg_path: Option<PathBuf>,
+    // Also names the mount point under /Volumes
+    pub volume_name: String,
+    pub keychain_account: String,
+    pub keychain_service: String,
+    // false: ask for the vault password on every launch and keep it in memory only, never in
+    // the keychain. A new vault then takes a chosen password that must be recorded elsewhere.
+    // For an existing vault, set a known password with --change-password before turning this off.
+    pub remember_password: bool,
+}
+
+impl Default for VaultConfig {
+    fn default() -> Self {
+        Self {
+            dmg_path: None,
+            volume_name: volume::DEFAULT_VOLUME_NAME.to_string(),
+            keychain_account: volume::DEFAULT_KEYCHAIN_ACCOUNT.to_string(),
+            keychain_service: volume::DEFAULT_KEYCHAIN_SERVICE.to_string(),
+            remember_password: true,
+        }
+    }
+}
+
+// JOURNAL_TUI_HOME overrides the user's home directory for all journal paths,
+// which also makes the app usable where $HOME isn't set (cron, containers, CI)
+pub fn home_dir() -> Result<PathBuf> {
+    if let Some(home) = std::env::var_os("JOURNAL_TUI_HOME")
+        && !home.is_empty()
+    {
+        return Ok(PathBuf::from(home));
+    }
+    
+    match dirs::home_dir() {
+        Some(home) => Ok(home),
+        None => bail!("Could not determine your home directory; set $HOME or JOURNAL_TUI_HOME"),
+    }
+}
+
+// `~/Dropbox/journal` as written in the config
+pub fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), home_dir()) {
+        (Ok(rest), Ok(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+fn env_path() -> Option<PathBuf> {
+    std::env::var_os("JOURNAL_TUI_CONFIG")
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+}
+
+impl Config {
+    // JOURNAL_TUI_CONFIG names a specific file (separate profiles, hermetic tests),
+    // otherwise ~/.config/journal-tui/config.toml
+    pub fn path() -> Result<PathBuf> {
+        match env_path() {
+            Some(path) => Ok(path),
+            None => Ok(home_dir()?.join(".config").join("journal-tui").join("config.toml")),
+        }
+    }
+    
+    pub fn load() -> Result<Self> {
+        let path = Self::path()?;
+        if !path.exists() {
+            // A mistyped override shouldn't quietly fall back to defaults
+            if env_path().is_some() {
+                bail!("JOURNAL_TUI_CONFIG points to {}, which doesn't exist", path.display());
+            }
+            return Ok(Self::default());
+        }
+        
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read config {}", path.display()))?;
+        let config: Self = toml::from_str(&content)
+            .with_context(|| format!("Invalid config {}", path.display()))?;
+        config.validate()
+            .with_context(|| format!("Invalid config {}", path.display()))?;
+        Ok(config)
+    }
+    
+    fn validate(&self) -> Result<()> {
+        let filename_format = &self.entries.filename_date_format;
+        format::validate_date_format(filename_format)
+            .context("entries.filename_date_format")?;
+        if filename_format.contains(std::path::MAIN_SEPARATOR) || filename_format.contains('/') {
+            bail!("entries.filename_date_format must not contain path separators");
+        }
+        
+        if self.entries.extensions.is_empty() {
+            bail!("entries.extensions must list at least one extension, e.g. [\"md\"]");
+        }
+        for ext in &self.entries.extensions {
+            if ext.trim().is_empty() || ext.starts_with('.') || ext.contains(['/', '\\']) {
+                bail!("entries.extensions: '{}' must be a bare extension like \"txt\", without the dot", ext);
+            }
+        }
+        
+        if self.entries.external.iter().any(|p| p.as_os_str().is_empty()) {
+            bail!("entries.external must not contain empty paths");
+        }
+        
+        format::validate_date_format(&self.display.display_date_format)
+            .context("display.display_date_format")?;
+        if !(MIN_LIST_WIDTH..=MAX_LIST_WIDTH).contains(&self.display.list_width) {
+            bail!("display.list_width must be between {} and {} (percent of the screen)", MIN_LIST_WIDTH, MAX_LIST_WIDTH);
+        }
+        if self.display.preview_lines == 0 {
+            bail!("display.preview_lines must be at least 1; set show_preview = false to hide the preview");
+        }
+        
+        let editors = std::iter::once(("entries.editor".to_string(), &self.entries.editor))
+            .chain(self.notebooks.iter().map(|(name, n)| (format!("notebooks.{}.editor", name), &n.editor)));
+        for (key, editor) in editors {
+            if editor.as_ref().is_some_and(|e| e.trim().is_empty()) {
+                bail!("{} must not be empty", key);
+            }
+        }
+        
+        Theme::from_config(&self.theme)?;
+        
+        if self.auth.command.as_ref().is_some_and(|c| c.trim().is_empty()) {
+            bail!("auth.command must not be empty; remove it to use the platform default");
+        }
+        if self.auth.timeout_secs == 0 {
+            bail!("auth.timeout_secs must be at least 1");
+        }
+        
+        if self.backup.dir.as_ref().is_some_and(|d| d.as_os_str().is_empty()) {
+            bail!("backup.dir must not be empty; remove it to turn backups off");
+        }
+        
+        let vault = &self.vault;
+        if vault.volume_name.trim().is_empty() || vault.volume_name.contains('/') {
+            bail!("vault.volume_name must be a non-empty name without '/'");
+        }
+        if vault.keychain_account.trim().is_empty() || vault.keychain_service.trim().is_empty() {
+            bail!("vault.keychain_account and vault.keychain_service must not be empty");
+        }
+        
+        if self.retention.prune_after_months == Some(0) {
+            bail!("retention.prune_after_months must be at least 1; remove it to disable pruning");
+        }
+        
+        if self.display.confirm_timeout_secs == Some(0) {
+            bail!("display.confirm_timeout_secs must be at least 1; remove it to wait for an answer");
+        }
+        
+        let animation = &self.animation;
+        if !(0.0..=1.0).contains(&animation.rain_density) {
+            bail!("animation.rain_density must be between 0.0 and 1.0");
+        }
+        if !(animation.rain_speed > 0.0 && animation.rain_speed.is_finite()) {
+            bail!("animation.rain_speed must be a positive number");
+        }
+        if animation.screensaver_after_secs == Some(0) {
+            bail!("animation.screensaver_after_secs must be at least 1; remove it to turn the screensaver off");
+        }
+        if animation.exit_message.trim().is_empty() {
+            bail!("animation.exit_message must not be empty; set exit_animation = false to skip it");
+        }
+        Ok(())
+    }
+    
+    fn active_notebook(&self) -> Option<&NotebookConfig> {
+        self.entries.notebook.as_ref().and_then(|name| self.notebooks.get(name))
+    }
+    
+    // The active notebook's editor, then [entries], then $EDITOR
+    pub fn editor(&self) -> String {
+        self.active_notebook()
+            .and_then(|n| n.editor.clone())
+            .or_else(|| self.entries.editor.clone())
+            .or_else(|| std::env::var("EDITOR").ok().filter(|e| !e.trim().is_empty()))
+            .unwrap_or_else(|| "vim".to_string())
+    }
+    
+    pub fn template(&self) -> Option<&Path> {
+        self.active_notebook()
+            .and_then(|n| n.template.as_deref())
+            .or(self.entries.template.as_deref())
+    }
+    
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        
+        fs::write(&path, toml::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write config {}", path.display()))
+    }
+    
+    // Write back only the list width and preview toggle changed from inside the app, so
+    // the rest of a hand-edited config keeps its comments, layout and unset defaults
+    pub fn save_layout(&self) -> Result<()> {
+        let path = Self::path()?;
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err).with_context(|| format!("Failed to read config {}", path.display())),
+        };
+        let updated = self.layout_into(&content)
+            .with_context(|| format!("Invalid config {}", path.display()))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        
+        fs::write(&path, updated)
+            .with_context(|| format!("Failed to write config {}", path.display()))
+    }
+    
+    fn layout_into(&self, content: &str) -> Result<String> {
+        let mut document: toml_edit::DocumentMut = content.parse()?;
+        let display = document
+            .entry("display")
+            .or_insert(toml_edit::table())
+            .as_table_like_mut()
+            .context("`display` must be a table")?;
+        set_value(display, "list_width", (self.display.list_width as i64).into());
+        set_value(display, "show_preview", self.display.show_preview.into());
+        Ok(document.to_string())
+    }
+}
+
+// Replace a value in place so comments around the key and the value survive
+fn set_value(table: &mut dyn toml_edit::TableLike, key: &str, value: toml_edit::Value) {
+    match table.get_mut(key).and_then(|item| item.as_value_mut()) {
+        Some(existing) => {
+            let decor = existing.decor().clone();
+            *existing = value;
+            *existing.decor_mut() = decor;
+        }
+        None => {
+            table.insert(key, toml_edit::Item::Value(value));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn layout(list_width: u16, show_preview: bool) -> Config {
+        let mut config = Config::default();
+        config.display.list_width = list_width;
+        config.display.show_preview = show_preview;
+        config
+    }
+    
+    #[test]
+    fn saving_the_layout_keeps_the_rest_of_the_file() {
+        let content = "# my journal\n[entries]\nextensions = [\"md\"] # just markdown\n\n[display]\n# narrow list\nlist_width = 30 # percent\ncompact_list = true\n";
+        let updated = layout(55, false).layout_into(content).unwrap();
+        assert_eq!(
+            updated,
+            "# my journal\n[entries]\nextensions = [\"md\"] # just markdown\n\n[display]\n# narrow list\nlist_width = 55 # percent\ncompact_list = true\nshow_preview = false\n"
+        );
+    }
+    
+    #[test]
+    fn saving_the_layout_doesnt_write_out_defaults() {
+        let updated = layout(40, true).layout_into("").unwrap();
+        assert_eq!(updated, "[display]\nlist_width = 40\nshow_preview = true\n");
+        let config: Config = toml::from_str(&updated).unwrap();
+        assert_eq!(config.display.list_width, 40);
+    }
+    
+    #[test]
+    fn saving_the_layout_updates_an_inline_display_table() {
+        let updated = layout(60, true).layout_into("display = { list_width = 20 }\n").unwrap();
+        let config: Config = toml::from_str(&updated).unwrap();
+        assert_eq!((config.display.list_width, config.display.show_preview), (60, true));
+        assert!(updated.starts_with("display = {"));
+    }
+    
+    #[test]
+    fn saving_the_layout_refuses_a_broken_config() {
+        assert!(layout(40, true).layout_into("display = 3\n").is_err());
+        assert!(layout(40, true).layout_into("[display\n").is_err());
+    }
+    
+    #[test]
+    fn list_width_must_leave_room_for_both_panes() {
+        for width in [MIN_LIST_WIDTH, 40, MAX_LIST_WIDTH] {
+            assert!(layout(width, true).validate().is_ok(), "{}", width);
+        }
+        for width in [0, MIN_LIST_WIDTH - 1, MAX_LIST_WIDTH + 1, 100] {
+            let message = layout(width, true).validate().err().unwrap().to_string();
+            assert!(message.contains("display.list_width"), "{}", message);
+        }
+    }
+    
+    // The only test that touches the environment, so the variable can't leak into another
+    #[test]
+    fn the_config_override_is_loaded_and_checked() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("profile.toml");
+        let load = |content: &str| {
+            fs::write(&path, content).unwrap();
+            Config::load().map_err(|err| format!("{:#}", err))
+        };
+        // SAFETY: nothing else in the tests reads or writes JOURNAL_TUI_CONFIG
+        unsafe { std::env::set_var("JOURNAL_TUI_CONFIG", &path) };
+        
+        assert_eq!(Config::path().unwrap(), path);
+        assert_eq!(load("[display]\nlist_width = 30\n").unwrap().display.list_width, 30);
+        
+        let message = load("[entries]\nfilename_date_format = \"%Y-%Q\"\n").err().unwrap();
+        assert!(message.contains("entries.filename_date_format"), "{}", message);
+        assert!(message.contains(&path.display().to_string()), "{}", message);
+        
+        let message = load("[display]\nlist_widht = 30\n").err().unwrap();
+        assert!(message.contains("unknown field `list_widht`"), "{}", message);
+        let message = load("[dispaly]\nlist_width = 30\n").err().unwrap();
+        assert!(message.contains("unknown field `dispaly`"), "{}", message);
+        
+        fs::remove_file(&path).unwrap();
+        let message = Config::load().err().map(|err| err.to_string()).unwrap();
+        assert!(message.contains("JOURNAL_TUI_CONFIG"), "{}", message);
+        
+        unsafe { std::env::remove_var("JOURNAL_TUI_CONFIG") };
+    }
+}
\ No newline at end of file
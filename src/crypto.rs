@@ -0,0 +1,292 @@
+use anyhow::{anyhow, bail, Context, Result};
+use argon2::Argon2;
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use rand::RngCore;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::secrets;
+
+// Every encrypted entry starts with this marker so plaintext entries can coexist
+const MAGIC: &[u8] = b"JTUIENC1";
+const NONCE_LEN: usize = 12;
+const SALT_LEN: usize = 16;
+const SALT_FILE: &str = ".entry-salt";
+// A known plaintext encrypted with the journal's key, so a wrong passphrase is caught on open
+// rather than by every entry failing to decrypt, or by new entries being written under a second key
+const CHECK_FILE: &str = ".entry-check";
+const CHECK_PLAINTEXT: &[u8] = b"journal-tui entry key check";
+// Where the passphrase is looked up in the keychain or Credential Manager
+pub const PASSPHRASE_SERVICE: &str = "journal-tui";
+pub const PASSPHRASE_ACCOUNT: &str = "entry-encryption";
+
+// This process's own directory for decrypted copies, created on first use
+static PRIVATE_TEMP_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+pub struct EntryKey {
+    cipher: ChaCha20Poly1305,
+}
+
+impl EntryKey {
+    pub fn derive(passphrase: &str, salt: &[u8]) -> Result<Self> {
+        let mut key = Key::default();
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+            .map_err(|e| anyhow!("Failed to derive entry key: {}", e))?;
+        
+        Ok(Self {
+            cipher: ChaCha20Poly1305::new(&key),
+        })
+    }
+    
+    // Derive the key using the journal-wide salt, creating it on first use, and check it
+    // against the journal's stored check
+    pub fn for_journal(passphrase: &str, journal_dir: &Path) -> Result<Self> {
+        let salt_path = journal_dir.join(SALT_FILE);
+        let salt = if salt_path.exists() {
+            let salt = fs::read(&salt_path).with_context(|| format!("Failed to read {}", salt_path.display()))?;
+            if salt.len() != SALT_LEN {
+                bail!("{} is corrupt ({} bytes, expected {})", salt_path.display(), salt.len(), SALT_LEN);
+            }
+            salt
+        } else {
+            let mut salt = vec![0u8; SALT_LEN];
+            rand::thread_rng().fill_bytes(&mut salt);
+            fs::write(&salt_path, &salt)?;
+            salt
+        };
+        
+        let key = Self::derive(passphrase, &salt)?;
+        key.verify(journal_dir)?;
+        Ok(key)
+    }
+    
+    fn verify(&self, journal_dir: &Path) -> Result<()> {
+        let check_path = journal_dir.join(CHECK_FILE);
+        let wrong = || anyhow!("Wrong entry passphrase for {}", journal_dir.display());
+        if check_path.exists() {
+            let data = fs::read(&check_path).with_context(|| format!("Failed to read {}", check_path.display()))?;
+            return match decrypt(self, &data) {
+                Ok(plaintext) if plaintext == CHECK_PLAINTEXT => Ok(()),
+                _ => Err(wrong()),
+            };
+        }
+        
+        // Journals from before the check was kept are checked against an entry once instead
+        let existing = fs::read_dir(journal_dir)?
+            .filter_map(|entry| fs::read(entry.ok()?.path()).ok())
+            .find(|data| is_encrypted(data));
+        if let Some(data) = existing
+            && decrypt(self, &data).is_err()
+        {
+            return Err(wrong());
+        }
+        
+        let check = encrypt(self, CHECK_PLAINTEXT)?;
+        fs::write(&check_path, check).with_context(|| format!("Failed to write {}", check_path.display()))
+    }
+}
+
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+pub fn encrypt(key: &EntryKey, plaintext: &[u8]) -> Result<Vec<u8>> {
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = key
+        .cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| anyhow!("Failed to encrypt entry"))?;
+    
+    let mut data = Vec::with_capacity(MAGIC.len() + NONCE_LEN + ciphertext.len());
+    data.extend_from_slice(MAGIC);
+    data.extend_from_slice(&nonce);
+    data.extend_from_slice(&ciphertext);
+    Ok(data)
+}
+
+pub fn decrypt(key: &EntryKey, data: &[u8]) -> Result<Vec<u8>> {
+    if !is_encrypted(data) || data.len() < MAGIC.len() + NONCE_LEN {
+        bail!("Entry is not encrypted");
+    }
+    
+    let (nonce, ciphertext) = data[MAGIC.len()..].split_at(NONCE_LEN);
+    key.cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| anyhow!("Failed to decrypt entry (wrong passphrase?)"))
+}
+
+// Written to a temp file beside the entry and renamed over it, so a crash or full disk
+// mid-write leaves the old ciphertext rather than a truncated one that can't be decrypted
+pub fn encrypt_file(path: &Path, key: &EntryKey, plaintext: &str) -> Result<()> {
+    let data = encrypt(key, plaintext.as_bytes())?;
+    let dir = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let write = || -> std::io::Result<()> {
+        let mut temp = tempfile::NamedTempFile::new_in(dir)?;
+        temp.write_all(&data)?;
+        if let Ok(metadata) = fs::metadata(path) {
+            temp.as_file().set_permissions(metadata.permissions())?;
+        }
+        temp.as_file().sync_all()?;
+        temp.persist(path)?;
+        Ok(())
+    };
+    write().with_context(|| format!("Failed to write {}", path.display()))
+}
+
+pub fn decrypt_file(path: &Path, key: &EntryKey) -> Result<String> {
+    let data = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let plaintext = decrypt(key, &data)?;
+    String::from_utf8(plaintext).context("Decrypted entry is not valid UTF-8")
+}
+
+// Look up the entry passphrase: environment first, then config, then the keychain or
+// Credential Manager
+pub fn resolve_passphrase(configured: Option<&str>) -> Option<String> {
+    if let Ok(passphrase) = std::env::var("JOURNAL_TUI_PASSPHRASE")
+        && !passphrase.is_empty()
+    {
+        return Some(passphrase);
+    }
+    
+    if let Some(passphrase) = configured {
+        return Some(passphrase.to_string());
+    }
+    
+    secrets::get(PASSPHRASE_SERVICE, PASSPHRASE_ACCOUNT)
+}
+
+// A fresh, randomly named directory only this user can enter (0700 on Unix), so other
+// local users can't read the plaintext or plant a file or symlink for it to be written to
+fn private_temp_dir() -> Result<PathBuf> {
+    let mut dir = PRIVATE_TEMP_DIR.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(dir) = dir.as_ref() {
+        return Ok(dir.clone());
+    }
+    let created = tempfile::Builder::new()
+        .prefix("journal-tui-")
+        .tempdir()
+        .context("Failed to create a private temp directory")?
+        .keep();
+    *dir = Some(created.clone());
+    Ok(created)
+}
+
+// Write decrypted content to a private temp file for the external editor
+pub fn write_private_temp(file_name: &str, content: &str) -> Result<PathBuf> {
+    let path = private_temp_dir()?.join(file_name);
+    // Only we can write in the directory, so anything here is a copy we left earlier
+    let _ = fs::remove_file(&path);
+    
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    
+    options.open(&path)?.write_all(content.as_bytes())?;
+    Ok(path)
+}
+
+// Remove any decrypted copies left behind, e.g. by an editor that was still open. Only
+// this process's directory goes; nothing shared in the temp dir is touched.
+pub fn remove_private_temps() {
+    let dir = PRIVATE_TEMP_DIR.lock().unwrap_or_else(|e| e.into_inner()).take();
+    if let Some(dir) = dir {
+        let _ = fs::remove_dir_all(dir);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn key(passphrase: &str) -> EntryKey {
+        EntryKey::derive(passphrase, &[7u8; SALT_LEN]).unwrap()
+    }
+    
+    #[test]
+    fn entries_round_trip() {
+        let key = key("secret");
+        let data = encrypt(&key, "# Title\n\nbody ✓".as_bytes()).unwrap();
+        assert!(is_encrypted(&data));
+        assert_eq!(decrypt(&key, &data).unwrap(), "# Title\n\nbody ✓".as_bytes());
+    }
+    
+    #[test]
+    fn a_wrong_passphrase_fails_to_decrypt() {
+        let data = encrypt(&key("secret"), b"text").unwrap();
+        assert!(decrypt(&key("guess"), &data).is_err());
+    }
+    
+    #[test]
+    fn tampered_or_truncated_data_is_an_error() {
+        let key = key("secret");
+        let data = encrypt(&key, b"text").unwrap();
+        
+        let mut tampered = data.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(decrypt(&key, &tampered).is_err());
+        
+        for len in [0, MAGIC.len(), MAGIC.len() + NONCE_LEN - 1, MAGIC.len() + NONCE_LEN, data.len() - 1] {
+            assert!(decrypt(&key, &data[..len]).is_err(), "{} bytes", len);
+        }
+    }
+    
+    #[test]
+    fn plaintext_is_not_encrypted() {
+        assert!(!is_encrypted(b"# Title\n\nbody"));
+        assert!(!is_encrypted(b""));
+        assert!(!is_encrypted(&MAGIC[..MAGIC.len() - 1]));
+    }
+    
+    #[test]
+    fn the_journal_key_rejects_a_different_passphrase() {
+        let dir = tempfile::tempdir().unwrap();
+        EntryKey::for_journal("secret", dir.path()).unwrap();
+        
+        assert!(EntryKey::for_journal("secret", dir.path()).is_ok());
+        let message = EntryKey::for_journal("guess", dir.path()).err().unwrap().to_string();
+        assert!(message.contains("Wrong entry passphrase"), "{}", message);
+    }
+    
+    #[test]
+    fn a_journal_without_a_check_is_checked_against_its_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let salt = [3u8; SALT_LEN];
+        fs::write(dir.path().join(SALT_FILE), salt).unwrap();
+        encrypt_file(&dir.path().join("a.md"), &EntryKey::derive("secret", &salt).unwrap(), "text").unwrap();
+        
+        assert!(EntryKey::for_journal("guess", dir.path()).is_err());
+        assert!(!dir.path().join(CHECK_FILE).exists());
+        assert!(EntryKey::for_journal("secret", dir.path()).is_ok());
+        assert!(dir.path().join(CHECK_FILE).exists());
+    }
+    
+    #[test]
+    fn a_salt_of_the_wrong_length_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(SALT_FILE), [1u8; 4]).unwrap();
+        let message = EntryKey::for_journal("secret", dir.path()).err().unwrap().to_string();
+        assert!(message.contains("corrupt"), "{}", message);
+    }
+    
+    #[test]
+    fn encrypting_a_file_replaces_it_and_leaves_nothing_beside_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.md");
+        let key = key("secret");
+        fs::write(&path, "old").unwrap();
+        
+        encrypt_file(&path, &key, "new").unwrap();
+        assert_eq!(decrypt_file(&path, &key).unwrap(), "new");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
\ No newline at end of file
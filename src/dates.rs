@@ -0,0 +1,220 @@
+use chrono::{Datelike, Duration, Months, NaiveDate};
+
+// Date expressions for search, written after `@`: "today", "yesterday", "this week",
+// "last month", "last 10 days", "january", "jan 2024", "2024", "2024-01", "2024-01-15".
+// Ranges are inclusive and weeks start on Monday.
+
+// Split a search query into its `@date` term and the remaining text.
+// "@january meeting" -> (January's range, "meeting"). Unparseable `@` terms stay as text.
+pub fn parse_query(query: &str, today: NaiveDate) -> (Option<(NaiveDate, NaiveDate)>, String) {
+    let words: Vec<&str> = query.split_whitespace().collect();
+    let mut range = None;
+    let mut text = Vec::new();
+    
+    let mut i = 0;
+    while i < words.len() {
+        if range.is_none()
+            && let Some(first) = words[i].strip_prefix('@')
+        {
+            // Prefer the longest expression so "@last week" isn't read as "@last" + "week"
+            let found = (1..=3.min(words.len() - i)).rev().find_map(|len| {
+                let expr = std::iter::once(first)
+                    .chain(words[i + 1..i + len].iter().copied())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                parse_range(&expr, today).map(|r| (r, len))
+            });
+            if let Some((r, len)) = found {
+                range = Some(r);
+                i += len;
+                continue;
+            }
+        }
+        text.push(words[i]);
+        i += 1;
+    }
+    
+    (range, text.join(" "))
+}
+
+pub fn parse_range(expr: &str, today: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+    let expr = expr.trim().to_lowercase();
+    let words: Vec<&str> = expr.split_whitespace().collect();
+    
+    match words.as_slice() {
+        ["today"] => Some((today, today)),
+        ["yesterday"] => {
+            let day = today.pred_opt()?;
+            Some((day, day))
+        }
+        [which @ ("this" | "last"), unit] => {
+            let back = if *which == "last" { 1 } else { 0 };
+            match *unit {
+                "week" => {
+                    let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+                    let start = monday - Duration::weeks(back);
+                    Some((start, start + Duration::days(6)))
+                }
+                "month" => {
+                    let first = today.with_day(1)?.checked_sub_months(Months::new(back as u32))?;
+                    Some((first, month_end(first)?))
+                }
+                "year" => year_range(today.year() - back as i32),
+                _ => None,
+            }
+        }
+        ["last", count, unit] => {
+            let count: u32 = count.parse().ok().filter(|&n| n > 0)?;
+            let start = match unit.trim_end_matches('s') {
+                "day" => today - Duration::days(count as i64 - 1),
+                "week" => today - Duration::weeks(count as i64) + Duration::days(1),
+                "month" => today.checked_sub_months(Months::new(count))? + Duration::days(1),
+                _ => return None,
+            };
+            Some((start, today))
+        }
+        [month] if month_number(month).is_some() => {
+            let month = month_number(month)?;
+            // The most recent such month, so "december" in March means last December
+            let year = if month <= today.month() { today.year() } else { today.year() - 1 };
+            month_range(year, month)
+        }
+        [month, year] if month_number(month).is_some() => month_range(year.parse().ok()?, month_number(month)?),
+        [absolute] => absolute_range(absolute),
+        _ => None,
+    }
+}
+
+// "2024", "2024-01" or "2024-01-15"
+fn absolute_range(text: &str) -> Option<(NaiveDate, NaiveDate)> {
+    let parts: Vec<&str> = text.split('-').collect();
+    let numbers: Vec<u32> = parts.iter().map(|p| p.parse().ok()).collect::<Option<_>>()?;
+    if parts[0].len() != 4 {
+        return None;
+    }
+    match numbers.as_slice() {
+        [year] => year_range(*year as i32),
+        [year, month] => month_range(*year as i32, *month),
+        [year, month, day] => {
+            let date = NaiveDate::from_ymd_opt(*year as i32, *month, *day)?;
+            Some((date, date))
+        }
+        _ => None,
+    }
+}
+
+fn year_range(year: i32) -> Option<(NaiveDate, NaiveDate)> {
+    Some((NaiveDate::from_ymd_opt(year, 1, 1)?, NaiveDate::from_ymd_opt(year, 12, 31)?))
+}
+
+fn month_range(year: i32, month: u32) -> Option<(NaiveDate, NaiveDate)> {
+    let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+    Some((first, month_end(first)?))
+}
+
+fn month_end(first: NaiveDate) -> Option<NaiveDate> {
+    first.checked_add_months(Months::new(1))?.pred_opt()
+}
+
+// Full names or three-letter abbreviations
+fn month_number(name: &str) -> Option<u32> {
+    const MONTHS: [&str; 12] = [
+        "january", "february", "march", "april", "may", "june",
+        "july", "august", "september", "october", "november", "december",
+    ];
+    if name.len() < 3 {
+        return None;
+    }
+    MONTHS.iter()
+        .position(|month| *month == name || (name.len() == 3 && month.starts_with(name)))
+        .map(|i| i as u32 + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+    
+    // A Wednesday
+    fn today() -> NaiveDate {
+        date(2024, 3, 13)
+    }
+    
+    #[test]
+    fn relative_days_and_weeks() {
+        assert_eq!(parse_range("today", today()), Some((today(), today())));
+        assert_eq!(parse_range("Yesterday", today()), Some((date(2024, 3, 12), date(2024, 3, 12))));
+        assert_eq!(parse_range("this week", today()), Some((date(2024, 3, 11), date(2024, 3, 17))));
+        assert_eq!(parse_range("last week", today()), Some((date(2024, 3, 4), date(2024, 3, 10))));
+    }
+    
+    #[test]
+    fn relative_months_and_years_cross_boundaries() {
+        assert_eq!(parse_range("this month", today()), Some((date(2024, 3, 1), date(2024, 3, 31))));
+        // February of a leap year
+        assert_eq!(parse_range("last month", today()), Some((date(2024, 2, 1), date(2024, 2, 29))));
+        assert_eq!(parse_range("last month", date(2024, 1, 5)), Some((date(2023, 12, 1), date(2023, 12, 31))));
+        assert_eq!(parse_range("last year", today()), Some((date(2023, 1, 1), date(2023, 12, 31))));
+    }
+    
+    #[test]
+    fn last_n_units_end_today() {
+        assert_eq!(parse_range("last 10 days", today()), Some((date(2024, 3, 4), today())));
+        assert_eq!(parse_range("last 1 day", today()), Some((today(), today())));
+        assert_eq!(parse_range("last 2 weeks", today()), Some((date(2024, 2, 29), today())));
+        assert_eq!(parse_range("last 3 months", today()), Some((date(2023, 12, 14), today())));
+        assert_eq!(parse_range("last 0 days", today()), None);
+        assert_eq!(parse_range("last ten days", today()), None);
+        assert_eq!(parse_range("last 3 fortnights", today()), None);
+    }
+    
+    #[test]
+    fn month_names_pick_the_most_recent_one() {
+        assert_eq!(parse_range("january", today()), Some((date(2024, 1, 1), date(2024, 1, 31))));
+        assert_eq!(parse_range("Mar", today()), Some((date(2024, 3, 1), date(2024, 3, 31))));
+        assert_eq!(parse_range("december", today()), Some((date(2023, 12, 1), date(2023, 12, 31))));
+        assert_eq!(parse_range("feb 2023", today()), Some((date(2023, 2, 1), date(2023, 2, 28))));
+        assert_eq!(parse_range("ja", today()), None);
+        assert_eq!(parse_range("janu", today()), None);
+    }
+    
+    #[test]
+    fn absolute_dates() {
+        assert_eq!(parse_range("2023", today()), Some((date(2023, 1, 1), date(2023, 12, 31))));
+        assert_eq!(parse_range("2024-02", today()), Some((date(2024, 2, 1), date(2024, 2, 29))));
+        assert_eq!(parse_range("2024-01-15", today()), Some((date(2024, 1, 15), date(2024, 1, 15))));
+        assert_eq!(parse_range("2024-13", today()), None);
+        assert_eq!(parse_range("2023-02-29", today()), None);
+        assert_eq!(parse_range("24-01", today()), None);
+        assert_eq!(parse_range("meeting", today()), None);
+    }
+    
+    #[test]
+    fn query_splits_the_date_term_from_the_text() {
+        assert_eq!(
+            parse_query("@january meeting", today()),
+            (Some((date(2024, 1, 1), date(2024, 1, 31))), "meeting".to_string())
+        );
+        assert_eq!(
+            parse_query("notes @last week", today()),
+            (Some((date(2024, 3, 4), date(2024, 3, 10))), "notes".to_string())
+        );
+        assert_eq!(
+            parse_query("@last 10 days standup", today()),
+            (Some((date(2024, 3, 4), today())), "standup".to_string())
+        );
+    }
+    
+    #[test]
+    fn query_keeps_unparseable_and_extra_date_terms_as_text() {
+        assert_eq!(parse_query("@someday plans", today()), (None, "@someday plans".to_string()));
+        assert_eq!(
+            parse_query("@2024 @2023 review", today()),
+            (Some((date(2024, 1, 1), date(2024, 12, 31))), "@2023 review".to_string())
+        );
+        assert_eq!(parse_query("plain text", today()), (None, "plain text".to_string()));
+    }
+}
\ No newline at end of file
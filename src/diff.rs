@@ -0,0 +1,30 @@
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+
+use journal_tui::theme::Theme;
+
+// Styles `git diff` output for the viewer: additions in the accent color, removals in
+// the error color, hunk headers highlighted and git's file headers dimmed
+pub fn render(diff: &str, theme: &Theme) -> Vec<Line<'static>> {
+    diff.lines()
+        .map(|line| {
+            let style = if line.starts_with("+++") || line.starts_with("---") {
+                Style::default().fg(theme.dim).add_modifier(Modifier::BOLD)
+            } else if line.starts_with("@@") {
+                Style::default().fg(theme.secondary)
+            } else if line.starts_with('+') {
+                Style::default().fg(theme.accent)
+            } else if line.starts_with('-') {
+                Style::default().fg(theme.error)
+            } else if line.starts_with(' ') || line.is_empty() {
+                Style::default().fg(theme.muted)
+            } else {
+                // "diff --git", "index ...", "\ No newline at end of file"
+                Style::default().fg(theme.dim)
+            };
+            Line::from(Span::styled(line.to_string(), style))
+        })
+        .collect()
+}
\ No newline at end of file
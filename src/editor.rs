@@ -0,0 +1,361 @@
+use std::path::PathBuf;
+use unicode_width::UnicodeWidthChar;
+
+// Text being edited in the built-in editor. The cursor is a line index and a char
+// (not byte) index within that line, so multi-byte text moves one character at a time.
+pub struct Editor {
+    pub path: PathBuf,
+    lines: Vec<String>,
+    row: usize,
+    col: usize,
+    // First wrapped row on screen
+    scroll: usize,
+    // Screen column to return to when moving up or down through shorter rows
+    goal: Option<usize>,
+    // Column width from the last draw, which up and down move through
+    width: usize,
+    // Files with Windows line endings are saved with them again
+    crlf: bool,
+    pub modified: bool,
+    // Set by a first Esc or Ctrl-Q with unsaved changes; a second one discards them
+    pub confirm_discard: bool,
+}
+
+// One screen row: a line index and the char range of that line shown on it
+type Row = (usize, usize, usize);
+
+impl Editor {
+    pub fn new(path: PathBuf, text: &str, row: usize) -> Self {
+        let crlf = text.contains("\r\n");
+        let lines: Vec<String> = text.replace("\r\n", "\n").split('\n').map(str::to_string).collect();
+        let row = row.min(lines.len() - 1);
+        Self {
+            path,
+            lines,
+            row,
+            col: 0,
+            scroll: 0,
+            goal: None,
+            width: 0,
+            crlf,
+            modified: false,
+            confirm_discard: false,
+        }
+    }
+    
+    pub fn text(&self) -> String {
+        self.lines.join(if self.crlf { "\r\n" } else { "\n" })
+    }
+    
+    pub fn insert(&mut self, c: char) {
+        let at = self.byte_index();
+        self.lines[self.row].insert(at, c);
+        self.col += 1;
+        self.edited();
+    }
+    
+    // Pasted text, with its line breaks
+    pub fn insert_str(&mut self, text: &str) {
+        for c in text.replace("\r\n", "\n").chars() {
+            match c {
+                '\n' | '\r' => self.newline(),
+                c => self.insert(c),
+            }
+        }
+    }
+    
+    pub fn newline(&mut self) {
+        let at = self.byte_index();
+        let rest = self.lines[self.row].split_off(at);
+        self.lines.insert(self.row + 1, rest);
+        self.row += 1;
+        self.col = 0;
+        self.edited();
+    }
+    
+    // Joins with the previous line at the start of one
+    pub fn backspace(&mut self) {
+        if self.col > 0 {
+            self.col -= 1;
+            let at = self.byte_index();
+            self.lines[self.row].remove(at);
+        } else if self.row > 0 {
+            let line = self.lines.remove(self.row);
+            self.row -= 1;
+            self.col = self.line_len();
+            self.lines[self.row].push_str(&line);
+        } else {
+            return;
+        }
+        self.edited();
+    }
+    
+    // Joins with the next line at the end of one
+    pub fn delete(&mut self) {
+        if self.col < self.line_len() {
+            let at = self.byte_index();
+            self.lines[self.row].remove(at);
+        } else if self.row + 1 < self.lines.len() {
+            let next = self.lines.remove(self.row + 1);
+            self.lines[self.row].push_str(&next);
+        } else {
+            return;
+        }
+        self.edited();
+    }
+    
+    pub fn left(&mut self) {
+        if self.col > 0 {
+            self.col -= 1;
+        } else if self.row > 0 {
+            self.row -= 1;
+            self.col = self.line_len();
+        }
+        self.goal = None;
+    }
+    
+    pub fn right(&mut self) {
+        if self.col < self.line_len() {
+            self.col += 1;
+        } else if self.row + 1 < self.lines.len() {
+            self.row += 1;
+            self.col = 0;
+        }
+        self.goal = None;
+    }
+    
+    pub fn home(&mut self) {
+        self.col = 0;
+        self.goal = None;
+    }
+    
+    pub fn end(&mut self) {
+        self.col = self.line_len();
+        self.goal = None;
+    }
+    
+    // Up and down follow wrapped rows, not lines, so a long paragraph isn't skipped in one step
+    pub fn up(&mut self) {
+        self.move_rows(-1);
+    }
+    
+    pub fn down(&mut self) {
+        self.move_rows(1);
+    }
+    
+    // The wrapped rows that fit in `height`, scrolled to keep the cursor in view, and the
+    // cursor's position among them
+    pub fn view(&mut self, width: usize, height: usize) -> (Vec<String>, (usize, usize)) {
+        self.width = width.max(1);
+        let rows = self.rows();
+        let (cursor_row, cursor_x) = self.cursor_in(&rows);
+        
+        let height = height.max(1);
+        if cursor_row < self.scroll {
+            self.scroll = cursor_row;
+        } else if cursor_row >= self.scroll + height {
+            self.scroll = cursor_row + 1 - height;
+        }
+        
+        let shown = rows
+            .iter()
+            .skip(self.scroll)
+            .take(height)
+            .map(|&(line, start, end)| self.lines[line].chars().skip(start).take(end - start).collect())
+            .collect();
+        (shown, (cursor_x, cursor_row - self.scroll))
+    }
+    
+    fn edited(&mut self) {
+        self.modified = true;
+        self.confirm_discard = false;
+        self.goal = None;
+    }
+    
+    fn line_len(&self) -> usize {
+        self.lines[self.row].chars().count()
+    }
+    
+    fn byte_index(&self) -> usize {
+        let line = &self.lines[self.row];
+        line.char_indices().nth(self.col).map_or(line.len(), |(i, _)| i)
+    }
+    
+    fn move_rows(&mut self, delta: isize) {
+        let rows = self.rows();
+        let (cursor_row, cursor_x) = self.cursor_in(&rows);
+        let goal = *self.goal.get_or_insert(cursor_x);
+        let Some(&(line, start, end)) = cursor_row.checked_add_signed(delta).and_then(|r| rows.get(r)) else {
+            return;
+        };
+        
+        let chars: Vec<char> = self.lines[line].chars().collect();
+        let mut col = start;
+        let mut x = 0;
+        while col < end {
+            let w = chars[col].width().unwrap_or(0);
+            if x + w > goal {
+                break;
+            }
+            x += w;
+            col += 1;
+        }
+        // The end of a row that wraps is drawn at the start of the next one
+        let wraps = rows.iter().any(|&(l, s, _)| l == line && s == end && s > start);
+        self.row = line;
+        self.col = if wraps && col == end { end - 1 } else { col };
+    }
+    
+    fn cursor_in(&self, rows: &[Row]) -> (usize, usize) {
+        let index = rows
+            .iter()
+            .rposition(|&(line, start, _)| line == self.row && start <= self.col)
+            .unwrap_or(0);
+        let (line, start, _) = rows[index];
+        let x = self.lines[line].chars().skip(start).take(self.col - start).filter_map(|c| c.width()).sum();
+        (index, x)
+    }
+    
+    // Soft wrap at the last space that fits, or mid-word when a word is wider than the column
+    fn rows(&self) -> Vec<Row> {
+        let mut rows = Vec::new();
+        for (index, line) in self.lines.iter().enumerate() {
+            let chars: Vec<char> = line.chars().collect();
+            let mut start = 0;
+            let mut x = 0;
+            let mut last_space = None;
+            for (i, c) in chars.iter().enumerate() {
+                let w = c.width().unwrap_or(0);
+                if x + w > self.width && i > start {
+                    let end = last_space.filter(|&s| s > start).unwrap_or(i);
+                    rows.push((index, start, end));
+                    start = end;
+                    x = chars[start..i].iter().filter_map(|c| c.width()).sum();
+                    last_space = None;
+                }
+                x += w;
+                if *c == ' ' {
+                    last_space = Some(i + 1);
+                }
+            }
+            rows.push((index, start, chars.len()));
+        }
+        rows
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn editor(text: &str) -> Editor {
+        Editor::new(PathBuf::from("entry.md"), text, 0)
+    }
+    
+    fn shown(editor: &mut Editor, width: usize) -> Vec<String> {
+        editor.view(width, 100).0
+    }
+    
+    #[test]
+    fn lines_wrap_at_the_last_space_that_fits() {
+        assert_eq!(shown(&mut editor("hello world foo"), 11), ["hello ", "world foo"]);
+        assert_eq!(shown(&mut editor("one two three"), 20), ["one two three"]);
+        assert_eq!(shown(&mut editor("a\n\nb"), 5), ["a", "", "b"]);
+    }
+    
+    #[test]
+    fn words_wider_than_the_column_break_mid_word() {
+        assert_eq!(shown(&mut editor("abcdefghij"), 4), ["abcd", "efgh", "ij"]);
+        assert_eq!(shown(&mut editor("ab cdefghij"), 4), ["ab ", "cdef", "ghij"]);
+    }
+    
+    #[test]
+    fn wide_characters_take_two_columns() {
+        assert_eq!(shown(&mut editor("日本語です"), 5), ["日本", "語で", "す"]);
+        let mut e = editor("日本語");
+        e.end();
+        assert_eq!(e.view(10, 5).1, (6, 0));
+    }
+    
+    #[test]
+    fn the_cursor_at_a_wrap_point_is_drawn_on_the_next_row() {
+        let mut e = editor("abcdefgh");
+        e.view(4, 5);
+        for _ in 0..4 {
+            e.right();
+        }
+        assert_eq!(e.view(4, 5).1, (0, 1));
+        e.end();
+        assert_eq!(e.view(4, 5).1, (4, 1));
+    }
+    
+    #[test]
+    fn up_and_down_move_through_wrapped_rows_and_keep_the_column() {
+        let mut e = editor("abcdefgh\nab\nabcdef");
+        e.view(4, 10);
+        e.right();
+        e.down();
+        assert_eq!(e.view(4, 10).1, (1, 1));
+        e.up();
+        assert_eq!(e.view(4, 10).1, (1, 0));
+        
+        e.view(10, 10);
+        e.end();
+        e.left();
+        e.down();
+        // "ab" is too short, but the next line gets the column back
+        assert_eq!(e.view(10, 10).1, (2, 1));
+        e.down();
+        assert_eq!(e.view(10, 10).1, (6, 2));
+    }
+    
+    #[test]
+    fn moving_down_onto_a_full_wrapped_row_stops_before_its_end() {
+        let mut e = editor("abcd\nefghijkl");
+        e.view(4, 10);
+        e.end();
+        e.down();
+        // Column 4 of "efgh|ijkl" would be drawn at the start of the next row
+        assert_eq!(e.view(4, 10).1, (3, 1));
+    }
+    
+    #[test]
+    fn the_view_scrolls_to_keep_the_cursor_visible() {
+        let mut e = editor("1\n2\n3\n4\n5");
+        for _ in 0..4 {
+            e.down();
+        }
+        assert_eq!(e.view(10, 2), (vec!["4".to_string(), "5".to_string()], (0, 1)));
+        for _ in 0..4 {
+            e.up();
+        }
+        assert_eq!(e.view(10, 2), (vec!["1".to_string(), "2".to_string()], (0, 0)));
+    }
+    
+    #[test]
+    fn edits_move_the_cursor_by_characters_and_join_lines() {
+        let mut e = editor("héllo\nwörld");
+        e.right();
+        e.right();
+        e.insert('x');
+        assert_eq!(e.text(), "héxllo\nwörld");
+        e.end();
+        e.delete();
+        assert_eq!(e.text(), "héxllowörld");
+        e.home();
+        e.insert_str("a\r\nb");
+        assert_eq!(e.text(), "a\nbhéxllowörld");
+        e.home();
+        e.backspace();
+        assert_eq!(e.text(), "abhéxllowörld");
+        assert!(e.modified);
+    }
+    
+    #[test]
+    fn windows_line_endings_are_kept() {
+        let mut e = editor("one\r\ntwo");
+        e.end();
+        e.newline();
+        assert_eq!(e.text(), "one\r\n\r\ntwo");
+    }
+}
\ No newline at end of file
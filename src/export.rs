@@ -0,0 +1,163 @@
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag};
+use ratatui::style::Color;
+
+use crate::frontmatter;
+use crate::theme::Theme;
+
+// A standalone HTML page for an entry, styled with the theme's colors and no external assets.
+// Raw HTML in the entry is shown as text and script links are dropped, so the page is safe to share.
+pub fn entry_to_html(content: &str, theme: &Theme) -> String {
+    let (_, body) = frontmatter::parse(content);
+    let title = frontmatter::title(content)
+        .or_else(|| body.lines().find_map(|l| l.strip_prefix("# ")).map(|t| t.trim().to_string()))
+        .unwrap_or_else(|| "Journal entry".to_string());
+    
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let events = Parser::new_ext(body, options).map(|event| match event {
+        Event::Html(raw) | Event::InlineHtml(raw) => Event::Text(raw),
+        Event::Start(Tag::Link { link_type, dest_url, title, id }) => {
+            Event::Start(Tag::Link { link_type, dest_url: safe_url(dest_url), title, id })
+        }
+        Event::Start(Tag::Image { link_type, dest_url, title, id }) => {
+            Event::Start(Tag::Image { link_type, dest_url: safe_url(dest_url), title, id })
+        }
+        other => other,
+    });
+    let mut rendered = String::new();
+    html::push_html(&mut rendered, events);
+    
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{}</title>\n<style>\n{}</style>\n</head>\n<body>\n<main>\n{}</main>\n</body>\n</html>\n",
+        escape(&title),
+        stylesheet(theme),
+        rendered
+    )
+}
+
+// `javascript:` and friends would run when the link is clicked
+fn safe_url(url: CowStr<'_>) -> CowStr<'_> {
+    let scheme = url.trim_start().to_ascii_lowercase();
+    if ["javascript:", "vbscript:", "data:"].iter().any(|s| scheme.starts_with(s)) {
+        CowStr::Borrowed("#")
+    } else {
+        url
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn stylesheet(theme: &Theme) -> String {
+    let rule = |selector: &str, declarations: &[(&str, Color)]| {
+        let body: String = declarations
+            .iter()
+            .filter_map(|(property, color)| css_color(*color).map(|c| format!(" {}: {};", property, c)))
+            .collect();
+        format!("{} {{{} }}\n", selector, body)
+    };
+    
+    let mut css = String::from(
+        "body { margin: 0; font-family: ui-monospace, \"SF Mono\", Menlo, Consolas, monospace; line-height: 1.6; }\n\
+         main { max-width: 46rem; margin: 2rem auto; padding: 1.5rem 2rem; border: 1px solid; }\n\
+         pre { padding: 0.75rem; overflow-x: auto; }\n\
+         code { padding: 0 0.2rem; }\n\
+         blockquote { margin-left: 0; padding-left: 1rem; border-left: 3px solid; }\n\
+         table { border-collapse: collapse; }\n\
+         th, td { padding: 0.25rem 0.6rem; border: 1px solid; }\n",
+    );
+    css += &rule("body", &[("background", theme.background), ("color", theme.text)]);
+    css += &rule("main", &[("border-color", theme.accent_dim)]);
+    css += &rule("h1, h2, h3, h4, h5, h6", &[("color", theme.accent)]);
+    if let Some(glow) = css_color(theme.accent) {
+        css += &format!("h1 {{ text-shadow: 0 0 8px {}; }}\n", glow);
+    }
+    css += &rule("a", &[("color", theme.secondary)]);
+    css += &rule("pre, code", &[("background", theme.highlight), ("color", theme.accent)]);
+    css += &rule("blockquote", &[("border-color", theme.accent_dim), ("color", theme.muted)]);
+    css += &rule("hr", &[("border-color", theme.dim)]);
+    css += &rule("th, td", &[("border-color", theme.dim)]);
+    css
+}
+
+// None for the terminal's default color, which leaves the browser's default in place
+fn css_color(color: Color) -> Option<String> {
+    let rgb = match color {
+        Color::Reset => return None,
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Black => (0, 0, 0),
+        Color::Red => (205, 49, 49),
+        Color::Green => (13, 188, 121),
+        Color::Yellow => (229, 229, 16),
+        Color::Blue => (36, 114, 200),
+        Color::Magenta => (188, 63, 188),
+        Color::Cyan => (17, 168, 205),
+        Color::Gray => (204, 204, 204),
+        Color::DarkGray => (118, 118, 118),
+        Color::LightRed => (241, 76, 76),
+        Color::LightGreen => (35, 209, 139),
+        Color::LightYellow => (245, 245, 67),
+        Color::LightBlue => (59, 142, 234),
+        Color::LightMagenta => (214, 112, 214),
+        Color::LightCyan => (41, 184, 219),
+        Color::White => (255, 255, 255),
+        // 256-color palette entries have no fixed RGB value
+        Color::Indexed(_) => return None,
+    };
+    Some(format!("#{:02x}{:02x}{:02x}", rgb.0, rgb.1, rgb.2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn theme() -> Theme {
+        Theme::preset("matrix").unwrap()
+    }
+    
+    // Just the rendered entry, without the page around it
+    fn main_of(page: &str) -> &str {
+        let start = page.find("<main>\n").unwrap() + "<main>\n".len();
+        &page[start..page.find("</main>").unwrap()]
+    }
+    
+    #[test]
+    fn raw_html_is_escaped_as_text() {
+        let page = entry_to_html("Before <script>alert('x')</script> after\n\n<div onclick=\"x()\">hi</div>\n", &theme());
+        assert!(!page.contains("<script>"));
+        assert!(!page.contains("<div onclick"));
+        assert!(page.contains("&lt;script&gt;"));
+        assert!(page.contains("&lt;div onclick="));
+    }
+    
+    #[test]
+    fn ampersands_are_escaped_in_body_and_title() {
+        let page = entry_to_html("---\ntitle: Salt & <Pepper>\n---\nFish & chips\n", &theme());
+        assert!(page.contains("<title>Salt &amp; &lt;Pepper&gt;</title>"));
+        assert!(main_of(&page).contains("Fish &amp; chips"));
+    }
+    
+    #[test]
+    fn headings_and_lists_are_rendered() {
+        let page = entry_to_html("# Plans\n\n## Today\n\n- one\n- two\n\n1. first\n", &theme());
+        assert_eq!(
+            main_of(&page),
+            "<h1>Plans</h1>\n<h2>Today</h2>\n<ul>\n<li>one</li>\n<li>two</li>\n</ul>\n<ol>\n<li>first</li>\n</ol>\n"
+        );
+        assert!(page.contains("<title>Plans</title>"));
+    }
+    
+    #[test]
+    fn script_links_are_dropped() {
+        let page = entry_to_html("[click](javascript:alert(1)) [ok](https://example.com)\n", &theme());
+        assert!(page.contains("<a href=\"#\">click</a>"));
+        assert!(page.contains("<a href=\"https://example.com\">ok</a>"));
+    }
+    
+    #[test]
+    fn frontmatter_is_left_out_and_untitled_entries_get_a_default() {
+        let page = entry_to_html("---\nmood: fine\n---\nJust text\n", &theme());
+        assert!(!page.contains("mood"));
+        assert!(page.contains("<title>Journal entry</title>"));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,181 @@
+use anyhow::{bail, Result};
+use chrono::format::{parse_and_remainder, Item, Parsed, StrftimeItems};
+use chrono::{NaiveDateTime, NaiveTime, TimeDelta};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+// Human-friendly age like "3h ago"; None once it's a week old and an absolute date reads better,
+// and for future times (a clock change or an entry dated ahead), which have no sensible age
+pub fn humanize_duration(elapsed: TimeDelta) -> Option<String> {
+    if elapsed < TimeDelta::zero() {
+        return None;
+    }
+    let minutes = elapsed.num_minutes();
+    let hours = elapsed.num_hours();
+    let days = elapsed.num_days();
+    
+    let text = if minutes < 1 {
+        "just now".to_string()
+    } else if minutes < 60 {
+        format!("{}m ago", minutes)
+    } else if hours < 24 {
+        format!("{}h ago", hours)
+    } else if days < 2 {
+        "yesterday".to_string()
+    } else if days < 7 {
+        format!("{} days ago", days)
+    } else {
+        return None;
+    };
+    
+    Some(text)
+}
+
+// Rejects strftime formats chrono can't render, which would otherwise panic mid-draw
+pub fn validate_date_format(format: &str) -> Result<()> {
+    if format.trim().is_empty() {
+        bail!("date format is empty");
+    }
+    if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+        bail!("'{}' is not a valid date format", format);
+    }
+    Ok(())
+}
+
+// Parse the timestamp prefix written by `filename_date_format`, returning it with the
+// rest of the filename stem. Date-only formats are read as midnight.
+pub fn parse_filename_date<'a>(stem: &'a str, format: &str) -> Option<(NaiveDateTime, &'a str)> {
+    let mut parsed = Parsed::new();
+    let rest = parse_and_remainder(&mut parsed, stem, StrftimeItems::new(format)).ok()?;
+    let date = parsed.to_naive_date().ok()?;
+    let time = parsed.to_naive_time().unwrap_or(NaiveTime::MIN);
+    Some((date.and_time(time), rest))
+}
+
+// Average adult silent-reading speed
+const WORDS_PER_MINUTE: usize = 200;
+
+// Rounded up so short entries still read as "~1 min" rather than zero
+pub fn reading_time_minutes(word_count: usize) -> u32 {
+    word_count.div_ceil(WORDS_PER_MINUTE).max(1) as u32
+}
+
+// `text` cut to at most `max_cols` terminal columns, ending in "…" when anything was cut.
+// Slices at char boundaries, never splits a double-width character, and keeps combining
+// marks with the character they follow.
+pub fn truncate_display(text: &str, max_cols: usize) -> String {
+    if text.width() <= max_cols {
+        return text.to_string();
+    }
+    if max_cols == 0 {
+        return String::new();
+    }
+    
+    let budget = max_cols - 1;
+    let mut cols = 0;
+    let mut end = 0;
+    for (i, c) in text.char_indices() {
+        let w = c.width().unwrap_or(0);
+        if cols + w > budget {
+            break;
+        }
+        cols += w;
+        end = i + c.len_utf8();
+    }
+    format!("{}…", &text[..end])
+}
+
+// "512 B", "3.4 KB", "1.2 MB", in binary units
+pub fn human_size(bytes: u64) -> String {
+    const KB: f64 = 1024.0;
+    let size = bytes as f64;
+    if size < KB {
+        format!("{} B", bytes)
+    } else if size < KB * KB {
+        format!("{:.1} KB", size / KB)
+    } else {
+        format!("{:.1} MB", size / (KB * KB))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn text_that_fits_is_unchanged() {
+        assert_eq!(truncate_display("short", 10), "short");
+        assert_eq!(truncate_display("exact", 5), "exact");
+        assert_eq!(truncate_display("", 0), "");
+    }
+    
+    #[test]
+    fn ascii_is_cut_with_an_ellipsis_within_the_width() {
+        assert_eq!(truncate_display("hello world", 5), "hell…");
+        assert_eq!(truncate_display("hello", 1), "…");
+        assert_eq!(truncate_display("hello", 0), "");
+    }
+    
+    #[test]
+    fn double_width_characters_are_never_split() {
+        assert_eq!(truncate_display("日本語のテキスト", 7), "日本語…");
+        // 日 would straddle the last column before the ellipsis, so it goes entirely
+        assert_eq!(truncate_display("ab日cd", 4), "ab…");
+        assert_eq!(truncate_display("ab日cd", 5), "ab日…");
+    }
+    
+    #[test]
+    fn combining_marks_stay_with_their_base() {
+        // The mark right at the cut takes no column and stays on the kept "e"
+        assert_eq!(truncate_display("abce\u{301}fg", 5), "abce\u{301}…");
+        // When the base is cut, its mark goes with it
+        assert_eq!(truncate_display("abcde\u{301}f", 4), "abc…");
+        // Marks don't count towards the width
+        assert_eq!(truncate_display("e\u{301}e\u{301}", 2), "e\u{301}e\u{301}");
+    }
+    
+    #[test]
+    fn emoji_count_as_two_columns() {
+        assert_eq!(truncate_display("🎉🎉🎉", 4), "🎉…");
+        assert_eq!(truncate_display("🎉🎉", 4), "🎉🎉");
+    }
+    
+    #[test]
+    fn result_never_exceeds_the_width() {
+        for text in ["日本語のテキスト", "a🎉b🎉c🎉", "e\u{301}xample text", "mixed 日本 and 🎉"] {
+            for max in 0..12 {
+                assert!(truncate_display(text, max).width() <= max, "{:?} at {}", text, max);
+            }
+        }
+    }
+    
+    #[test]
+    fn ages_switch_units_at_their_boundaries() {
+        let age = |seconds: i64| humanize_duration(TimeDelta::seconds(seconds));
+        let (minute, hour, day) = (60, 60 * 60, 24 * 60 * 60);
+        assert_eq!(age(0).as_deref(), Some("just now"));
+        assert_eq!(age(59).as_deref(), Some("just now"));
+        assert_eq!(age(minute).as_deref(), Some("1m ago"));
+        assert_eq!(age(59 * minute).as_deref(), Some("59m ago"));
+        assert_eq!(age(hour).as_deref(), Some("1h ago"));
+        assert_eq!(age(23 * hour).as_deref(), Some("23h ago"));
+        assert_eq!(age(24 * hour).as_deref(), Some("yesterday"));
+        assert_eq!(age(47 * hour).as_deref(), Some("yesterday"));
+        assert_eq!(age(6 * day).as_deref(), Some("6 days ago"));
+        assert_eq!(age(7 * day), None);
+    }
+    
+    #[test]
+    fn future_times_have_no_age() {
+        assert_eq!(humanize_duration(TimeDelta::seconds(-1)), None);
+        assert_eq!(humanize_duration(TimeDelta::hours(-5)), None);
+    }
+    
+    #[test]
+    fn reading_time_rounds_up_to_whole_minutes() {
+        assert_eq!(reading_time_minutes(0), 1);
+        assert_eq!(reading_time_minutes(1), 1);
+        assert_eq!(reading_time_minutes(200), 1);
+        assert_eq!(reading_time_minutes(201), 2);
+        assert_eq!(reading_time_minutes(1000), 5);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,153 @@
+// Minimal YAML-style frontmatter support: a leading `---` block of `key: value` lines
+
+pub struct Frontmatter {
+    fields: Vec<(String, String)>,
+}
+
+impl Frontmatter {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+// Split content into its frontmatter (if any) and the remaining body
+pub fn parse(content: &str) -> (Option<Frontmatter>, &str) {
+    let Some(rest) = content.strip_prefix("---\n").or_else(|| content.strip_prefix("---\r\n")) else {
+        return (None, content);
+    };
+    
+    let mut fields = Vec::new();
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        offset += line.len();
+        let trimmed = line.trim_end();
+        if trimmed == "---" || trimmed == "..." {
+            return (Some(Frontmatter { fields }), &rest[offset..]);
+        }
+        
+        if let Some((key, value)) = trimmed.split_once(':') {
+            fields.push((key.trim().to_string(), unquote(value.trim()).to_string()));
+        }
+    }
+    
+    // An unterminated block isn't frontmatter, just content that starts with a rule
+    (None, content)
+}
+
+// What's left to read once the title is shown: the text after any frontmatter and a
+// leading `# ` heading, without the blank lines around it
+pub fn body(content: &str) -> &str {
+    let (_, mut rest) = parse(content);
+    let mut title_skipped = false;
+    loop {
+        let line_end = rest.find('\n').map_or(rest.len(), |i| i + 1);
+        let line = rest[..line_end].trim();
+        let skip = if line.is_empty() {
+            line_end > 0
+        } else {
+            !title_skipped && line.starts_with("# ")
+        };
+        if !skip {
+            return rest;
+        }
+        title_skipped |= !line.is_empty();
+        rest = &rest[line_end..];
+    }
+}
+
+// Title precedence: frontmatter `title:` first, then the first `# ` heading
+pub fn title(content: &str) -> Option<String> {
+    let (frontmatter, body) = parse(content);
+    
+    if let Some(title) = frontmatter.as_ref().and_then(|fm| fm.get("title"))
+        && !title.is_empty()
+    {
+        return Some(title.to_string());
+    }
+    
+    body.lines()
+        .find_map(|line| line.strip_prefix("# "))
+        .map(|title| title.trim().to_string())
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = value.strip_prefix(quote).and_then(|v| v.strip_suffix(quote)) {
+            return inner;
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn parse_reads_fields_and_returns_the_rest() {
+        let (fm, body) = parse("---\ntitle: \"Hello: world\"\nTags: a, b\n---\n# Heading\n");
+        let fm = fm.unwrap();
+        assert_eq!(fm.get("title"), Some("Hello: world"));
+        // Keys match case-insensitively
+        assert_eq!(fm.get("tags"), Some("a, b"));
+        assert_eq!(fm.get("missing"), None);
+        assert_eq!(body, "# Heading\n");
+    }
+    
+    #[test]
+    fn parse_accepts_crlf_and_a_dots_terminator() {
+        let (fm, body) = parse("---\r\ncolor: 'red'\r\n...\r\nText");
+        assert_eq!(fm.unwrap().get("color"), Some("red"));
+        assert_eq!(body, "Text");
+    }
+    
+    #[test]
+    fn parse_leaves_content_without_a_closed_block_alone() {
+        for content in ["", "No frontmatter", "---\nkey: value\nnever closed", " ---\nkey: v\n---\n"] {
+            let (fm, body) = parse(content);
+            assert!(fm.is_none(), "{:?}", content);
+            assert_eq!(body, content);
+        }
+    }
+    
+    #[test]
+    fn body_skips_frontmatter_title_and_surrounding_blank_lines() {
+        assert_eq!(body("---\ntitle: x\n---\n\n# Title\n\nFirst line\nSecond"), "First line\nSecond");
+        assert_eq!(body("# Title\nRight after"), "Right after");
+    }
+    
+    #[test]
+    fn body_of_empty_or_blank_content_is_empty() {
+        assert_eq!(body(""), "");
+        assert_eq!(body("\n\n  \n\t"), "");
+        assert_eq!(body("# Only a title\n\n"), "");
+        assert_eq!(body("---\nkey: v\n---\n"), "");
+    }
+    
+    #[test]
+    fn body_keeps_a_heading_that_isnt_first() {
+        assert_eq!(body("Intro\n# Heading\n"), "Intro\n# Heading\n");
+        // Only one title is skipped
+        assert_eq!(body("# One\n# Two\ntext"), "# Two\ntext");
+        // `#tag` isn't a heading
+        assert_eq!(body("#tag line"), "#tag line");
+    }
+    
+    #[test]
+    fn title_prefers_frontmatter_then_the_first_heading() {
+        assert_eq!(title("---\ntitle: From fm\n---\n# Heading"), Some("From fm".to_string()));
+        // An empty `title:` falls through to the heading
+        assert_eq!(title("---\ntitle:\n---\n# Heading "), Some("Heading".to_string()));
+        assert_eq!(title("Some text\n# Later heading"), Some("Later heading".to_string()));
+        assert_eq!(title("No heading here"), None);
+    }
+    
+    #[test]
+    fn frontmatter_title_needs_no_heading() {
+        assert_eq!(title("---\ntitle: Only fm\nmood: ok\n---\nJust text\n"), Some("Only fm".to_string()));
+        assert_eq!(title("---\ntitle: Only fm\n---\n"), Some("Only fm".to_string()));
+    }
+}
\ No newline at end of file
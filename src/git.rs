@@ -0,0 +1,89 @@
+use anyhow::{anyhow, Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+// Commit `files` on their own when `dir` is inside a git work tree. A file that no longer
+// exists is committed as deleted if git knew it, so a rename is recorded as one. Returns
+// false without doing anything when git isn't installed, `dir` isn't a repo, or nothing changed.
+pub fn autocommit(dir: &Path, files: &[&Path], message: &str) -> Result<bool> {
+    if !is_work_tree(dir) {
+        return Ok(false);
+    }
+    let files: Vec<&Path> = files.iter().copied().filter(|f| f.exists() || is_tracked(dir, f)).collect();
+    if files.is_empty() {
+        return Ok(false);
+    }
+    
+    run(dir, &["add", "-A", "--"], &files)?;
+    // `diff --cached --quiet` exits 0 when nothing is staged for these files
+    let unchanged = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["diff", "--cached", "--quiet", "--"])
+        .args(&files)
+        .status()
+        .context("Failed to run git diff")?
+        .success();
+    if unchanged {
+        return Ok(false);
+    }
+    
+    // Only these files, so anything else the user has staged stays staged
+    run(dir, &["commit", "-q", "-m", message, "--"], &files)?;
+    Ok(true)
+}
+
+fn is_tracked(dir: &Path, file: &Path) -> bool {
+    Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["ls-files", "--error-unmatch", "--"])
+        .arg(file)
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+// False when git is missing as well as outside a repository
+fn is_work_tree(dir: &Path) -> bool {
+    Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["rev-parse", "--is-inside-work-tree"])
+        .output()
+        .is_ok_and(|output| output.status.success() && output.stdout.starts_with(b"true"))
+}
+
+fn run(dir: &Path, args: &[&str], files: &[&Path]) -> Result<()> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .args(files)
+        .output()
+        .with_context(|| format!("Failed to run git {}", args[0]))?;
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("git {} failed: {}", args[0], error.trim()));
+    }
+    Ok(())
+}
+
+// Uncommitted changes to `file` as a unified diff against HEAD; None when `dir` isn't
+// in a git work tree. An empty string means nothing changed since the last commit.
+pub fn diff_head(dir: &Path, file: &Path) -> Result<Option<String>> {
+    if !is_work_tree(dir) {
+        return Ok(None);
+    }
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["diff", "--no-color", "HEAD", "--"])
+        .arg(file)
+        .output()
+        .context("Failed to run git diff")?;
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("git diff failed: {}", error.trim()));
+    }
+    Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned()))
+}
\ No newline at end of file
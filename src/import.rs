@@ -0,0 +1,148 @@
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone};
+use serde_json::Value;
+
+// Titles taken from an entry's first line are cut to this many characters
+const MAX_TITLE_CHARS: usize = 80;
+
+// One entry from another journal app's export
+pub struct Record {
+    pub created: DateTime<Local>,
+    pub title: String,
+    pub text: String,
+}
+
+// Records from a JSON array of `{date, title, text}` objects, or an object holding one under
+// `entries` (as Day One exports do, where `creationDate` stands in for `date`). Records that
+// can't be used are left out and described, with their 1-based position, in the second list.
+pub fn parse(json: &str) -> Result<(Vec<Record>, Vec<String>)> {
+    let value: Value = serde_json::from_str(json).context("Not valid JSON")?;
+    let items = match &value {
+        Value::Array(items) => items,
+        Value::Object(object) => match object.get("entries") {
+            Some(Value::Array(items)) => items,
+            _ => bail!("Expected an array of entries, or an object with an \"entries\" array"),
+        },
+        _ => bail!("Expected an array of entries"),
+    };
+    
+    let mut records = Vec::new();
+    let mut skipped = Vec::new();
+    for (i, item) in items.iter().enumerate() {
+        match parse_record(item) {
+            Ok(record) => records.push(record),
+            Err(reason) => skipped.push(format!("record {}: {}", i + 1, reason)),
+        }
+    }
+    Ok((records, skipped))
+}
+
+fn parse_record(item: &Value) -> std::result::Result<Record, String> {
+    let Value::Object(object) = item else {
+        return Err("not an object".to_string());
+    };
+    let field = |name: &str| object.get(name).and_then(Value::as_str);
+    
+    let date = field("date").or_else(|| field("creationDate")).ok_or("missing date")?;
+    let created = parse_date(date).ok_or_else(|| format!("unrecognized date '{}'", date))?;
+    let mut text = field("text").ok_or("missing text")?.trim_end().to_string();
+    let title = match field("title").map(str::trim).filter(|t| !t.is_empty()) {
+        Some(title) => title.to_string(),
+        // Untitled exports usually open with the title as a heading or plain line; it
+        // becomes the entry's `# ` heading, so it isn't repeated in the body
+        None => {
+            let first = text.lines().position(|line| !line.trim().is_empty()).ok_or("no title and no text")?;
+            let line = text.lines().nth(first).unwrap_or_default();
+            let title = line.trim().trim_start_matches('#').trim().chars().take(MAX_TITLE_CHARS).collect::<String>();
+            text = text.lines().skip(first + 1).collect::<Vec<_>>().join("\n").trim_start_matches('\n').to_string();
+            title
+        }
+    };
+    if title.is_empty() {
+        return Err("empty title".to_string());
+    }
+    Ok(Record { created, title, text })
+}
+
+// RFC 3339 (with any offset), or a local "2024-01-31 09:30[:00]", "2024-01-31T09:30:00" or "2024-01-31"
+fn parse_date(date: &str) -> Option<DateTime<Local>> {
+    let date = date.trim();
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(date) {
+        return Some(parsed.with_timezone(&Local));
+    }
+    let naive = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(date, format).ok())
+        .or_else(|| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok().and_then(|d| d.and_hms_opt(0, 0, 0)))?;
+    Local.from_local_datetime(&naive).earliest()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    
+    fn titles(json: &str) -> Vec<String> {
+        parse(json).unwrap().0.into_iter().map(|record| record.title).collect()
+    }
+    
+    #[test]
+    fn reads_a_bare_array_or_an_entries_object() {
+        let record = r#"{"date": "2024-01-31", "title": "One", "text": "body"}"#;
+        assert_eq!(titles(&format!("[{}]", record)), ["One"]);
+        assert_eq!(titles(&format!(r#"{{"entries": [{}], "metadata": {{}}}}"#, record)), ["One"]);
+        assert!(parse(r#"{"items": []}"#).is_err());
+        assert!(parse(r#""text""#).is_err());
+        assert!(parse("not json").is_err());
+    }
+    
+    #[test]
+    fn creation_date_stands_in_for_date() {
+        let (records, skipped) = parse(r#"[{"creationDate": "2024-01-31T09:30:00Z", "title": "Day One", "text": ""}]"#).unwrap();
+        assert!(skipped.is_empty());
+        assert_eq!(records[0].created, Utc.with_ymd_and_hms(2024, 1, 31, 9, 30, 0).unwrap());
+    }
+    
+    #[test]
+    fn a_missing_or_empty_title_comes_from_the_first_line() {
+        let (records, _) = parse(r##"[
+            {"date": "2024-01-31", "text": "\n# Heading line\nrest\n"},
+            {"date": "2024-01-31", "title": "  ", "text": "Plain line\n\nmore"}
+        ]"##).unwrap();
+        assert_eq!((records[0].title.as_str(), records[0].text.as_str()), ("Heading line", "rest"));
+        assert_eq!((records[1].title.as_str(), records[1].text.as_str()), ("Plain line", "more"));
+    }
+    
+    #[test]
+    fn dates_are_rfc_3339_or_naive_local() {
+        let offset = parse_date("2024-01-31T09:30:00+02:00").unwrap();
+        assert_eq!(offset, Utc.with_ymd_and_hms(2024, 1, 31, 7, 30, 0).unwrap());
+        
+        let local = Local.with_ymd_and_hms(2024, 1, 31, 9, 30, 0).unwrap();
+        assert_eq!(parse_date("2024-01-31 09:30:00"), Some(local));
+        assert_eq!(parse_date("2024-01-31T09:30:00"), Some(local));
+        assert_eq!(parse_date(" 2024-01-31 09:30 "), Some(local));
+        assert_eq!(parse_date("2024-01-31"), Some(Local.with_ymd_and_hms(2024, 1, 31, 0, 0, 0).unwrap()));
+        assert_eq!(parse_date("31/01/2024"), None);
+    }
+    
+    #[test]
+    fn unusable_records_are_skipped_by_position() {
+        let (records, skipped) = parse(r#"[
+            {"date": "2024-01-31", "title": "Kept", "text": ""},
+            "a string",
+            {"title": "No date", "text": ""},
+            {"date": "yesterday", "title": "Bad date", "text": ""},
+            {"date": "2024-01-31", "title": "No text"},
+            {"date": "2024-01-31", "text": "  \n"}
+        ]"#).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(skipped, [
+            "record 2: not an object",
+            "record 3: missing date",
+            "record 4: unrecognized date 'yesterday'",
+            "record 5: missing text",
+            "record 6: no title and no text",
+        ]);
+    }
+}
\ No newline at end of file
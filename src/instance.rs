@@ -0,0 +1,90 @@
+use anyhow::{bail, Context, Result};
+use std::fs::{self, File, TryLockError};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+// Held while the TUI has a vault open, so a second launch can't mount or unmount the same
+// image underneath it. It's an OS file lock (flock, LockFileEx) rather than the file existing,
+// so it goes away with the process however that ends and is never left behind stale. The file
+// stays put and holds the owner's PID for the "already open" message.
+pub struct InstanceLock {
+    // The lock lasts as long as this handle is open
+    _file: File,
+}
+
+impl InstanceLock {
+    // Beside the vault image, e.g. ~/.journal/vault.dmg.lock, so separate vaults don't block each other
+    pub fn path_for(dmg_path: &Path) -> PathBuf {
+        let mut name = dmg_path.file_name().unwrap_or_default().to_os_string();
+        name.push(".lock");
+        dmg_path.with_file_name(name)
+    }
+    
+    pub fn acquire(path: PathBuf) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        // Never truncated or removed before the lock is held, so an owner's PID can't be lost
+        let mut file = fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                let mut holder = String::new();
+                let _ = file.read_to_string(&mut holder);
+                let holder = holder.trim().parse::<u32>().map(|pid| format!(" (process {})", pid)).unwrap_or_default();
+                bail!(
+                    "The journal is already open in another window{}; close it first (lock file {})",
+                    holder,
+                    path.display()
+                );
+            }
+            Err(TryLockError::Error(err)) => return Err(err).with_context(|| format!("Failed to lock {}", path.display())),
+        }
+        
+        file.set_len(0)
+            .and_then(|_| write!(file, "{}", std::process::id()))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(Self { _file: file })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn a_second_lock_fails_and_names_the_holder_and_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = InstanceLock::path_for(&dir.path().join("vault.dmg"));
+        assert_eq!(path, dir.path().join("vault.dmg.lock"));
+        
+        let _lock = InstanceLock::acquire(path.clone()).unwrap();
+        let message = InstanceLock::acquire(path.clone()).err().unwrap().to_string();
+        assert!(message.contains(&format!("(process {})", std::process::id())), "{}", message);
+        assert!(message.contains(&path.display().to_string()), "{}", message);
+    }
+    
+    #[test]
+    fn the_lock_is_free_again_once_dropped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vault.dmg.lock");
+        drop(InstanceLock::acquire(path.clone()).unwrap());
+        assert!(InstanceLock::acquire(path).is_ok());
+    }
+    
+    #[test]
+    fn a_file_left_by_a_dead_process_is_taken_over() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vault.dmg.lock");
+        fs::write(&path, "4294967295").unwrap();
+        
+        let _lock = InstanceLock::acquire(path.clone()).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), std::process::id().to_string());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,1021 @@
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Local, NaiveDate, TimeZone};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::{config, crypto, format, frontmatter, search, trash};
+
+// Archived entries live in this subdirectory of the journal and are hidden by default
+pub const ARCHIVE_DIR: &str = "archive";
+// Record of retention pruning, kept next to the trash inside the vault
+const PRUNE_LOG: &str = "pruned.log";
+// Plain-text entries without a heading are titled by their first line, cut to this length
+const MAX_LINE_TITLE_CHARS: usize = 80;
+// Marks where editing should start in an entry template; removed from the entry
+pub const CURSOR_PLACEHOLDER: &str = "{cursor}";
+const MAX_ICON_CHARS: usize = 2;
+
+// Something `Journal::audit` found wrong with an entry file
+#[derive(Debug)]
+pub struct Issue {
+    pub path: PathBuf,
+    pub kind: IssueKind,
+}
+
+#[derive(Debug)]
+pub enum IssueKind {
+    // Zero bytes; fixed by moving it to the trash
+    Empty,
+    // Neither a frontmatter `title:` nor a `# ` heading; fixed by adding a heading from the filename
+    MissingTitle,
+    // The filename's title part doesn't match the entry's title; fixed by renaming to this
+    NameMismatch(String),
+    InvalidUtf8,
+    // Couldn't be read or decrypted, with the reason
+    Unreadable(String),
+}
+
+impl std::fmt::Display for IssueKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IssueKind::Empty => write!(f, "empty file"),
+            IssueKind::MissingTitle => write!(f, "no title"),
+            IssueKind::NameMismatch(wanted) => write!(f, "filename doesn't match the title (expected {})", wanted),
+            IssueKind::InvalidUtf8 => write!(f, "not valid UTF-8"),
+            IssueKind::Unreadable(reason) => write!(f, "unreadable: {}", reason),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct JournalEntry {
+    pub title: String,
+    pub path: PathBuf,
+    pub created: DateTime<Local>,
+    // Last write to the file, which is later than `created` once an entry is edited
+    pub modified: DateTime<Local>,
+    // On-disk size in bytes (of the ciphertext, when entries are encrypted)
+    pub size: u64,
+    // Decrypted text cached at load time for search and preview; None if unreadable
+    pub content: Option<String>,
+    // Why `content` couldn't be read, so the UI can say more than "empty", or what's off
+    // about text that was read anyway (invalid UTF-8)
+    pub error: Option<String>,
+    pub archived: bool,
+    // From frontmatter `color:` (a color name or #rrggbb) and `icon:`, to make entries stand out
+    pub color: Option<String>,
+    pub icon: Option<String>,
+    // From frontmatter `tags: [work, ideas]` (or `tags: work, ideas`), lowercased, no duplicates
+    pub tags: Vec<String>,
+    // Frontmatter `locked: true`: opening it asks for authentication again
+    pub locked: bool,
+    // Listed from `entries.external` rather than found in the journal directory
+    pub external: bool,
+}
+
+// The storage layer: entry files, optional per-entry encryption and the trash.
+// Everything here returns data; drawing and editors belong to the frontend.
+pub struct Journal {
+    dir: PathBuf,
+    trash_dir: PathBuf,
+    // Set when per-entry encryption is enabled in the config
+    key: Option<crypto::EntryKey>,
+    // Files are written back in the form they're already in and new ones plain, for folders
+    // that aren't the journal's own (see `keep_file_format`)
+    keep_format: bool,
+    filename_date_format: String,
+    // Never empty; the config requires at least one
+    extensions: Vec<String>,
+    ascii_filenames: bool,
+    date_from_filename: bool,
+    external: Vec<PathBuf>,
+    // Newest first
+    entries: Vec<JournalEntry>,
+    include_archived: bool,
+    // Files skipped during the last load and why
+    load_errors: Vec<String>,
+}
+
+impl Journal {
+    // Opens (creating if needed) the journal in `dir`; call `load` to read its entries
+    pub fn open(dir: PathBuf, trash_dir: PathBuf, config: &Config) -> Result<Self> {
+        if !dir.exists() {
+            // A dangling symlink or unmounted volume also lands here
+            fs::create_dir_all(&dir)
+                .with_context(|| format!("Journal directory {} is unavailable", dir.display()))?;
+        }
+        
+        let key = if config.encryption.enabled {
+            let passphrase = crypto::resolve_passphrase(config.encryption.passphrase.as_deref()).ok_or_else(|| {
+                anyhow!(
+                    "Entry encryption is enabled but no passphrase was found (set JOURNAL_TUI_PASSPHRASE, or save it in {} as service \"{}\", account \"{}\")",
+                    crate::secrets::STORE_NAME,
+                    crypto::PASSPHRASE_SERVICE,
+                    crypto::PASSPHRASE_ACCOUNT
+                )
+            })?;
+            Some(crypto::EntryKey::for_journal(&passphrase, &dir)?)
+        } else {
+            None
+        };
+        
+        Ok(Self {
+            dir,
+            trash_dir,
+            key,
+            keep_format: false,
+            filename_date_format: config.entries.filename_date_format.clone(),
+            extensions: config.entries.extensions.clone(),
+            ascii_filenames: config.entries.ascii_filenames,
+            date_from_filename: config.entries.date_from_filename,
+            external: config.entries.external.iter().map(|p| config::expand_home(p)).collect(),
+            entries: Vec::new(),
+            include_archived: false,
+            load_errors: Vec::new(),
+        })
+    }
+    
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+    
+    pub fn trash_dir(&self) -> &Path {
+        &self.trash_dir
+    }
+    
+    pub fn extensions(&self) -> &[String] {
+        &self.extensions
+    }
+    
+    pub fn is_encrypted(&self) -> bool {
+        self.key.is_some()
+    }
+    
+    // For a folder opened from outside the vault: someone's plain notes must not come back
+    // encrypted, while files that already are stay that way
+    pub fn keep_file_format(&mut self) {
+        self.keep_format = true;
+    }
+    
+    pub fn entries(&self) -> &[JournalEntry] {
+        &self.entries
+    }
+    
+    pub fn load_errors(&self) -> &[String] {
+        &self.load_errors
+    }
+    
+    // Re-read every entry from disk. Fails only if the journal directory itself can't be
+    // listed; problems with individual files are collected in `load_errors`.
+    pub fn load(&mut self, include_archived: bool) -> Result<()> {
+        self.include_archived = include_archived;
+        self.entries.clear();
+        self.load_errors.clear();
+        
+        let entries = fs::read_dir(&self.dir)
+            .with_context(|| format!("Cannot read journal directory {}", self.dir.display()))?;
+        self.load_dir(entries, false);
+        
+        for path in self.external.clone() {
+            match self.read_entry_file(&path, false) {
+                Ok(Some(entry)) => self.entries.push(entry),
+                Ok(None) => self.load_errors.push(format!("{}: not an entry file (see entries.extensions)", path.display())),
+                Err(err) => self.load_errors.push(format!("{:#}", err)),
+            }
+        }
+        
+        if include_archived {
+            let archive_dir = self.dir.join(ARCHIVE_DIR);
+            match fs::read_dir(&archive_dir) {
+                Ok(entries) => self.load_dir(entries, true),
+                // Nothing has been archived yet
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => self.load_errors.push(format!("{}: {}", archive_dir.display(), err)),
+            }
+        }
+        
+        self.entries.sort_by(list_order);
+        Ok(())
+    }
+    
+    fn load_dir(&mut self, entries: fs::ReadDir, archived: bool) {
+        for entry in entries {
+            let result = entry
+                .map_err(anyhow::Error::from)
+                .and_then(|entry| self.read_entry_file(&entry.path(), archived));
+            match result {
+                Ok(Some(entry)) => self.entries.push(entry),
+                Ok(None) => {}
+                Err(err) => self.load_errors.push(format!("{:#}", err)),
+            }
+        }
+    }
+    
+    // Entries whose title or content contains `query`, case-insensitively
+    pub fn search(&self, query: &str) -> Vec<&JournalEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| {
+                search::contains(&entry.title, query)
+                    || entry.content.as_deref().is_some_and(|c| search::contains(c, query))
+            })
+            .collect()
+    }
+    
+    // Read an entry's text, decrypting it if it was written with per-entry encryption
+    // Strict, for text that may be written back: invalid UTF-8 is an error rather than
+    // being replaced. A leading byte order mark is dropped.
+    pub fn read(&self, path: &Path) -> Result<String> {
+        let text = String::from_utf8(self.read_bytes(path)?)?;
+        Ok(strip_bom(text))
+    }
+    
+    // For display: invalid UTF-8 (e.g. a Latin-1 file) comes back with U+FFFD in place of the
+    // bad bytes, and true alongside so the reader can be told
+    pub fn read_lossy(&self, path: &Path) -> Result<(String, bool)> {
+        let (text, lossy) = match String::from_utf8(self.read_bytes(path)?) {
+            Ok(text) => (text, false),
+            Err(err) => (String::from_utf8_lossy(err.as_bytes()).into_owned(), true),
+        };
+        Ok((strip_bom(text), lossy))
+    }
+    
+    fn read_bytes(&self, path: &Path) -> Result<Vec<u8>> {
+        let data = fs::read(path)?;
+        if !crypto::is_encrypted(&data) {
+            return Ok(data);
+        }
+        
+        let key = self.key.as_ref()
+            .ok_or_else(|| anyhow!("{} is encrypted but entry encryption is disabled", path.display()))?;
+        crypto::decrypt(key, &data)
+    }
+    
+    // External notes stay plain text so whatever else uses them can still read them
+    pub fn write(&self, path: &Path, content: &str) -> Result<()> {
+        let encrypt = !self.is_external(path)
+            && (!self.keep_format || fs::read(path).is_ok_and(|data| crypto::is_encrypted(&data)));
+        match &self.key {
+            Some(key) if encrypt => crypto::encrypt_file(path, key, content),
+            _ => Ok(fs::write(path, content)?),
+        }
+    }
+    
+    // Listed from `entries.external`: not the journal's to rename, archive or trash
+    pub fn is_external(&self, path: &Path) -> bool {
+        self.external.iter().any(|p| p == path)
+    }
+    
+    fn ensure_owned(&self, path: &Path) -> Result<()> {
+        if self.is_external(path) {
+            bail!("{} is an external note; remove it from entries.external instead", path.display());
+        }
+        Ok(())
+    }
+    
+    // Like `create`, with `template` as the body, also returning the 1-based line to start
+    // editing on: the template's `{cursor}`, else the first blank line of its first section,
+    // else the blank line under the heading
+    pub fn create_from_template(&mut self, title: &str, template: &str) -> Result<(PathBuf, usize)> {
+        // The body starts on line 3, after the heading and a blank line
+        const BODY_LINE: usize = 3;
+        
+        let marked = template.lines().position(|line| line.contains(CURSOR_PLACEHOLDER));
+        let body = template.replacen(CURSOR_PLACEHOLDER, "", 1);
+        let line = match marked {
+            Some(index) => BODY_LINE + index,
+            None => {
+                let lines: Vec<&str> = body.lines().collect();
+                match lines.iter().position(|line| line.starts_with('#')) {
+                    Some(heading) => {
+                        let blank = lines[heading + 1..].iter().position(|line| line.trim().is_empty());
+                        BODY_LINE + heading + 1 + blank.unwrap_or(0)
+                    }
+                    None => BODY_LINE - 1,
+                }
+            }
+        };
+        Ok((self.create(title, &body)?, line))
+    }
+    
+    // Title for any entry file, including ones in the trash
+    pub fn title_of(&self, path: &Path) -> String {
+        entry_title(path, self.read_lossy(path).ok().map(|(content, _)| content).as_deref(), &self.filename_date_format)
+    }
+    
+    // Write a new `# title` entry and return its path
+    pub fn create(&mut self, title: &str, body: &str) -> Result<PathBuf> {
+        self.create_at(title, body, Local::now())
+    }
+    
+    // Like `create`, dated `created` in both the filename and the file's modified time,
+    // e.g. for entries imported from elsewhere
+    pub fn create_at(&mut self, title: &str, body: &str, created: DateTime<Local>) -> Result<PathBuf> {
+        let title = normalize_title(title, self.ascii_filenames);
+        let timestamp = created.format(&self.filename_date_format);
+        let filename = format!("{}_{}.{}", timestamp, title.slug, self.extensions[0]);
+        let path = trash::unique_path(&self.dir.join(filename));
+        
+        self.write(&path, &format!("# {}\n\n{}", title.display, body))?;
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .and_then(|file| file.set_modified(created.into()))
+            .with_context(|| format!("Failed to set the date of {}", path.display()))?;
+        self.refresh(&path, false);
+        Ok(path)
+    }
+    
+    // Rename an entry so the part after its timestamp matches its current title, e.g. after
+    // the `# ` heading was edited. Files without a timestamp prefix, or already in sync
+    // (including a numeric collision suffix), keep their name. Returns the entry's path.
+    pub fn sync_filename(&mut self, path: &Path) -> Result<PathBuf> {
+        if self.is_external(path) {
+            return Ok(path.to_path_buf());
+        }
+        let Some(wanted) = self.synced_stem(path, &self.read(path)?) else {
+            return Ok(path.to_path_buf());
+        };
+        
+        let parent = path.parent().unwrap_or(&self.dir);
+        let ext = path.extension().and_then(|s| s.to_str()).unwrap_or(&self.extensions[0]);
+        let target = trash::unique_path(&parent.join(format!("{}.{}", wanted, ext)));
+        fs::rename(path, &target)
+            .with_context(|| format!("Failed to rename {} to {}", path.display(), target.display()))?;
+        let archived = self.entries.iter().any(|e| e.path == path && e.archived);
+        self.entries.retain(|e| e.path != path);
+        self.refresh(&target, archived);
+        Ok(target)
+    }
+    
+    // The filename stem `path` should have for the title in `content`, or None if it
+    // already has it or doesn't follow the timestamp-and-title pattern
+    fn synced_stem(&self, path: &Path, content: &str) -> Option<String> {
+        let stem = path.file_stem()?.to_str()?;
+        let (_, rest) = format::parse_filename_date(stem, &self.filename_date_format)?;
+        let title = content_title(path, content)?;
+        
+        let prefix = &stem[..stem.len() - rest.len()];
+        let wanted = format!("{}_{}", prefix, normalize_title(&title, self.ascii_filenames).slug);
+        let in_sync = stem == wanted
+            || stem.strip_prefix(wanted.as_str())
+                .and_then(|suffix| suffix.strip_prefix('_'))
+                .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+        (!in_sync).then_some(wanted)
+    }
+    
+    // Problems with entry files, archived ones included, that loading would skip or
+    // quietly paper over. Reads from disk rather than the loaded list so nothing is missed.
+    pub fn audit(&self) -> Vec<Issue> {
+        let mut issues = Vec::new();
+        for dir in [self.dir.clone(), self.dir.join(ARCHIVE_DIR)] {
+            let Ok(files) = fs::read_dir(&dir) else {
+                continue;
+            };
+            let mut paths: Vec<PathBuf> = files
+                .filter_map(|f| f.ok().map(|f| f.path()))
+                .filter(|p| p.is_file() && has_entry_extension(p, &self.extensions))
+                .collect();
+            paths.sort();
+            
+            for path in paths {
+                if let Some(kind) = self.audit_file(&path) {
+                    issues.push(Issue { path, kind });
+                }
+            }
+        }
+        issues
+    }
+    
+    fn audit_file(&self, path: &Path) -> Option<IssueKind> {
+        if fs::metadata(path).is_ok_and(|m| m.len() == 0) {
+            return Some(IssueKind::Empty);
+        }
+        let content = match self.read(path) {
+            Ok(content) => content,
+            Err(err) if err.downcast_ref::<std::string::FromUtf8Error>().is_some() => {
+                return Some(IssueKind::InvalidUtf8);
+            }
+            Err(err) => return Some(IssueKind::Unreadable(format!("{:#}", err))),
+        };
+        if content_title(path, &content).is_none() {
+            return Some(IssueKind::MissingTitle);
+        }
+        let ext = path.extension().and_then(|s| s.to_str()).unwrap_or_default();
+        self.synced_stem(path, &content).map(|stem| IssueKind::NameMismatch(format!("{}.{}", stem, ext)))
+    }
+    
+    // Apply the fix for an issue found by `audit`, returning what was done, or None when
+    // it needs a person: unreadable files are left alone rather than risk losing text.
+    pub fn fix(&mut self, issue: &Issue) -> Result<Option<String>> {
+        match &issue.kind {
+            IssueKind::Empty => {
+                let trashed = self.delete(&issue.path)?;
+                Ok(Some(format!("moved to trash as {}", trashed.display())))
+            }
+            IssueKind::MissingTitle => {
+                let title = title_from_stem(
+                    issue.path.file_stem().and_then(|s| s.to_str()).unwrap_or("Untitled"),
+                    &self.filename_date_format,
+                );
+                let content = self.read(&issue.path)?;
+                // The heading goes after any frontmatter so that stays first
+                let (frontmatter_len, body) = match frontmatter::parse(&content) {
+                    (Some(_), body) => (content.len() - body.len(), body),
+                    (None, body) => (0, body),
+                };
+                let fixed = format!("{}# {}\n\n{}", &content[..frontmatter_len], title, body);
+                self.write(&issue.path, &fixed)?;
+                Ok(Some(format!("added the title \"{}\"", title)))
+            }
+            IssueKind::NameMismatch(_) => {
+                let renamed = self.sync_filename(&issue.path)?;
+                Ok(Some(format!("renamed to {}", renamed.display())))
+            }
+            IssueKind::InvalidUtf8 | IssueKind::Unreadable(_) => Ok(None),
+        }
+    }
+    
+    // Append a timestamped bullet to today's day file, creating it if needed.
+    // Returns the day's name.
+    pub fn append_to_today(&mut self, line: &str) -> Result<String> {
+        let now = Local::now();
+        let day = now.format("%Y-%m-%d").to_string();
+        // Keep appending to a day file written under an earlier extension setting
+        let path = self.extensions.iter()
+            .map(|ext| self.dir.join(format!("{}.{}", day, ext)))
+            .find(|path| path.exists())
+            .unwrap_or_else(|| self.dir.join(format!("{}.{}", day, self.extensions[0])));
+        
+        let mut content = if path.exists() {
+            self.read(&path)?
+        } else {
+            format!("# {}\n\n", day)
+        };
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push_str(&format!("- {} {}\n", now.format("%H:%M"), line.trim()));
+        
+        self.write(&path, &content)?;
+        self.refresh(&path, false);
+        Ok(day)
+    }
+    
+    // Move an entry to the trash, returning where it went
+    pub fn delete(&mut self, path: &Path) -> Result<PathBuf> {
+        self.ensure_owned(path)?;
+        let trashed = trash::move_to_trash(path, &self.trash_dir)?;
+        self.entries.retain(|e| e.path != path);
+        Ok(trashed)
+    }
+    
+    // Put a trashed entry back at its original path, or beside it if something has taken it
+    // since. Returns where it went.
+    pub fn undo_delete(&mut self, trashed: &Path, original: &Path) -> Result<PathBuf> {
+        let path = trash::unique_path(original);
+        fs::rename(trashed, &path)?;
+        trash::forget(trashed);
+        let archived = path.parent().is_some_and(|p| p.ends_with(ARCHIVE_DIR));
+        self.refresh(&path, archived);
+        Ok(path)
+    }
+    
+    // Restore an entry chosen from the trash browser into the journal
+    pub fn restore_from_trash(&mut self, trashed: &Path) -> Result<PathBuf> {
+        let path = trash::restore(trashed, &self.dir)?;
+        self.refresh(&path, false);
+        Ok(path)
+    }
+    
+    // Move loaded entries created before `cutoff` to the trash, appending each to the prune log.
+    // Returns the original paths.
+    pub fn prune_older_than(&mut self, cutoff: NaiveDate) -> Result<Vec<PathBuf>> {
+        let stale: Vec<PathBuf> = self.entries
+            .iter()
+            .filter(|e| e.created.date_naive() < cutoff && !e.external)
+            .map(|e| e.path.clone())
+            .collect();
+        if stale.is_empty() {
+            return Ok(stale);
+        }
+        
+        fs::create_dir_all(&self.trash_dir)?;
+        let mut log = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.trash_dir.join(PRUNE_LOG))?;
+        let now = Local::now().to_rfc3339();
+        for path in &stale {
+            let trashed = self.delete(path)?;
+            writeln!(log, "{}\tolder than {}\t{}\t-> {}", now, cutoff, path.display(), trashed.display())?;
+        }
+        
+        Ok(stale)
+    }
+    
+    pub fn trashed(&self) -> Result<Vec<trash::TrashedEntry>> {
+        trash::list(&self.trash_dir, &self.extensions, |p| Some(self.title_of(p)))
+    }
+    
+    // Group loaded entries whose normalized content hashes identically; each group is oldest
+    // first, newest group first
+    pub fn find_duplicates(&self) -> Vec<Vec<PathBuf>> {
+        let mut groups: HashMap<[u8; 32], Vec<&JournalEntry>> = HashMap::new();
+        // External notes can't be trashed, so they're never offered as the extra copy
+        for entry in self.entries.iter().filter(|e| !e.external) {
+            let Some(content) = &entry.content else {
+                continue;
+            };
+            
+            // Ignore line-ending and trailing whitespace differences between machines
+            let normalized: Vec<&str> = content.lines().map(str::trim_end).collect();
+            let digest = Sha256::digest(normalized.join("\n").trim_end().as_bytes());
+            groups.entry(digest.into()).or_default().push(entry);
+        }
+        
+        let mut duplicates: Vec<Vec<&JournalEntry>> = groups.into_values()
+            .filter(|group| group.len() > 1)
+            .collect();
+        for group in &mut duplicates {
+            group.sort_by_key(|e| e.created);
+        }
+        duplicates.sort_by_key(|group| std::cmp::Reverse(group[0].created));
+        
+        duplicates.into_iter()
+            .map(|group| group.into_iter().map(|e| e.path.clone()).collect())
+            .collect()
+    }
+    
+    // Move an entry into or out of the archive, returning its new path
+    pub fn set_archived(&mut self, path: &Path, archived: bool) -> Result<PathBuf> {
+        self.ensure_owned(path)?;
+        let target_dir = if archived {
+            self.dir.join(ARCHIVE_DIR)
+        } else {
+            self.dir.clone()
+        };
+        fs::create_dir_all(&target_dir)?;
+        let file_name = path.file_name()
+            .ok_or_else(|| anyhow!("Invalid entry path: {}", path.display()))?;
+        let target = trash::unique_path(&target_dir.join(file_name));
+        fs::rename(path, &target)?;
+        
+        self.entries.retain(|e| e.path != path);
+        self.refresh(&target, archived);
+        Ok(target)
+    }
+    
+    // Re-read one entry after it was written, keeping the list sorted
+    fn refresh(&mut self, path: &Path, archived: bool) {
+        self.entries.retain(|e| e.path != path);
+        if archived && !self.include_archived {
+            return;
+        }
+        if let Ok(Some(entry)) = self.read_entry_file(path, archived) {
+            let index = self.entries.partition_point(|e| list_order(e, &entry).is_lt());
+            self.entries.insert(index, entry);
+        }
+    }
+    
+    // Ok(None) for files that aren't entries. An unreadable body still yields an entry,
+    // with the reason in `error`, so it stays visible in the list.
+    fn read_entry_file(&self, path: &Path, archived: bool) -> Result<Option<JournalEntry>> {
+        if !has_entry_extension(path, &self.extensions) {
+            return Ok(None);
+        }
+        
+        let metadata = fs::metadata(path)
+            .with_context(|| format!("{}", path.display()))?;
+        // Not every filesystem records a birth time
+        let modified = metadata.modified()
+            .with_context(|| format!("{}: no timestamp", path.display()))?;
+        // Copies and syncs reset the birth time, so the timestamp the entry was named with wins
+        let named = path.file_stem()
+            .and_then(|s| s.to_str())
+            .filter(|_| self.date_from_filename)
+            .and_then(|stem| format::parse_filename_date(stem, &self.filename_date_format))
+            .and_then(|(date, _)| Local.from_local_datetime(&date).earliest());
+        let created = match named {
+            Some(named) => named,
+            None => DateTime::<Local>::from(metadata.created().unwrap_or(modified)),
+        };
+        
+        let (content, error) = match self.read_lossy(path) {
+            Ok((content, false)) => (Some(content), None),
+            // Shown anyway; the note explains the replacement characters
+            Ok((content, true)) => (Some(content), Some("not valid UTF-8; unreadable bytes are shown as �".to_string())),
+            Err(err) => (None, Some(format!("{:#}", err))),
+        };
+        let title = entry_title(path, content.as_deref(), &self.filename_date_format);
+        let frontmatter = content.as_deref().and_then(|c| frontmatter::parse(c).0);
+        let color = frontmatter.as_ref()
+            .and_then(|fm| fm.get("color"))
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty());
+        // A few characters at most, so the list stays aligned
+        let icon = frontmatter.as_ref()
+            .and_then(|fm| fm.get("icon"))
+            .map(|icon| icon.chars().take(MAX_ICON_CHARS).collect::<String>())
+            .filter(|icon| !icon.trim().is_empty());
+        let tags = frontmatter.as_ref().and_then(|fm| fm.get("tags")).map(parse_tags).unwrap_or_default();
+        let locked = frontmatter.as_ref()
+            .and_then(|fm| fm.get("locked"))
+            .is_some_and(|value| matches!(value.trim().to_lowercase().as_str(), "true" | "yes"));
+        Ok(Some(JournalEntry {
+            title,
+            path: path.to_path_buf(),
+            created,
+            modified: DateTime::<Local>::from(modified),
+            size: metadata.len(),
+            content,
+            error,
+            archived,
+            color,
+            icon,
+            tags,
+            locked,
+            external: self.is_external(path),
+        }))
+    }
+}
+
+// Editors on Windows often start UTF-8 files with U+FEFF, which would hide a `# ` heading
+fn strip_bom(text: String) -> String {
+    match text.strip_prefix('\u{feff}') {
+        Some(rest) => rest.to_string(),
+        None => text,
+    }
+}
+
+// "[work, #Ideas]" -> ["work", "ideas"]; a leading # is allowed, as in hashtags
+fn parse_tags(value: &str) -> Vec<String> {
+    let value = value.trim();
+    let value = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')).unwrap_or(value);
+    let mut tags: Vec<String> = Vec::new();
+    for tag in value.split(',') {
+        let tag = tag.trim().trim_matches(['"', '\'']).trim_start_matches('#').trim().to_lowercase();
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+// Whether `path` ends in one of the configured entry extensions, ignoring case
+pub fn has_entry_extension(path: &Path, extensions: &[String]) -> bool {
+    path.extension()
+        .and_then(|s| s.to_str())
+        .is_some_and(|ext| extensions.iter().any(|e| e.eq_ignore_ascii_case(ext)))
+}
+
+// The title written in the entry itself. Plain-text notes rarely have a `# ` heading,
+// so anything that isn't markdown falls back to its first non-blank line.
+fn content_title(path: &Path, content: &str) -> Option<String> {
+    let markdown = path.extension()
+        .and_then(|s| s.to_str())
+        .is_some_and(|ext| ["md", "markdown"].iter().any(|m| m.eq_ignore_ascii_case(ext)));
+    frontmatter::title(content).or_else(|| {
+        if markdown {
+            return None;
+        }
+        content.lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .map(|line| line.chars().take(MAX_LINE_TITLE_CHARS).collect::<String>().trim_end().to_string())
+    })
+}
+
+// Newest first. read_dir order isn't stable, so entries created in the same second fall
+// back to their path; otherwise they could swap places between refreshes.
+fn list_order(a: &JournalEntry, b: &JournalEntry) -> std::cmp::Ordering {
+    b.created.cmp(&a.created).then_with(|| a.path.cmp(&b.path))
+}
+
+// Title precedence: frontmatter `title:`, then the first `# ` heading (first line for plain
+// text), then the filename
+pub fn entry_title(path: &Path, content: Option<&str>, filename_format: &str) -> String {
+    content
+        .and_then(|content| content_title(path, content))
+        .or_else(|| path.file_stem().and_then(|s| s.to_str()).map(|stem| title_from_stem(stem, filename_format)))
+        .unwrap_or_default()
+}
+
+// A title as typed, in the two forms an entry uses
+pub struct Title {
+    // For the filename: whitespace as `_`, path separators as `-`
+    pub slug: String,
+    // For the `# ` heading: whitespace runs (tabs, non-breaking spaces, ...) as one space
+    pub display: String,
+}
+
+// With `ascii`, accented Latin letters in the slug lose their accents and anything else
+// outside ASCII is dropped, falling back to "untitled" when nothing is left (e.g. a CJK title)
+pub fn normalize_title(title: &str, ascii: bool) -> Title {
+    let display = title.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut slug = String::new();
+    for c in display.chars() {
+        match c {
+            ' ' => slug.push('_'),
+            '/' | '\\' => slug.push('-'),
+            c if c.is_control() => {}
+            c if !ascii || c.is_ascii() => slug.push(c),
+            c => slug.push_str(fold_to_ascii(c)),
+        }
+    }
+    if ascii {
+        // Dropped characters can leave separators side by side or at either end
+        while slug.contains("__") {
+            slug = slug.replace("__", "_");
+        }
+        slug = slug.trim_matches('_').to_string();
+        if slug.is_empty() {
+            slug = "untitled".to_string();
+        }
+    }
+    Title { slug, display }
+}
+
+// The ASCII spelling of common accented Latin letters; empty for anything else
+fn fold_to_ascii(c: char) -> &'static str {
+    match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' | 'Ā' | 'Ă' | 'Ą' => "A",
+        'æ' => "ae",
+        'Æ' => "AE",
+        'ç' | 'ć' | 'č' => "c",
+        'Ç' | 'Ć' | 'Č' => "C",
+        'ď' | 'đ' | 'ð' => "d",
+        'Ď' | 'Đ' | 'Ð' => "D",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ė' | 'ę' | 'ě' => "e",
+        'È' | 'É' | 'Ê' | 'Ë' | 'Ē' | 'Ė' | 'Ę' | 'Ě' => "E",
+        'ğ' => "g",
+        'Ğ' => "G",
+        'ì' | 'í' | 'î' | 'ï' | 'ī' | 'į' | 'ı' => "i",
+        'Ì' | 'Í' | 'Î' | 'Ï' | 'Ī' | 'Į' | 'İ' => "I",
+        'ł' | 'ľ' | 'ĺ' => "l",
+        'Ł' | 'Ľ' | 'Ĺ' => "L",
+        'ñ' | 'ń' | 'ň' => "n",
+        'Ñ' | 'Ń' | 'Ň' => "N",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ő' => "o",
+        'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' | 'Ø' | 'Ō' | 'Ő' => "O",
+        'œ' => "oe",
+        'Œ' => "OE",
+        'ř' | 'ŕ' => "r",
+        'Ř' | 'Ŕ' => "R",
+        'ß' => "ss",
+        'ś' | 'š' | 'ş' => "s",
+        'Ś' | 'Š' | 'Ş' => "S",
+        'ť' | 'ţ' => "t",
+        'Ť' | 'Ţ' => "T",
+        'þ' => "th",
+        'Þ' => "TH",
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' | 'ű' | 'ų' => "u",
+        'Ù' | 'Ú' | 'Û' | 'Ü' | 'Ū' | 'Ů' | 'Ű' | 'Ų' => "U",
+        'ý' | 'ÿ' => "y",
+        'Ý' | 'Ÿ' => "Y",
+        'ź' | 'ż' | 'ž' => "z",
+        'Ź' | 'Ż' | 'Ž' => "Z",
+        _ => "",
+    }
+}
+
+// "20240101_093000_Morning_pages" -> "Morning pages", using the configured timestamp prefix
+fn title_from_stem(stem: &str, filename_format: &str) -> String {
+    let Some((_, rest)) = format::parse_filename_date(stem, filename_format) else {
+        return stem.to_string();
+    };
+    let title = rest.replace('_', " ");
+    let title = title.trim();
+    if title.is_empty() {
+        stem.to_string()
+    } else {
+        title.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn slug(title: &str, ascii: bool) -> String {
+        normalize_title(title, ascii).slug
+    }
+    
+    // A journal in a fresh temporary directory, with its trash alongside
+    fn temp_journal() -> (tempfile::TempDir, Journal) {
+        let root = tempfile::tempdir().unwrap();
+        let journal = Journal::open(root.path().join("entries"), root.path().join(".trash"), &Config::default()).unwrap();
+        (root, journal)
+    }
+    
+    fn listed_names(journal: &Journal) -> Vec<String> {
+        journal.entries().iter().map(|e| e.path.file_name().unwrap().to_string_lossy().into_owned()).collect()
+    }
+    
+    #[test]
+    fn accented_titles_fold_only_when_ascii() {
+        assert_eq!(slug("Café crème", false), "Café_crème");
+        assert_eq!(slug("Café crème", true), "Cafe_creme");
+        assert_eq!(slug("Straße Œuvre", true), "Strasse_OEuvre");
+        // A combining accent is dropped, leaving its base letter
+        assert_eq!(slug("Cafe\u{301}", true), "Cafe");
+    }
+    
+    #[test]
+    fn cjk_titles_keep_their_characters_unless_ascii() {
+        assert_eq!(slug("日本 旅行", false), "日本_旅行");
+        assert_eq!(slug("日本 travel", true), "travel");
+    }
+    
+    #[test]
+    fn all_non_ascii_titles_fall_back_to_untitled() {
+        assert_eq!(slug("日本語", true), "untitled");
+        assert_eq!(slug("🎉🎉", true), "untitled");
+        assert_eq!(slug("日本語", false), "日本語");
+    }
+    
+    #[test]
+    fn emoji_are_kept_or_dropped_without_doubling_separators() {
+        assert_eq!(slug("Trip 🎉 2024", false), "Trip_🎉_2024");
+        assert_eq!(slug("Trip 🎉 2024", true), "Trip_2024");
+        assert_eq!(slug("🎉 Party", true), "Party");
+    }
+    
+    #[test]
+    fn whitespace_runs_become_one_separator() {
+        let title = normalize_title("  Morning\tthoughts\u{a0}\u{a0}today  ", false);
+        assert_eq!(title.display, "Morning thoughts today");
+        assert_eq!(title.slug, "Morning_thoughts_today");
+        assert_eq!(slug("Morning\tthoughts\u{a0}today", true), "Morning_thoughts_today");
+    }
+    
+    #[test]
+    fn path_separators_and_controls_never_reach_the_filename() {
+        assert_eq!(slug("a/b\\c", false), "a-b-c");
+        assert_eq!(slug("bell\u{7}", true), "bell");
+    }
+    
+    #[test]
+    fn display_keeps_the_title_as_typed() {
+        assert_eq!(normalize_title("Café 日本 🎉", true).display, "Café 日本 🎉");
+    }
+    
+    #[test]
+    fn entries_created_together_are_ordered_by_path() {
+        let (_root, mut journal) = temp_journal();
+        for name in ["20240101_120000_b.md", "20240102_090000_c.md", "20240101_120000_a.md"] {
+            fs::write(journal.dir().join(name), "# Entry\n").unwrap();
+        }
+        
+        journal.load(false).unwrap();
+        let first = listed_names(&journal);
+        assert_eq!(first, ["20240102_090000_c.md", "20240101_120000_a.md", "20240101_120000_b.md"]);
+        // Reloading never reorders entries that share a timestamp
+        for _ in 0..5 {
+            journal.load(false).unwrap();
+            assert_eq!(listed_names(&journal), first);
+        }
+    }
+    
+    #[test]
+    fn opening_a_directory_creates_it_and_lists_nothing() {
+        let (root, mut journal) = temp_journal();
+        assert!(root.path().join("entries").is_dir());
+        journal.load(false).unwrap();
+        assert!(journal.entries().is_empty());
+        assert!(journal.load_errors().is_empty());
+    }
+    
+    #[test]
+    fn created_entries_are_listed_and_survive_a_reload() {
+        let (_root, mut journal) = temp_journal();
+        let path = journal.create("Morning thoughts", "Coffee first.").unwrap();
+        assert_eq!(journal.read(&path).unwrap(), "# Morning thoughts\n\nCoffee first.");
+        
+        journal.load(false).unwrap();
+        let [entry] = journal.entries() else {
+            panic!("expected one entry, found {}", journal.entries().len());
+        };
+        assert_eq!(entry.path, path);
+        assert_eq!(entry.title, "Morning thoughts");
+        assert!(entry.content.as_deref().is_some_and(|c| c.contains("Coffee first.")));
+    }
+    
+    #[test]
+    fn deleted_entries_go_to_the_trash_and_can_come_back() {
+        let (root, mut journal) = temp_journal();
+        let path = journal.create("Draft", "").unwrap();
+        
+        let trashed = journal.delete(&path).unwrap();
+        assert!(!path.exists());
+        assert!(trashed.starts_with(root.path().join(".trash")));
+        assert!(journal.entries().is_empty());
+        journal.load(false).unwrap();
+        assert!(journal.entries().is_empty());
+        
+        journal.undo_delete(&trashed, &path).unwrap();
+        assert!(path.exists());
+        assert_eq!(listed_names(&journal).len(), 1);
+    }
+    
+    fn audited(journal: &Journal) -> Vec<(String, String)> {
+        journal.audit().iter()
+            .map(|issue| (issue.path.file_name().unwrap().to_string_lossy().into_owned(), issue.kind.to_string()))
+            .collect()
+    }
+    
+    #[test]
+    fn audit_reports_each_kind_of_problem_and_passes_clean_entries() {
+        let (_root, journal) = temp_journal();
+        let dir = journal.dir().to_path_buf();
+        fs::write(dir.join("20240101_120000_empty.md"), "").unwrap();
+        fs::write(dir.join("20240102_120000_notes.md"), "just some text\n").unwrap();
+        fs::write(dir.join("20240103_120000_old_name.md"), "# New name\n").unwrap();
+        fs::write(dir.join("20240104_120000_binary.md"), [0xff, 0xfe, 0x00]).unwrap();
+        fs::write(dir.join("20240105_120000_Fine.md"), "# Fine\n").unwrap();
+        fs::write(dir.join("20240105_120000_Fine_2.md"), "# Fine\n").unwrap();
+        fs::write(dir.join("20240106_120000_Titled.md"), "---\ntitle: Titled\n---\nbody\n").unwrap();
+        fs::write(dir.join("notes.txt.bak"), "").unwrap();
+        fs::create_dir_all(dir.join(ARCHIVE_DIR)).unwrap();
+        fs::write(dir.join(ARCHIVE_DIR).join("20230101_120000_archived.md"), "").unwrap();
+        
+        assert_eq!(audited(&journal), [
+            ("20240101_120000_empty.md".to_string(), "empty file".to_string()),
+            ("20240102_120000_notes.md".to_string(), "no title".to_string()),
+            ("20240103_120000_old_name.md".to_string(), "filename doesn't match the title (expected 20240103_120000_New_name.md)".to_string()),
+            ("20240104_120000_binary.md".to_string(), "not valid UTF-8".to_string()),
+            ("20230101_120000_archived.md".to_string(), "empty file".to_string()),
+        ]);
+    }
+    
+    #[test]
+    fn fixing_issues_leaves_only_what_needs_a_person() {
+        let (_root, mut journal) = temp_journal();
+        let dir = journal.dir().to_path_buf();
+        fs::write(dir.join("20240101_120000_empty.md"), "").unwrap();
+        fs::write(dir.join("20240102_120000_notes.md"), "---\nmood: ok\n---\njust some text\n").unwrap();
+        fs::write(dir.join("20240103_120000_old_name.md"), "# New name\n").unwrap();
+        fs::write(dir.join("20240104_120000_binary.md"), [0xff, 0xfe, 0x00]).unwrap();
+        journal.load(false).unwrap();
+        
+        for issue in journal.audit() {
+            let fixed = journal.fix(&issue).unwrap();
+            assert_eq!(fixed.is_none(), matches!(issue.kind, IssueKind::InvalidUtf8), "{:?}", issue);
+        }
+        
+        assert_eq!(audited(&journal), [("20240104_120000_binary.md".to_string(), "not valid UTF-8".to_string())]);
+        assert!(!dir.join("20240101_120000_empty.md").exists());
+        assert_eq!(journal.trashed().unwrap().len(), 1);
+        // The heading comes from the filename and goes after the frontmatter
+        let titled = fs::read_to_string(dir.join("20240102_120000_notes.md")).unwrap();
+        assert_eq!(titled, "---\nmood: ok\n---\n# notes\n\njust some text\n");
+        assert!(dir.join("20240103_120000_New_name.md").exists());
+    }
+    
+    #[test]
+    fn undoing_a_delete_keeps_an_entry_written_at_the_same_path_since() {
+        let (_root, mut journal) = temp_journal();
+        let original = journal.dir().join("20240101_120000_a.md");
+        fs::write(&original, "# Deleted\n").unwrap();
+        journal.load(false).unwrap();
+        let trashed = journal.delete(&original).unwrap();
+        fs::write(&original, "# Recreated\n").unwrap();
+        
+        let restored = journal.undo_delete(&trashed, &original).unwrap();
+        assert_eq!(restored, journal.dir().join("20240101_120000_a_1.md"));
+        assert_eq!(fs::read_to_string(&original).unwrap(), "# Recreated\n");
+        assert_eq!(fs::read_to_string(&restored).unwrap(), "# Deleted\n");
+        assert!(journal.trashed().unwrap().is_empty());
+    }
+    
+    #[test]
+    fn titles_fall_back_to_the_filename() {
+        let format = "%Y%m%d_%H%M%S";
+        let path = Path::new("/j/20240101_120000_Morning_walk.md");
+        assert_eq!(entry_title(path, Some("---\ntitle: From fm\n---\ntext\n"), format), "From fm");
+        assert_eq!(entry_title(path, Some("---\nmood: ok\n---\nNo heading\n"), format), "Morning walk");
+        assert_eq!(entry_title(path, Some(""), format), "Morning walk");
+        assert_eq!(entry_title(path, None, format), "Morning walk");
+        // A stem that's only the date, or doesn't match the format, is used whole
+        assert_eq!(entry_title(Path::new("/j/20240101_120000.md"), None, format), "20240101_120000");
+        assert_eq!(entry_title(Path::new("/j/notes.md"), Some("plain"), format), "notes");
+    }
+    
+    #[test]
+    fn duplicates_ignore_line_endings_and_trailing_whitespace() {
+        let (_root, mut journal) = temp_journal();
+        let dir = journal.dir().to_path_buf();
+        fs::write(dir.join("20240101_120000_a.md"), "# Same\n\ntext\n").unwrap();
+        fs::write(dir.join("20240102_120000_b.md"), "# Same\r\n\r\ntext  \r\n\n").unwrap();
+        fs::write(dir.join("20240103_120000_c.md"), "# Same\n\nother text\n").unwrap();
+        fs::write(dir.join("20240104_120000_d.md"), "# Same\n\n text\n").unwrap();
+        journal.load(false).unwrap();
+        
+        assert_eq!(journal.find_duplicates(), [vec![dir.join("20240101_120000_a.md"), dir.join("20240102_120000_b.md")]]);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,421 @@
+use anyhow::{anyhow, bail, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+// Actions that can be rebound from the [keybindings] config section. All but `Panic` only
+// apply in the normal list view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    Down,
+    Up,
+    Top,
+    Bottom,
+    Create,
+    Open,
+    Delete,
+    Undo,
+    Trash,
+    ToggleTimestamps,
+    Capture,
+    Search,
+    ClearSearch,
+    ShrinkList,
+    GrowList,
+    TogglePreview,
+    Duplicates,
+    View,
+    TodayFilter,
+    Archive,
+    ToggleArchived,
+    NextDay,
+    PreviousDay,
+    Refresh,
+    WordReport,
+    Mark,
+    Random,
+    ToggleWrap,
+    PanLeft,
+    PanRight,
+    CopyEntry,
+    CreateDated,
+    NextMatch,
+    PreviousMatch,
+    EditInApp,
+    EditLast,
+    PageDown,
+    PageUp,
+    Tags,
+    CompactList,
+    OpenDir,
+    Bookmark,
+    // Checked before anything else, in every mode
+    Panic,
+}
+
+impl Action {
+    const ALL: &'static [Action] = &[
+        Action::Quit,
+        Action::Down,
+        Action::Up,
+        Action::Top,
+        Action::Bottom,
+        Action::Create,
+        Action::Open,
+        Action::Delete,
+        Action::Undo,
+        Action::Trash,
+        Action::ToggleTimestamps,
+        Action::Capture,
+        Action::Search,
+        Action::ClearSearch,
+        Action::ShrinkList,
+        Action::GrowList,
+        Action::TogglePreview,
+        Action::Duplicates,
+        Action::View,
+        Action::TodayFilter,
+        Action::Archive,
+        Action::ToggleArchived,
+        Action::NextDay,
+        Action::PreviousDay,
+        Action::Refresh,
+        Action::WordReport,
+        Action::Mark,
+        Action::Random,
+        Action::ToggleWrap,
+        Action::PanLeft,
+        Action::PanRight,
+        Action::CopyEntry,
+        Action::CreateDated,
+        Action::NextMatch,
+        Action::PreviousMatch,
+        Action::EditInApp,
+        Action::EditLast,
+        Action::PageDown,
+        Action::PageUp,
+        Action::Tags,
+        Action::CompactList,
+        Action::OpenDir,
+        Action::Bookmark,
+        Action::Panic,
+    ];
+    
+    // The name used for this action in the config file
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Down => "down",
+            Action::Up => "up",
+            Action::Top => "top",
+            Action::Bottom => "bottom",
+            Action::Create => "create",
+            Action::Open => "open",
+            Action::Delete => "delete",
+            Action::Undo => "undo",
+            Action::Trash => "trash",
+            Action::ToggleTimestamps => "toggle_timestamps",
+            Action::Capture => "capture",
+            Action::Search => "search",
+            Action::ClearSearch => "clear_search",
+            Action::ShrinkList => "shrink_list",
+            Action::GrowList => "grow_list",
+            Action::TogglePreview => "toggle_preview",
+            Action::Duplicates => "duplicates",
+            Action::View => "view",
+            Action::TodayFilter => "today_filter",
+            Action::Archive => "archive",
+            Action::ToggleArchived => "toggle_archived",
+            Action::NextDay => "next_day",
+            Action::PreviousDay => "previous_day",
+            Action::Refresh => "refresh",
+            Action::WordReport => "word_report",
+            Action::Mark => "mark",
+            Action::Random => "random",
+            Action::ToggleWrap => "toggle_wrap",
+            Action::PanLeft => "pan_left",
+            Action::PanRight => "pan_right",
+            Action::CopyEntry => "copy_entry",
+            Action::CreateDated => "create_dated",
+            Action::NextMatch => "next_match",
+            Action::PreviousMatch => "previous_match",
+            Action::EditInApp => "edit_in_app",
+            Action::EditLast => "edit_last",
+            Action::PageDown => "page_down",
+            Action::PageUp => "page_up",
+            Action::Tags => "tags",
+            Action::CompactList => "compact_list",
+            Action::OpenDir => "open_dir",
+            Action::Bookmark => "bookmark",
+            Action::Panic => "panic",
+        }
+    }
+    
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|action| action.name() == name)
+    }
+    
+    fn default_keys(self) -> &'static [&'static str] {
+        match self {
+            Action::Quit => &["q"],
+            Action::Down => &["j", "down"],
+            Action::Up => &["k", "up"],
+            Action::Top => &["g", "home"],
+            Action::Bottom => &["G", "end"],
+            Action::Create => &["c"],
+            Action::Open => &["enter"],
+            Action::Delete => &["d"],
+            Action::Undo => &["u"],
+            Action::Trash => &["X"],
+            Action::ToggleTimestamps => &["t"],
+            Action::Capture => &["a"],
+            Action::Search => &["/"],
+            Action::ClearSearch => &["esc"],
+            Action::ShrinkList => &["<"],
+            Action::GrowList => &[">"],
+            Action::TogglePreview => &["p"],
+            Action::Duplicates => &["D"],
+            Action::View => &["v"],
+            Action::TodayFilter => &["T"],
+            Action::Archive => &["A"],
+            Action::ToggleArchived => &["H"],
+            Action::NextDay => &["}"],
+            Action::PreviousDay => &["{"],
+            Action::Refresh => &["R"],
+            Action::WordReport => &["W"],
+            Action::Mark => &["space"],
+            Action::Random => &["?"],
+            Action::ToggleWrap => &["w"],
+            Action::PanLeft => &["h"],
+            Action::PanRight => &["l"],
+            Action::CopyEntry => &["Y"],
+            Action::CreateDated => &["C"],
+            Action::NextMatch => &["n"],
+            Action::PreviousMatch => &["N"],
+            Action::EditInApp => &["e"],
+            Action::EditLast => &["."],
+            Action::PageDown => &["pagedown"],
+            Action::PageUp => &["pageup"],
+            Action::Tags => &["#"],
+            Action::CompactList => &["m"],
+            Action::OpenDir => &["O"],
+            Action::Bookmark => &["b"],
+            Action::Panic => &["ctrl-l"],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyCombo {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyCombo {
+    // Shift is already reflected in the character itself, and terminals disagree on
+    // whether they report it, so it's dropped for printable keys
+    pub fn from_event(key: &KeyEvent) -> Self {
+        let mut modifiers = key.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        if matches!(key.code, KeyCode::Char(_)) {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        Self { code: key.code, modifiers }
+    }
+    
+    // Specs look like "j", "G", "enter", "ctrl-n" or "alt-down"
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = spec;
+        loop {
+            let lower = rest.to_ascii_lowercase();
+            let (modifier, len) = if lower.starts_with("ctrl-") {
+                (KeyModifiers::CONTROL, 5)
+            } else if lower.starts_with("alt-") {
+                (KeyModifiers::ALT, 4)
+            } else if lower.starts_with("shift-") {
+                (KeyModifiers::SHIFT, 6)
+            } else {
+                break;
+            };
+            modifiers |= modifier;
+            rest = &rest[len..];
+        }
+        
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "space" => KeyCode::Char(' '),
+                "tab" => KeyCode::Tab,
+                "backspace" => KeyCode::Backspace,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "delete" | "del" => KeyCode::Delete,
+                _ => bail!("Unknown key '{}'", spec),
+            },
+        };
+        
+        // "shift-g" means "G"; other shifted printable keys are written as the character
+        if let KeyCode::Char(c) = code
+            && modifiers.contains(KeyModifiers::SHIFT)
+        {
+            modifiers.remove(KeyModifiers::SHIFT);
+            return Ok(Self { code: KeyCode::Char(c.to_ascii_uppercase()), modifiers });
+        }
+        
+        Ok(Self { code, modifiers })
+    }
+    
+    // Keys the main loop handles before the keymap, so binding them would never fire
+    fn reserved_for(self) -> Option<&'static str> {
+        match self.code {
+            KeyCode::Char('q' | 'c') if self.modifiers.contains(KeyModifiers::CONTROL) => Some("force quit"),
+            KeyCode::Char('Q') if self.modifiers.is_empty() => Some("force quit"),
+            KeyCode::Char('1'..='9') if self.modifiers.contains(KeyModifiers::ALT) => Some("jumping to bookmarks"),
+            _ => None,
+        }
+    }
+}
+
+// A binding in the config is either a single key or a list of keys
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum KeySpec {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeySpec {
+    fn keys(&self) -> Vec<&str> {
+        match self {
+            KeySpec::One(key) => vec![key.as_str()],
+            KeySpec::Many(keys) => keys.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+pub struct Keymap {
+    bindings: HashMap<KeyCombo, Action>,
+}
+
+impl Keymap {
+    // Configured actions replace their default keys; everything else keeps the defaults
+    pub fn from_config(overrides: &BTreeMap<String, KeySpec>) -> Result<Self> {
+        let mut configured = HashMap::new();
+        for (name, spec) in overrides {
+            let action = Action::from_name(name)
+                .ok_or_else(|| anyhow!("Unknown action '{}' in [keybindings]", name))?;
+            configured.insert(action, spec.keys());
+        }
+        
+        let mut bindings = HashMap::new();
+        for &action in Action::ALL {
+            let keys = configured.get(&action)
+                .cloned()
+                .unwrap_or_else(|| action.default_keys().to_vec());
+            for key in keys {
+                let combo = KeyCombo::parse(key)
+                    .map_err(|e| anyhow!("{} in [keybindings] for '{}'", e, action.name()))?;
+                if let Some(purpose) = combo.reserved_for() {
+                    bail!("Key '{}' for '{}' in [keybindings] is reserved for {}", key, action.name(), purpose);
+                }
+                if let Some(existing) = bindings.insert(combo, action)
+                    && existing != action
+                {
+                    bail!(
+                        "Key '{}' is bound to both '{}' and '{}' in [keybindings]",
+                        key,
+                        existing.name(),
+                        action.name()
+                    );
+                }
+            }
+        }
+        
+        Ok(Self { bindings })
+    }
+    
+    pub fn action_for(&self, key: &KeyEvent) -> Option<Action> {
+        self.bindings.get(&KeyCombo::from_event(key)).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn combo(code: KeyCode, modifiers: KeyModifiers) -> KeyCombo {
+        KeyCombo { code, modifiers }
+    }
+    
+    fn bindings(pairs: &[(&str, &str)]) -> BTreeMap<String, KeySpec> {
+        pairs.iter().map(|(action, key)| (action.to_string(), KeySpec::One(key.to_string()))).collect()
+    }
+    
+    fn press(keymap: &Keymap, code: KeyCode) -> Option<Action> {
+        keymap.action_for(&KeyEvent::new(code, KeyModifiers::NONE))
+    }
+    
+    #[test]
+    fn parses_modifiers_and_named_keys() {
+        assert_eq!(KeyCombo::parse("j").unwrap(), combo(KeyCode::Char('j'), KeyModifiers::NONE));
+        assert_eq!(KeyCombo::parse("ctrl-n").unwrap(), combo(KeyCode::Char('n'), KeyModifiers::CONTROL));
+        assert_eq!(KeyCombo::parse("Ctrl-Alt-x").unwrap(), combo(KeyCode::Char('x'), KeyModifiers::CONTROL | KeyModifiers::ALT));
+        assert_eq!(KeyCombo::parse("alt-down").unwrap(), combo(KeyCode::Down, KeyModifiers::ALT));
+        assert_eq!(KeyCombo::parse("shift-tab").unwrap(), combo(KeyCode::Tab, KeyModifiers::SHIFT));
+        assert_eq!(KeyCombo::parse("Enter").unwrap(), combo(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(KeyCombo::parse("space").unwrap(), combo(KeyCode::Char(' '), KeyModifiers::NONE));
+        assert_eq!(KeyCombo::parse("pagedown").unwrap(), combo(KeyCode::PageDown, KeyModifiers::NONE));
+    }
+    
+    #[test]
+    fn shift_with_a_letter_is_the_capital() {
+        assert_eq!(KeyCombo::parse("shift-g").unwrap(), KeyCombo::parse("G").unwrap());
+        assert_eq!(KeyCombo::parse("shift-g").unwrap(), KeyCombo::from_event(&KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT)));
+    }
+    
+    #[test]
+    fn unknown_keys_are_rejected() {
+        for spec in ["", "ctrl-", "f13", "enterr", "hyper-x"] {
+            assert!(KeyCombo::parse(spec).is_err(), "{:?}", spec);
+        }
+    }
+    
+    #[test]
+    fn an_override_replaces_the_default_keys() {
+        let keymap = Keymap::from_config(&bindings(&[("delete", "x")])).unwrap();
+        assert_eq!(press(&keymap, KeyCode::Char('x')), Some(Action::Delete));
+        assert_eq!(press(&keymap, KeyCode::Char('d')), None);
+        assert_eq!(press(&keymap, KeyCode::Char('j')), Some(Action::Down));
+        
+        let keymap = Keymap::from_config(&BTreeMap::from([("down".to_string(), KeySpec::Many(vec!["n".into(), "ctrl-n".into()]))]));
+        assert!(keymap.is_err(), "n is still next_match");
+    }
+    
+    #[test]
+    fn a_key_bound_to_two_actions_is_a_conflict() {
+        let message = Keymap::from_config(&bindings(&[("delete", "j")])).err().unwrap().to_string();
+        assert!(message.contains("'down'") && message.contains("'delete'"), "{}", message);
+        assert!(Keymap::from_config(&bindings(&[("delete", "x"), ("undo", "x")])).is_err());
+    }
+    
+    #[test]
+    fn force_quit_and_bookmark_keys_cant_be_bound() {
+        for key in ["Q", "ctrl-q", "ctrl-c", "alt-1", "alt-9"] {
+            let message = Keymap::from_config(&bindings(&[("refresh", key)])).err().unwrap().to_string();
+            assert!(message.contains("reserved"), "{}: {}", key, message);
+        }
+        assert!(Keymap::from_config(&bindings(&[("refresh", "alt-0")])).is_ok());
+        assert!(Keymap::from_config(&bindings(&[("refresh", "q")])).is_err(), "q is still quit");
+        assert!(Keymap::from_config(&bindings(&[("quit", "ctrl-x"), ("refresh", "q")])).is_ok());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,25 @@
+// Journal storage and configuration, shared by the TUI binary and usable on its own
+pub mod attachments;
+pub mod backup;
+pub mod bookmarks;
+pub mod config;
+pub mod crypto;
+pub mod dates;
+pub mod export;
+pub mod format;
+pub mod frontmatter;
+pub mod git;
+pub mod import;
+pub mod journal;
+pub mod keymap;
+pub mod links;
+pub mod listing;
+pub mod search;
+pub mod secrets;
+pub mod theme;
+pub mod trash;
+pub mod volume;
+pub mod watch;
+pub mod words;
+
+pub use journal::{Journal, JournalEntry};
\ No newline at end of file
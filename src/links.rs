@@ -0,0 +1,93 @@
+// `[[Title]]` / `[[Title|label]]` references between entries
+
+pub struct WikiLink<'a> {
+    // Byte range of the whole `[[...]]` in the source text
+    pub start: usize,
+    pub end: usize,
+    pub target: &'a str,
+    pub label: &'a str,
+}
+
+pub fn find(text: &str) -> Vec<WikiLink<'_>> {
+    let mut links = Vec::new();
+    let mut offset = 0;
+    while let Some(open) = text[offset..].find("[[") {
+        let start = offset + open;
+        let inner_start = start + 2;
+        let Some(close) = text[inner_start..].find("]]") else {
+            break;
+        };
+        let inner = &text[inner_start..inner_start + close];
+        let end = inner_start + close + 2;
+        
+        let (target, label) = match inner.split_once('|') {
+            Some((target, label)) => (target.trim(), label.trim()),
+            None => (inner.trim(), inner.trim()),
+        };
+        // Links don't span lines and need a target
+        if inner.contains('\n') || target.is_empty() {
+            offset = inner_start;
+            continue;
+        }
+        
+        links.push(WikiLink { start, end, target, label });
+        offset = end;
+    }
+    links
+}
+
+// Comparison key so "My Entry", "my-entry" and "my_entry" all resolve alike
+pub fn slug(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn targets(text: &str) -> Vec<(&str, &str)> {
+        find(text).into_iter().map(|link| (link.target, link.label)).collect()
+    }
+    
+    #[test]
+    fn a_label_follows_the_bar() {
+        let text = "See [[My Entry | that day]] and [[Other]].";
+        let links = find(text);
+        assert_eq!(targets(text), [("My Entry", "that day"), ("Other", "Other")]);
+        assert_eq!(&text[links[0].start..links[0].end], "[[My Entry | that day]]");
+    }
+    
+    #[test]
+    fn unclosed_brackets_are_not_links() {
+        assert!(targets("an [[unclosed link").is_empty());
+        assert_eq!(targets("[[open [[Closed]]"), [("open [[Closed", "open [[Closed")]);
+    }
+    
+    #[test]
+    fn links_dont_span_lines() {
+        assert_eq!(targets("[[first\nline]] then [[Second]]"), [("Second", "Second")]);
+    }
+    
+    #[test]
+    fn empty_links_are_skipped() {
+        assert!(targets("[[]] [[   ]] [[|label]]").is_empty());
+        assert_eq!(targets("[[]] [[Real]]"), [("Real", "Real")]);
+    }
+    
+    #[test]
+    fn spaces_case_and_separators_slug_alike() {
+        assert_eq!(slug("My Entry"), "my-entry");
+        assert_eq!(slug("my-entry"), "my-entry");
+        assert_eq!(slug("my_entry"), "my-entry");
+        assert_eq!(slug("  My -- Entry! "), "my-entry");
+        assert_ne!(slug("My Entry"), slug("My Entries"));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,55 @@
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::JournalEntry;
+
+// Output formats for `--list`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Json,
+    // Tab-separated `created<TAB>title<TAB>path`, one entry per line
+    Plain,
+}
+
+impl OutputFormat {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "json" => Some(Self::Json),
+            "plain" | "tsv" => Some(Self::Plain),
+            _ => None,
+        }
+    }
+}
+
+// Stable, script-friendly view of an entry
+#[derive(Debug, Serialize)]
+pub struct EntryRecord {
+    pub title: String,
+    pub path: String,
+    // RFC 3339 with the local offset
+    pub created: String,
+}
+
+impl From<&JournalEntry> for EntryRecord {
+    fn from(entry: &JournalEntry) -> Self {
+        Self {
+            title: entry.title.clone(),
+            path: entry.path.to_string_lossy().into_owned(),
+            created: entry.created.to_rfc3339(),
+        }
+    }
+}
+
+pub fn format_entries(entries: &[JournalEntry], format: OutputFormat) -> Result<String> {
+    let records: Vec<EntryRecord> = entries.iter().map(EntryRecord::from).collect();
+    
+    match format {
+        OutputFormat::Json => Ok(serde_json::to_string_pretty(&records)?),
+        OutputFormat::Plain => Ok(records
+            .iter()
+            // Tabs and newlines in titles would break the columns
+            .map(|r| format!("{}\t{}\t{}", r.created, r.title.replace(['\t', '\n'], " "), r.path))
+            .collect::<Vec<_>>()
+            .join("\n")),
+    }
+}
\ No newline at end of file
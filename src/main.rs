@@ -1,13 +1,18 @@
 mod auth;
+mod cli;
+mod diff;
+mod editor;
+mod instance;
+mod markdown;
 mod matrix;
-mod volume;
+mod onboarding;
 
-use anyhow::Result;
-use chrono::{DateTime, Local};
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Datelike, Local, Months, NaiveDate};
 use crossterm::{
-    event::{self, Event, KeyCode, KeyModifiers, KeyEventKind},
+    event::{self, DisableBracketedPaste, EnableBracketedPaste, Event, KeyCode, KeyModifiers, KeyEventKind},
     execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    terminal::{disable_raw_mode, enable_raw_mode, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{
     backend::CrosstermBackend,
@@ -15,169 +20,1437 @@ use ratatui::{
     style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{
-        Block, BorderType, Borders, List, ListItem, ListState, Paragraph, Wrap,
+        Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap,
     },
     Frame, Terminal,
 };
 use std::{
+    collections::{HashMap, HashSet},
     fs,
     io,
     path::{Path, PathBuf},
     process::Command,
-    time::Duration,
+    str::FromStr,
+    time::{Duration, Instant},
 };
-use volume::VolumeManager;
+use journal_tui::{attachments, backup, bookmarks, config, crypto, dates, export, format, frontmatter, git, links, listing, search, trash, words, Journal, JournalEntry};
+use journal_tui::config::{Config, ListPrefix, MAX_LIST_WIDTH, MIN_LIST_WIDTH};
+use journal_tui::keymap::{Action, Keymap};
+use journal_tui::theme::{Theme, ThemeConfig};
+use journal_tui::volume::{self, VolumeManager};
+use instance::InstanceLock;
+use journal_tui::watch::JournalWatcher;
 
-#[derive(Debug)]
-struct JournalEntry {
-    title: String,
-    path: PathBuf,
-    created: DateTime<Local>,
+use editor::Editor;
+
+// How long the list shows REFRESHED after outside changes are picked up
+const REFRESH_FLASH: Duration = Duration::from_millis(1500);
+// Words listed in the word report
+const WORD_REPORT_SIZE: usize = 20;
+// Columns the unwrapped preview moves per h/l
+const PAN_STEP: u16 = 8;
+// Widest the built-in editor's text column gets, for a comfortable line length
+const EDITOR_COLUMN_WIDTH: u16 = 72;
+// Titles listed on the unlock screen; as many as its message box has rows for
+const DECODE_TITLES: usize = 3;
+
+// Inclusive range of local creation dates the list is narrowed to
+#[derive(Debug, Clone, PartialEq)]
+struct DateFilter {
+    label: String,
+    from: NaiveDate,
+    to: NaiveDate,
+}
+
+impl DateFilter {
+    fn today() -> Self {
+        let today = Local::now().date_naive();
+        Self { label: "TODAY".to_string(), from: today, to: today }
+    }
+    
+    fn contains(&self, created: &DateTime<Local>) -> bool {
+        let date = created.date_naive();
+        self.from <= date && date <= self.to
+    }
 }
 
 #[derive(Debug)]
 enum AppMode {
     Normal,
     TitleInput,
+    QuickCapture,
+    Search,
+    ConfirmDelete,
+    Trash,
+    ConfirmPurge,
+    Duplicates,
+    View,
+    // Typing a search within the viewed entry
+    ViewSearch,
+    ConfirmQuit,
+    // `C` found an entry already titled with today's date
+    ConfirmOpenDated,
+    WordReport,
+    TagBrowser,
+    // Typing the path of another folder to browse, and confirming it should be created
+    DirPicker,
+    ConfirmCreateDir,
+    // `b` was pressed; the next digit picks the bookmark slot
+    BookmarkSlot,
+    OnThisDay,
+    // The built-in editor, full screen
+    Edit,
+}
+
+impl AppMode {
+    // Modes where printable keys are typed into a buffer rather than treated as commands
+    fn is_text_input(&self) -> bool {
+        matches!(self, AppMode::TitleInput | AppMode::QuickCapture | AppMode::Search | AppMode::ViewSearch | AppMode::Edit | AppMode::DirPicker)
+    }
 }
 
 struct App {
-    entries: Vec<JournalEntry>,
+    journal: Journal,
+    // Slugged titles and filenames to entry index, for resolving `[[links]]`
+    link_targets: HashMap<String, usize>,
+    // Entry index to the entries that link to it, rebuilt with the entry cache
+    backlinks: HashMap<usize, Vec<usize>>,
+    // Indices into `entries` that pass the active filter, in display order
+    visible: Vec<usize>,
+    search_query: String,
+    // The query split into its `@date` range and the text terms that remain
+    search_dates: Option<(NaiveDate, NaiveDate)>,
+    search_text: String,
+    date_filter: Option<DateFilter>,
+    // Only entries with this tag are listed
+    tag_filter: Option<String>,
+    // Every tag with how many entries carry it, most used first; rebuilt with the entry cache
+    tag_counts: Vec<(String, usize)>,
+    tag_state: ListState,
+    show_archived: bool,
     list_state: ListState,
+    // Rendered height of each list row and the rows that fit, recorded at draw time for paging
+    list_row_heights: Vec<usize>,
+    list_page_height: usize,
     mode: AppMode,
     title_input: String,
-    journal_dir: PathBuf,
+    dir_input: String,
+    // A folder from the picker that doesn't exist yet, awaiting confirmation
+    pending_dir: Option<PathBuf>,
+    // The journal is a folder opened with O from outside the vault
+    outside_vault: bool,
+    capture_input: String,
     volume_manager: VolumeManager,
+    // Most recently trashed entries (original path, path in trash) so they can be restored
+    last_deleted: Vec<(PathBuf, PathBuf)>,
+    // Entries marked with Space; delete and archive apply to all of them when any are marked
+    marked: HashSet<PathBuf>,
+    // When the pending delete or purge confirmation was asked, for `display.confirm_timeout_secs`
+    confirm_opened: Option<Instant>,
+    // Entries on the Alt-1..9 slots, by filename
+    bookmarks: bookmarks::Bookmarks,
+    trash_entries: Vec<trash::TrashedEntry>,
+    trash_state: ListState,
+    // Groups of byte-identical entries, oldest first, shown in the duplicate review
+    duplicate_groups: Vec<Vec<PathBuf>>,
+    duplicate_state: ListState,
+    // Read-only viewer position; the height is recorded at render time for paging
+    view_scroll: usize,
+    view_height: usize,
+    // Entries written on today's date in earlier years: (years ago, title, first line)
+    on_this_day: Vec<(i32, String, String)>,
+    // Most frequent words of the selected entry, for the word report
+    word_report: Vec<(String, usize)>,
+    // Where reading stopped in each entry, restored when it's viewed again
+    view_positions: HashMap<PathBuf, usize>,
+    // `git diff` of the viewed entry, shown instead of its text, and the scroll to return to
+    view_diff: Option<(String, usize)>,
+    // Preview wraps long lines; off, it pans sideways by `preview_hscroll` columns instead
+    preview_wrap: bool,
+    preview_hscroll: u16,
+    // Wiki link highlighted in the viewer, cycled with Tab
+    view_link: Option<usize>,
+    // Search within the viewed entry: the query, where it matches as (line, byte offset)
+    // in the rendered lines, and which match n/N are on
+    view_search: String,
+    view_matches: Vec<(usize, usize)>,
+    view_match: usize,
+    // When the viewer's decrypt reveal started; None once the text is fully shown
+    view_reveal: Option<Instant>,
+    status_message: Option<String>,
+    // Why the list may be empty or incomplete; shown until a load succeeds cleanly
+    load_warning: Option<String>,
+    relative_timestamps: bool,
+    // One row per entry instead of a four-row card
+    compact: bool,
+    keymap: Keymap,
+    // Last key, paste or resize, for `animation.screensaver_after_secs`
+    last_input: Instant,
+    screensaver: Option<matrix::MatrixAnimation>,
+    // Kept so layout changes can be persisted back to disk
+    config: Config,
+    theme: Theme,
+    // None if the platform can't watch the journal directory
+    watcher: Option<JournalWatcher>,
+    // When outside changes were last picked up, for the REFRESHED flash
+    refreshed_at: Option<Instant>,
+    // Opened on first copy and kept, since on X11 the app itself serves what it copied
+    clipboard: Option<arboard::Clipboard>,
+    // The entry open in the built-in editor
+    editor: Option<Editor>,
 }
 
 impl App {
-    fn new(volume_manager: VolumeManager) -> Result<Self> {
-        // Always use encrypted volume path
-        let journal_dir = volume_manager.get_entries_path();
+    // `journal` can be opened on any directory, so the app runs the same on a scratch one;
+    // `volume_manager` is only used to lock the vault on the way out
+    fn new(journal: Journal, volume_manager: VolumeManager, config: &Config, theme: Theme) -> Result<Self> {
+        let keymap = Keymap::from_config(&config.keybindings)?;
+        let watcher = JournalWatcher::new(journal.dir()).ok();
+        let bookmarks = bookmarks::Bookmarks::load(journal.dir())?;
+        
+        let mut app = App {
+            journal,
+            link_targets: HashMap::new(),
+            backlinks: HashMap::new(),
+            visible: Vec::new(),
+            search_query: String::new(),
+            search_dates: None,
+            search_text: String::new(),
+            date_filter: None,
+            tag_filter: None,
+            tag_counts: Vec::new(),
+            tag_state: ListState::default(),
+            show_archived: false,
+            list_state: ListState::default(),
+            list_row_heights: Vec::new(),
+            list_page_height: 0,
+            mode: AppMode::Normal,
+            title_input: String::new(),
+            dir_input: String::new(),
+            pending_dir: None,
+            outside_vault: false,
+            capture_input: String::new(),
+            volume_manager,
+            last_deleted: Vec::new(),
+            marked: HashSet::new(),
+            confirm_opened: None,
+            bookmarks,
+            trash_entries: Vec::new(),
+            trash_state: ListState::default(),
+            duplicate_groups: Vec::new(),
+            duplicate_state: ListState::default(),
+            view_scroll: 0,
+            view_height: 0,
+            view_positions: HashMap::new(),
+            word_report: Vec::new(),
+            view_diff: None,
+            preview_wrap: true,
+            preview_hscroll: 0,
+            on_this_day: Vec::new(),
+            view_link: None,
+            view_search: String::new(),
+            view_matches: Vec::new(),
+            view_match: 0,
+            view_reveal: None,
+            editor: None,
+            status_message: None,
+            load_warning: None,
+            relative_timestamps: config.display.relative_timestamps,
+            compact: config.display.compact_list,
+            last_input: Instant::now(),
+            screensaver: None,
+            keymap,
+            config: config.clone(),
+            theme,
+            watcher,
+            refreshed_at: None,
+            clipboard: None,
+        };
+        
+        // Opt-in retention: old entries, archived ones included, go to the trash
+        let mut notices = Vec::new();
+        if let Some(months) = config.retention.prune_after_months
+            && let Some(cutoff) = Local::now().date_naive().checked_sub_months(Months::new(months))
+        {
+            app.journal.load(true)?;
+            let pruned = app.journal.prune_older_than(cutoff)?;
+            if !pruned.is_empty() {
+                notices.push(format!("Moved {} entries older than {} months to trash", pruned.len(), months));
+            }
+        }
+        
+        // Keep the trash from growing unbounded, without letting a stuck file block startup
+        let days = config.retention.purge_trash_after_days;
+        if days > 0 {
+            let max_age = Duration::from_secs(days * 24 * 60 * 60);
+            let (purged, failures) = trash::purge_older_than(app.journal.trash_dir(), app.journal.extensions(), max_age);
+            if purged > 0 {
+                notices.push(format!("Purged {} old entries from trash", purged));
+            }
+            if !failures.is_empty() {
+                notices.push(format!("Warning: couldn't purge {} from trash: {}", failures.len(), failures.join(", ")));
+            }
+        }
+        if !notices.is_empty() {
+            app.status_message = Some(notices.join(" · "));
+        }
+        
+        app.load_entries(app.show_archived)?;
+        // Always select the first item (Create New Entry)
+        app.list_state.select(Some(0));
+        
+        app.on_this_day = app.entries_on_this_day();
+        if !app.on_this_day.is_empty() {
+            app.mode = AppMode::OnThisDay;
+        }
+        
+        Ok(app)
+    }
+    
+    fn entries_on_this_day(&self) -> Vec<(i32, String, String)> {
+        let today = Local::now().date_naive();
+        self.journal.entries()
+            .iter()
+            .filter(|e| {
+                let date = e.created.date_naive();
+                date.month() == today.month() && date.day() == today.day() && date.year() < today.year()
+            })
+            .map(|e| {
+                // The first line of prose, skipping the title heading. Locked entries show a
+                // placeholder, as in the preview, until they're unlocked.
+                if e.locked {
+                    return (today.year() - e.created.year(), e.title.clone(), "🔒 Locked".to_string());
+                }
+                let first_line = e.content.as_deref()
+                    .and_then(|c| frontmatter::parse(c).1.lines().map(str::trim).find(|l| !l.is_empty() && !l.starts_with('#')))
+                    .unwrap_or("")
+                    .to_string();
+                (today.year() - e.created.year(), e.title.clone(), first_line)
+            })
+            .collect()
+    }
+    
+    fn load_entries(&mut self, include_archived: bool) -> Result<()> {
+        // Keep running with an empty list rather than exiting, but say why it's empty
+        self.load_warning = match self.journal.load(include_archived) {
+            Err(err) => Some(format!("{:#}", err)),
+            Ok(()) => {
+                let unreadable = self.journal.load_errors().len()
+                    + self.journal.entries().iter().filter(|e| e.content.is_none()).count();
+                (unreadable > 0).then(|| format!("{} entries could not be read", unreadable))
+            }
+        };
+        self.refresh_entries();
+        Ok(())
+    }
+    
+    // Re-read the journal keeping the selected entry selected even if it moved in the list.
+    // Returns whether anything actually changed.
+    fn reload_from_disk(&mut self) -> Result<bool> {
+        let before = self.entries_fingerprint();
+        let selected = self.selected_entry().map(|e| e.path.clone());
+        self.load_entries(self.show_archived)?;
+        if let Some(path) = selected {
+            self.select_path(&path);
+        }
+        Ok(self.entries_fingerprint() != before)
+    }
+    
+    fn entries_fingerprint(&self) -> u64 {
+        use std::hash::{DefaultHasher, Hash, Hasher};
+        let mut hasher = DefaultHasher::new();
+        for entry in self.journal.entries() {
+            (&entry.path, &entry.created, &entry.content).hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+    
+    // Rebuild everything derived from the entry list after it changes
+    fn refresh_entries(&mut self) {
+        // Marks on entries that are gone (or moved) no longer mean anything
+        let entries = self.journal.entries();
+        self.marked.retain(|path| entries.iter().any(|e| &e.path == path));
+        self.index_links();
+        self.count_tags();
+        self.apply_filter();
+    }
+    
+    fn count_tags(&mut self) {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for tag in self.journal.entries().iter().flat_map(|e| &e.tags) {
+            *counts.entry(tag).or_insert(0) += 1;
+        }
+        let mut counts: Vec<(String, usize)> = counts.into_iter().map(|(tag, n)| (tag.to_string(), n)).collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        self.tag_counts = counts;
+    }
+    
+    fn open_tag_browser(&mut self) {
+        // Start on the tag being filtered by, if any
+        let current = self.tag_filter.as_ref().and_then(|tag| self.tag_counts.iter().position(|(t, _)| t == tag));
+        self.tag_state.select(current.or((!self.tag_counts.is_empty()).then_some(0)));
+        self.mode = AppMode::TagBrowser;
+    }
+    
+    fn set_tag_filter(&mut self, tag: Option<String>) {
+        self.tag_filter = tag;
+        self.apply_filter();
+        self.list_state.select(Some(if self.visible.is_empty() { 0 } else { 1 }));
+    }
+    
+    // One pass over the cached content so link lookups never rescan files while drawing
+    fn index_links(&mut self) {
+        self.link_targets.clear();
+        self.backlinks.clear();
+        
+        // Titles win over filenames when both could match
+        for (i, entry) in self.journal.entries().iter().enumerate() {
+            self.link_targets.entry(links::slug(&entry.title)).or_insert(i);
+        }
+        for (i, entry) in self.journal.entries().iter().enumerate() {
+            if let Some(stem) = entry.path.file_stem().and_then(|s| s.to_str()) {
+                self.link_targets.entry(links::slug(stem)).or_insert(i);
+            }
+        }
+        // Titles made only of punctuation slug to nothing and shouldn't match anything
+        self.link_targets.remove("");
+        
+        for (source, entry) in self.journal.entries().iter().enumerate() {
+            let Some(content) = &entry.content else {
+                continue;
+            };
+            for link in links::find(content) {
+                if let Some(&target) = self.link_targets.get(&links::slug(link.target)) {
+                    let sources = self.backlinks.entry(target).or_default();
+                    if target != source && !sources.contains(&source) {
+                        sources.push(source);
+                    }
+                }
+            }
+        }
+    }
+    
+    fn apply_filter(&mut self) {
+        self.visible = (0..self.journal.entries().len())
+            .filter(|&i| self.matches_filter(&self.journal.entries()[i]))
+            .collect();
+        self.clamp_selection();
+    }
+    
+    fn matches_filter(&self, entry: &JournalEntry) -> bool {
+        if let Some(tag) = &self.tag_filter
+            && !entry.tags.contains(tag)
+        {
+            return false;
+        }
+        
+        if let Some(filter) = &self.date_filter
+            && !filter.contains(&entry.created)
+        {
+            return false;
+        }
+        
+        if let Some((from, to)) = self.search_dates {
+            let date = entry.created.date_naive();
+            if date < from || date > to {
+                return false;
+            }
+        }
+        
+        matches_search(entry, &self.search_text)
+    }
+    
+    // Pressing the same filter again turns it off
+    fn toggle_date_filter(&mut self, filter: DateFilter) {
+        self.date_filter = if self.date_filter.as_ref() == Some(&filter) {
+            None
+        } else {
+            Some(filter)
+        };
+        self.apply_filter();
+        self.list_state.select(Some(if self.visible.is_empty() { 0 } else { 1 }));
+    }
+    
+    fn set_search_query(&mut self, query: String) {
+        (self.search_dates, self.search_text) = dates::parse_query(&query, Local::now().date_naive());
+        self.search_query = query;
+        self.apply_filter();
+        // Jump to the first match while typing
+        self.list_state.select(Some(if self.visible.is_empty() { 0 } else { 1 }));
+    }
+    
+    // `line` is where the cursor should start, for editors that take a `+N` argument
+    fn run_editor(&self, path: &Path, line: Option<usize>) -> Result<()> {
+        let editor = self.config.editor();
+        
+        if !self.journal.is_encrypted() {
+            editor_command(&editor, path, line, self.config.entries.editor_wait).status()
+                .with_context(|| format!("Failed to launch editor '{}'", editor))?;
+            return Ok(());
+        }
+        
+        // Edit a decrypted copy, then encrypt the result back into place
+        let file_name = path.file_name().and_then(|s| s.to_str()).unwrap_or("entry.md");
+        let temp_path = crypto::write_private_temp(file_name, &self.journal.read(path)?)?;
+        
+        let status = editor_command(&editor, &temp_path, line, self.config.entries.editor_wait).status()
+            .with_context(|| format!("Failed to launch editor '{}'", editor));
+        let edited = fs::read_to_string(&temp_path);
+        let _ = fs::remove_file(&temp_path);
+        
+        status?;
+        self.journal.write(path, &edited?)
+    }
+    
+    fn create_new_entry(&mut self) -> Result<()> {
+        if self.title_input.trim().is_empty() {
+            return Ok(());
+        }
+        let title = std::mem::take(&mut self.title_input);
+        self.create_entry(&title)
+    }
+    
+    // Today's date as the title, no prompt. Returns false when an entry with that title
+    // already exists, leaving the choice to open it instead with the user.
+    fn create_dated_entry(&mut self) -> Result<bool> {
+        let title = dated_title();
+        let existing = self.journal.entries().iter().find(|e| e.title == title).map(|e| e.path.clone());
+        let Some(path) = existing else {
+            self.create_entry(&title)?;
+            return Ok(true);
+        };
+        
+        // It may be hidden by the current search or filter
+        if !self.visible_entries().any(|e| e.path == path) {
+            self.date_filter = None;
+            self.set_search_query(String::new());
+        }
+        self.select_path(&path);
+        self.mode = AppMode::ConfirmOpenDated;
+        Ok(false)
+    }
+    
+    fn create_entry(&mut self, title: &str) -> Result<()> {
+        let body = match self.config.template() {
+            Some(template) => fs::read_to_string(template)
+                .with_context(|| format!("Failed to read template {}", template.display()))?,
+            None => String::new(),
+        };
+        let (filepath, line) = self.journal.create_from_template(title, &body)?;
+        if self.config.entries.builtin_editor {
+            self.last_deleted.clear();
+            self.load_entries(self.show_archived)?;
+            self.select_path(&filepath);
+            return self.edit_in_app(&filepath, line - 1);
+        }
+        
+        // Suspend raw mode but don't clear screen
+        disable_raw_mode()?;
+        let _ = execute!(io::stdout(), DisableBracketedPaste);
+        
+        self.run_editor(&filepath, Some(line))?;
+        
+        // Re-enable raw mode; the editor may have turned bracketed paste off on exit
+        enable_raw_mode()?;
+        let _ = execute!(io::stdout(), EnableBracketedPaste);
+        
+        self.mode = AppMode::Normal;
+        // Any new write invalidates the undo buffer
+        self.last_deleted.clear();
+        let filepath = self.sync_filename(&filepath);
+        self.load_entries(self.show_archived)?;
+        self.select_path(&filepath);
+        self.commit_to_git(&[&filepath]);
+        self.back_up(&filepath);
+        
+        Ok(())
+    }
+    
+    // With `rename_on_title_change`, keep the filename in step with an edited title.
+    // Returns the entry's path, renamed or not; a failed rename is reported and skipped.
+    fn sync_filename(&mut self, path: &Path) -> PathBuf {
+        if !self.config.entries.rename_on_title_change {
+            return path.to_path_buf();
+        }
+        match self.journal.sync_filename(path) {
+            Ok(renamed) => {
+                self.follow_rename(path, &renamed);
+                renamed
+            }
+            Err(err) => {
+                self.status_message = Some(format!("Error: {:#}", err));
+                path.to_path_buf()
+            }
+        }
+    }
+    
+    // Bookmarks are kept by filename, so they have to follow the app's own renames and moves
+    fn follow_rename(&mut self, from: &Path, to: &Path) {
+        let name = |path: &Path| path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        if from != to
+            && let Err(err) = self.bookmarks.rename(&name(from), &name(to))
+        {
+            self.status_message = Some(format!("Error: {:#}", err));
+        }
+    }
+    
+    // Record the entry in the journal's git history when `[git] autocommit` is on.
+    // `paths` ends with the entry's current path; earlier ones are names it just had.
+    // A failed commit is reported but never loses the edit.
+    fn commit_to_git(&mut self, paths: &[&Path]) {
+        let Some(current) = paths.last() else {
+            return;
+        };
+        // External notes and browsed folders live outside the journal's repository
+        if !self.config.git.autocommit || self.outside_vault || self.journal.is_external(current) {
+            return;
+        }
+        let message = format!("journal: {} {}", self.journal.title_of(current), Local::now().format("%Y-%m-%d %H:%M"));
+        if let Err(err) = git::autocommit(self.journal.dir(), paths, &message) {
+            self.status_message = Some(format!("Error: {}", err));
+        }
+    }
+    
+    fn open_dir_picker(&mut self) {
+        self.dir_input = self.journal.dir().display().to_string();
+        self.mode = AppMode::DirPicker;
+    }
+    
+    // An empty path goes back to the vault's own entries
+    fn submit_dir(&mut self) -> Result<()> {
+        self.mode = AppMode::Normal;
+        let input = std::mem::take(&mut self.dir_input);
+        let dir = match input.trim() {
+            "" => self.volume_manager.get_entries_path(),
+            path => config::expand_home(Path::new(path)),
+        };
+        if !dir.exists() {
+            self.pending_dir = Some(dir);
+            self.mode = AppMode::ConfirmCreateDir;
+            return Ok(());
+        }
+        if !dir.is_dir() {
+            bail!("{} is a file, not a folder", dir.display());
+        }
+        self.switch_dir(dir)
+    }
+    
+    // Browse `dir` instead of the current journal. Inside the vault the trash stays the
+    // vault's; anywhere else it's a `.trash` folder inside `dir`, so deleted entries never
+    // cross into (or out of) the encrypted volume. Outside the vault the folder is treated
+    // as someone else's: notes keep their plain or encrypted form and nothing is committed.
+    fn switch_dir(&mut self, dir: PathBuf) -> Result<()> {
+        let dir = fs::canonicalize(&dir).unwrap_or(dir);
+        let vault_entries = self.volume_manager.get_entries_path();
+        let in_vault = vault_entries.parent().is_some_and(|mount| dir.starts_with(mount));
+        let trash_dir = if in_vault { self.volume_manager.get_trash_path() } else { dir.join(".trash") };
+        let mut journal = Journal::open(dir, trash_dir, &self.config)?;
+        if !in_vault {
+            journal.keep_file_format();
+        }
+        self.outside_vault = !in_vault;
+        self.bookmarks = bookmarks::Bookmarks::load(journal.dir())?;
+        
+        self.watcher = JournalWatcher::new(journal.dir()).ok();
+        self.journal = journal;
+        self.marked.clear();
+        self.last_deleted.clear();
+        self.tag_filter = None;
+        self.date_filter = None;
+        self.load_entries(self.show_archived)?;
+        self.set_search_query(String::new());
+        self.list_state.select(Some(0));
         
-        if !journal_dir.exists() {
-            fs::create_dir_all(&journal_dir)?;
+        let shown = self.journal.dir().display().to_string();
+        self.status_message = Some(if in_vault {
+            format!("Opened {}", shown)
+        } else {
+            format!("Opened {} - outside the vault: new notes aren't encrypted and nothing is committed to git", shown)
+        });
+        Ok(())
+    }
+    
+    // Ask before a delete or purge; `display.confirm_timeout_secs` counts from here
+    fn confirm(&mut self, mode: AppMode) {
+        self.mode = mode;
+        self.confirm_opened = Some(Instant::now());
+    }
+    
+    // Time left to answer the pending confirmation, when it has a timeout
+    fn confirm_remaining(&self) -> Option<Duration> {
+        let timeout = Duration::from_secs(self.config.display.confirm_timeout_secs?);
+        let opened = self.confirm_opened?;
+        matches!(self.mode, AppMode::ConfirmDelete | AppMode::ConfirmPurge)
+            .then(|| timeout.saturating_sub(opened.elapsed()))
+    }
+    
+    // An unanswered confirmation gives up as if any other key had been pressed, so a stray
+    // `y` long after walking away can't delete what was selected back then
+    fn expire_confirmation(&mut self) {
+        if self.confirm_remaining() != Some(Duration::ZERO) {
+            return;
+        }
+        self.mode = match self.mode {
+            AppMode::ConfirmPurge => AppMode::Trash,
+            _ => AppMode::Normal,
+        };
+        self.confirm_opened = None;
+        self.status_message = Some("No answer in time; nothing was deleted".to_string());
+    }
+    
+    // Start the screensaver once the list has sat idle long enough, and advance it while it
+    // runs. Prompts, popups and the editor never trigger it.
+    fn tick_screensaver(&mut self, size: ratatui::layout::Size) {
+        if let Some(animation) = self.screensaver.as_mut() {
+            animation.update();
+            return;
+        }
+        let idle = self.config.animation.screensaver_after_secs.map(Duration::from_secs);
+        if matches!(self.mode, AppMode::Normal) && idle.is_some_and(|idle| self.last_input.elapsed() >= idle) {
+            self.screensaver = Some(matrix::MatrixAnimation::new(size.width, size.height, &self.config.animation, &self.theme));
+        }
+    }
+    
+    // Mirror the entry to `[backup] dir`; a failure is reported and the edit stands
+    fn back_up(&mut self, path: &Path) {
+        let Some(dir) = self.config.backup.dir.clone() else {
+            return;
+        };
+        if let Err(err) = backup::mirror(path, &dir, self.config.backup.allow_plaintext) {
+            self.status_message = Some(format!("Backup failed: {:#}", err));
+        }
+    }
+    
+    fn view_entry(&mut self) {
+        if let Some(path) = self.selected_entry().map(|e| e.path.clone())
+            && !self.unlock_entry(&path)
+        {
+            // Following a link from the viewer lands back on the list, not on the locked text
+            self.mode = AppMode::Normal;
+            return;
+        }
+        if let Some(entry) = self.selected_entry() {
+            let path = entry.path.clone();
+            let lines = entry.content.as_deref().map_or(0, |c| c.lines().count());
+            // A position past the end of an entry that has since shrunk is meaningless
+            self.view_scroll = match self.view_positions.get(&path) {
+                Some(&offset) if offset < lines => offset,
+                Some(_) => {
+                    self.view_positions.remove(&path);
+                    0
+                }
+                None => 0,
+            };
+            self.view_link = None;
+            self.view_diff = None;
+            self.clear_view_search();
+            self.view_reveal = self.config.animation.viewer_reveal.then(Instant::now);
+            self.mode = AppMode::View;
+        }
+    }
+    
+    // A fresh authentication for entries marked `locked: true`, each time one is opened.
+    // Unlocked entries pass straight through.
+    fn unlock_entry(&mut self, path: &Path) -> bool {
+        if !self.journal.entries().iter().any(|e| e.path == path && e.locked) {
+            return true;
+        }
+        match auth::authenticate(&self.config.auth) {
+            Ok(true) => true,
+            Ok(false) => {
+                self.status_message = Some("🔒 Authentication failed; the entry stays locked".to_string());
+                false
+            }
+            Err(err) => {
+                self.status_message = Some(format!("Error: {:#}", err));
+                false
+            }
+        }
+    }
+    
+    // Index of the entry a `[[target]]` refers to, by title or filename
+    fn resolve_link(&self, target: &str) -> Option<usize> {
+        self.link_targets.get(&links::slug(target)).copied()
+    }
+    
+    fn viewer_links(&self) -> Vec<markdown::LinkRef> {
+        let content = self.selected_entry().and_then(|e| e.content.as_deref()).unwrap_or("");
+        markdown::render(content, &self.theme, &|_, _| Style::default()).links
+    }
+    
+    // The viewer's lines as plain text, the diff when it's shown
+    fn viewer_text(&self) -> Vec<String> {
+        let lines = match &self.view_diff {
+            Some((diff, _)) => diff::render(diff, &self.theme),
+            None => {
+                let content = self.selected_entry().and_then(|e| e.content.as_deref()).unwrap_or("");
+                markdown::render(content, &self.theme, &|_, _| Style::default()).lines
+            }
+        };
+        lines.iter().map(|line| line.spans.iter().map(|s| s.content.as_ref()).collect()).collect()
+    }
+    
+    // Find `view_search` in the viewed text, starting from the first match on screen or below
+    fn find_in_view(&mut self) {
+        self.view_matches = self.viewer_text()
+            .iter()
+            .enumerate()
+            .flat_map(|(line, text)| search::find_matches(text, &self.view_search).into_iter().map(move |(start, _)| (line, start)))
+            .collect();
+        if self.view_matches.is_empty() {
+            if !self.view_search.is_empty() {
+                self.status_message = Some(format!("\"{}\": no matches", self.view_search));
+            }
+            return;
+        }
+        self.view_match = self.view_matches.iter().position(|&(line, _)| line >= self.view_scroll).unwrap_or(0);
+        self.scroll_to_view_match();
+    }
+    
+    fn cycle_view_match(&mut self, forward: bool) {
+        let count = self.view_matches.len();
+        if count == 0 {
+            return;
+        }
+        self.view_match = if forward { (self.view_match + 1) % count } else { (self.view_match + count - 1) % count };
+        self.scroll_to_view_match();
+    }
+    
+    fn scroll_to_view_match(&mut self) {
+        let line = self.view_matches[self.view_match].0;
+        let height = self.view_height.max(1);
+        if line < self.view_scroll || line >= self.view_scroll + height {
+            self.view_scroll = line.saturating_sub(height / 2);
+        }
+    }
+    
+    fn clear_view_search(&mut self) {
+        self.view_search.clear();
+        self.view_matches.clear();
+        self.view_match = 0;
+    }
+    
+    // Highlight the next (or previous) link and scroll it into view
+    fn cycle_link(&mut self, forward: bool) {
+        let links = self.viewer_links();
+        if links.is_empty() {
+            return;
+        }
+        
+        let next = match (self.view_link, forward) {
+            (None, true) => 0,
+            (None, false) => links.len() - 1,
+            (Some(i), true) => (i + 1) % links.len(),
+            (Some(i), false) => (i + links.len() - 1) % links.len(),
+        };
+        self.view_link = Some(next);
+        
+        let line = links[next].line;
+        let height = self.view_height.max(1);
+        if line < self.view_scroll || line >= self.view_scroll + height {
+            self.view_scroll = line.saturating_sub(height / 2);
+        }
+    }
+    
+    // Switch the viewer between the entry and its uncommitted changes
+    fn toggle_view_diff(&mut self) {
+        self.clear_view_search();
+        if let Some((_, scroll)) = self.view_diff.take() {
+            self.view_scroll = scroll;
+            return;
+        }
+        let Some(path) = self.selected_entry().map(|e| e.path.clone()) else {
+            return;
+        };
+        if self.journal.is_encrypted() {
+            self.status_message = Some("Diffs aren't available for encrypted entries".to_string());
+            return;
+        }
+        match git::diff_head(self.journal.dir(), &path) {
+            Ok(None) => self.status_message = Some("The journal isn't in a git repository".to_string()),
+            Ok(Some(diff)) if diff.is_empty() => {
+                self.status_message = Some("No changes since the last commit".to_string());
+            }
+            Ok(Some(diff)) => {
+                self.view_diff = Some((diff, self.view_scroll));
+                self.view_scroll = 0;
+            }
+            Err(err) => self.status_message = Some(format!("Error: {}", err)),
+        }
+    }
+    
+    // Called when the viewer moves off an entry
+    fn remember_view_position(&mut self) {
+        if let Some(path) = self.selected_entry().map(|e| e.path.clone()) {
+            self.view_positions.insert(path, self.view_scroll);
+        }
+    }
+    
+    fn follow_link(&mut self) {
+        let Some(link) = self.view_link.and_then(|i| self.viewer_links().into_iter().nth(i)) else {
+            return;
+        };
+        if link.image {
+            self.open_attachment(&link.target);
+            return;
+        }
+        let Some(index) = self.resolve_link(&link.target) else {
+            self.status_message = Some(format!("No entry titled \"{}\"", link.target));
+            return;
+        };
+        
+        self.remember_view_position();
+        // The target may be hidden by the current search
+        if !self.visible.contains(&index) {
+            self.set_search_query(String::new());
+        }
+        let path = self.journal.entries()[index].path.clone();
+        self.select_path(&path);
+        self.view_entry();
+    }
+    
+    // Images go to the system viewer; local paths are relative to the journal directory
+    fn open_attachment(&mut self, target: &str) {
+        let target = if attachments::is_remote(target) {
+            target.to_string()
+        } else {
+            let path = attachments::resolve(target, self.journal.dir());
+            if !path.exists() {
+                self.status_message = Some(format!("Attachment not found: {}", path.display()));
+                return;
+            }
+            path.to_string_lossy().into_owned()
+        };
+        if let Err(err) = attachments::open_external(&target) {
+            self.status_message = Some(format!("Error: {}", err));
+        }
+    }
+    
+    fn open_entry(&mut self) -> Result<()> {
+        match self.selected_entry().map(|e| e.path.clone()) {
+            Some(path) => self.open_path(&path, None),
+            None => Ok(()),
+        }
+    }
+    
+    // Straight back into the newest entry with the cursor at its end, to keep writing.
+    // Returns false when there's nothing to open.
+    fn open_last_entry(&mut self) -> Result<bool> {
+        let Some(entry) = self.journal.entries().first() else {
+            self.status_message = Some("No entries yet".to_string());
+            return Ok(false);
+        };
+        let path = entry.path.clone();
+        let last_line = entry.content.as_deref().map_or(1, |c| c.lines().count().max(1));
+        self.open_path(&path, Some(last_line))?;
+        Ok(true)
+    }
+    
+    // `line` is 1-based, as for `run_editor`; the built-in editor also starts at its end
+    fn open_path(&mut self, path: &Path, line: Option<usize>) -> Result<()> {
+        if self.config.entries.builtin_editor {
+            self.edit_in_app(path, line.map_or(0, |l| l - 1))?;
+            if line.is_some()
+                && let Some(editor) = self.editor.as_mut()
+            {
+                editor.end();
+            }
+            return Ok(());
+        }
+        if !self.unlock_entry(path) {
+            return Ok(());
+        }
+        
+        // Leave alternate screen and disable raw mode for the editor
+        disable_raw_mode()?;
+        let _ = execute!(io::stdout(), DisableBracketedPaste);
+        execute!(
+            io::stdout(),
+            LeaveAlternateScreen,
+            crossterm::cursor::Show
+        )?;
+        
+        self.run_editor(path, line)?;
+        
+        // Re-enter alternate screen and re-enable raw mode
+        execute!(
+            io::stdout(),
+            EnterAlternateScreen,
+            crossterm::cursor::Hide
+        )?;
+        // The editor may have turned bracketed paste off on exit
+        let _ = execute!(io::stdout(), EnableBracketedPaste);
+        enable_raw_mode()?;
+        
+        self.last_deleted.clear();
+        let renamed = self.sync_filename(path);
+        self.load_entries(self.show_archived)?;
+        self.select_path(&renamed);
+        self.commit_to_git(&[path, &renamed]);
+        self.back_up(&renamed);
+        Ok(())
+    }
+    
+    // Bracketed paste: prompts take the text on one line, so a pasted newline never submits;
+    // the built-in editor keeps it as is
+    fn paste(&mut self, text: &str) {
+        let line = text.split(['\r', '\n']).filter(|l| !l.is_empty()).collect::<Vec<_>>().join(" ");
+        match self.mode {
+            AppMode::TitleInput => self.title_input.push_str(&line),
+            AppMode::DirPicker => self.dir_input.push_str(&line),
+            AppMode::QuickCapture => self.capture_input.push_str(&line),
+            AppMode::Search => self.set_search_query(format!("{}{}", self.search_query, line)),
+            AppMode::ViewSearch => self.view_search.push_str(&line),
+            AppMode::Edit => {
+                if let Some(editor) = self.editor.as_mut() {
+                    editor.insert_str(text);
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    fn edit_in_app(&mut self, path: &Path, row: usize) -> Result<()> {
+        if !self.unlock_entry(path) {
+            return Ok(());
+        }
+        let text = self.journal.read(path)?;
+        self.editor = Some(Editor::new(path.to_path_buf(), &text, row));
+        self.mode = AppMode::Edit;
+        Ok(())
+    }
+    
+    // Write the built-in editor's text back, then rename and commit as after an external edit
+    fn save_editor(&mut self) -> Result<()> {
+        let Some(editor) = self.editor.as_mut() else {
+            return Ok(());
+        };
+        let path = editor.path.clone();
+        self.journal.write(&path, &editor.text())?;
+        editor.modified = false;
+        editor.confirm_discard = false;
+        
+        self.last_deleted.clear();
+        let renamed = self.sync_filename(&path);
+        if let Some(editor) = self.editor.as_mut() {
+            editor.path = renamed.clone();
+        }
+        self.load_entries(self.show_archived)?;
+        self.select_path(&renamed);
+        self.commit_to_git(&[&path, &renamed]);
+        self.back_up(&renamed);
+        if self.status_message.is_none() {
+            self.status_message = Some("Saved".to_string());
+        }
+        Ok(())
+    }
+    
+    // Append a timestamped bullet to today's day file, creating it if needed
+    fn append_to_today(&mut self, line: &str) -> Result<()> {
+        if line.trim().is_empty() {
+            return Ok(());
+        }
+        
+        let day = self.journal.append_to_today(line)?;
+        self.last_deleted.clear();
+        self.refresh_entries();
+        self.status_message = Some(format!("Captured to {}", day));
+        
+        Ok(())
+    }
+    
+    // Index into `entries` of the highlighted row, if it's an entry
+    fn selected_index(&self) -> Option<usize> {
+        match self.list_state.selected() {
+            Some(selected) if selected > 0 => self.visible.get(selected - 1).copied(),
+            _ => None,
+        }
+    }
+    
+    fn selected_entry(&self) -> Option<&JournalEntry> {
+        self.selected_index().map(|i| &self.journal.entries()[i])
+    }
+    
+    fn visible_entries(&self) -> impl Iterator<Item = &JournalEntry> {
+        self.visible.iter().map(|&i| &self.journal.entries()[i])
+    }
+    
+    fn select_path(&mut self, path: &Path) {
+        let index = self.visible_entries().position(|e| e.path == path);
+        if let Some(index) = index {
+            self.list_state.select(Some(index + 1));
+        }
+    }
+    
+    // Alt-N: select the entry on bookmark slot N, dropping any filter that hides it
+    fn jump_to_bookmark(&mut self, slot: usize) {
+        let Some(name) = self.bookmarks.get(slot) else {
+            self.status_message = Some(format!("Nothing on bookmark {} (b then {} sets it)", slot, slot));
+            return;
+        };
+        let name = name.to_string();
+        // Archived entries are only listed when shown, so show them for a bookmark on one
+        if !self.show_archived
+            && self.journal.dir().join(journal_tui::journal::ARCHIVE_DIR).join(&name).is_file()
+            && let Err(err) = self.toggle_show_archived()
+        {
+            self.status_message = Some(format!("Error: {:#}", err));
+            return;
         }
-        
-        let mut app = App {
-            entries: Vec::new(),
-            list_state: ListState::default(),
-            mode: AppMode::Normal,
-            title_input: String::new(),
-            journal_dir,
-            volume_manager,
+        let index = self.journal.entries().iter().position(|e| e.path.file_name().is_some_and(|n| *n == *name));
+        let Some(index) = index else {
+            self.status_message = Some(format!("Bookmark {} ({}) isn't listed; it was deleted or renamed outside the app", slot, name));
+            return;
         };
         
-        app.load_entries()?;
-        // Always select the first item (Create New Entry)
-        app.list_state.select(Some(0));
+        if !self.visible.contains(&index) {
+            self.tag_filter = None;
+            self.date_filter = None;
+            self.set_search_query(String::new());
+        }
+        let path = self.journal.entries()[index].path.clone();
+        self.select_path(&path);
+    }
+    
+    // The digit after `b`: 1-9 puts the selected entry on that slot, 0 takes it off its slot
+    fn assign_bookmark(&mut self, slot: usize) -> Result<()> {
+        let Some(entry) = self.selected_entry() else {
+            return Ok(());
+        };
+        let name = entry.path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        let title = entry.title.clone();
         
-        Ok(app)
+        self.status_message = Some(if slot == 0 {
+            if self.bookmarks.clear(&name)? {
+                format!("Removed the bookmark on \"{}\"", title)
+            } else {
+                format!("\"{}\" isn't bookmarked", title)
+            }
+        } else {
+            self.bookmarks.set(slot, &name)?;
+            format!("Bookmarked \"{}\" on Alt-{}", title, slot)
+        });
+        Ok(())
     }
     
-    fn load_entries(&mut self) -> Result<()> {
-        self.entries.clear();
-        
-        if let Ok(entries) = fs::read_dir(&self.journal_dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.extension().and_then(|s| s.to_str()) == Some("md") {
-                    if let Ok(metadata) = entry.metadata() {
-                        if let Ok(created) = metadata.created() {
-                            let created: DateTime<Local> = created.into();
-                            if let Some(title) = self.read_title_from_file(&path) {
-                                self.entries.push(JournalEntry {
-                                    title,
-                                    path,
-                                    created,
-                                });
-                            }
-                        }
-                    }
+    fn toggle_mark(&mut self) {
+        if let Some(path) = self.selected_entry().map(|e| e.path.clone())
+            && !self.marked.remove(&path)
+        {
+            self.marked.insert(path);
+        }
+        self.move_selection_down();
+    }
+    
+    // What batch operations act on: the marked entries in list order, or else the selected one
+    fn targets(&self) -> Vec<&JournalEntry> {
+        if self.marked.is_empty() {
+            return self.selected_entry().into_iter().collect();
+        }
+        self.journal.entries().iter().filter(|e| self.marked.contains(&e.path)).collect()
+    }
+    
+    // `targets` the journal owns, for moving into the trash or archive. Says so when only
+    // external notes were picked.
+    fn owned_targets(&mut self) -> Vec<(PathBuf, String)> {
+        let targets: Vec<&JournalEntry> = self.targets();
+        let owned: Vec<(PathBuf, String)> = targets.iter().filter(|e| !e.external).map(|e| (e.path.clone(), e.title.clone())).collect();
+        if owned.is_empty() && !targets.is_empty() {
+            self.status_message = Some("External notes stay where they are; remove them from entries.external instead".to_string());
+        }
+        owned
+    }
+    
+    fn trash_entry(&mut self) -> Result<()> {
+        let targets = self.owned_targets();
+        if targets.is_empty() {
+            return Ok(());
+        }
+        
+        // Earlier moves stay undoable even if a later one fails
+        self.last_deleted.clear();
+        let mut result = Ok(());
+        for (path, _) in &targets {
+            match self.journal.delete(path) {
+                Ok(trashed_path) => self.last_deleted.push((path.clone(), trashed_path)),
+                Err(err) => {
+                    result = Err(err);
+                    break;
                 }
             }
         }
+        self.marked.clear();
         
-        self.entries.sort_by(|a, b| b.created.cmp(&a.created));
-        Ok(())
+        self.refresh_entries();
+        self.clamp_selection();
+        self.status_message = Some(match targets.as_slice() {
+            [(_, title)] => format!("Moved \"{}\" to trash - press u to undo", title),
+            _ => format!("Moved {} entries to trash - press u to undo", self.last_deleted.len()),
+        });
+        
+        result
     }
     
-    fn read_title_from_file(&self, path: &Path) -> Option<String> {
-        if let Ok(content) = fs::read_to_string(path) {
-            for line in content.lines() {
-                if let Some(title) = line.strip_prefix("# ") {
-                    return Some(title.to_string());
+    // Move the selected (or marked) entries into the archive, or back out of it. A mixed
+    // batch is archived.
+    fn toggle_archive(&mut self) -> Result<()> {
+        let targets = self.owned_targets();
+        if targets.is_empty() {
+            return Ok(());
+        }
+        let archived = self.targets().iter().filter(|e| !e.external).all(|e| e.archived);
+        
+        // One entry that can't be moved doesn't stop the rest of the batch
+        let mut moved = Vec::new();
+        let mut failed = Vec::new();
+        for (path, title) in &targets {
+            match self.journal.set_archived(path, !archived) {
+                Ok(target) => {
+                    // A name clash in the other folder gets a numbered name
+                    self.follow_rename(path, &target);
+                    moved.push(target);
                 }
+                Err(err) => failed.push(format!("\"{}\": {:#}", title, err)),
             }
         }
-        path.file_stem()
-            .and_then(|s| s.to_str())
-            .map(|s| s.to_string())
+        self.marked.clear();
+        
+        self.last_deleted.clear();
+        self.refresh_entries();
+        if let Some(target) = moved.last() {
+            self.select_path(target);
+        }
+        let what = match targets.as_slice() {
+            [(_, title)] => format!("\"{}\"", title),
+            _ => format!("{} entries", moved.len()),
+        };
+        let done = if archived { "Unarchived" } else { "Archived" };
+        self.status_message = Some(match failed.as_slice() {
+            [] => format!("{} {}", done, what),
+            _ if moved.is_empty() => format!("Error: couldn't move {}", failed.join(", ")),
+            _ => format!("{} {}; couldn't move {}", done, what, failed.join(", ")),
+        });
+        
+        Ok(())
     }
     
-    fn create_new_entry(&mut self) -> Result<()> {
-        if self.title_input.trim().is_empty() {
+    fn toggle_show_archived(&mut self) -> Result<()> {
+        self.show_archived = !self.show_archived;
+        let selected = self.selected_entry().map(|e| e.path.clone());
+        self.load_entries(self.show_archived)?;
+        if let Some(path) = selected {
+            self.select_path(&path);
+        }
+        self.status_message = Some(if self.show_archived {
+            "Showing archived entries".to_string()
+        } else {
+            "Hiding archived entries".to_string()
+        });
+        Ok(())
+    }
+    
+    fn undo_delete(&mut self) -> Result<()> {
+        let deleted = std::mem::take(&mut self.last_deleted);
+        if deleted.is_empty() {
+            self.status_message = Some("Nothing to undo".to_string());
             return Ok(());
         }
         
-        let timestamp = Local::now().format("%Y%m%d_%H%M%S");
-        let filename = format!("{}_{}.md", timestamp, self.title_input.replace(' ', "_"));
-        let filepath = self.journal_dir.join(filename);
+        // Restore what we can; anything that fails stays in the trash and can be undone again
+        let mut restored = Vec::new();
+        let mut failed = Vec::new();
+        for (path, trashed_path) in deleted {
+            match self.journal.undo_delete(&trashed_path, &path) {
+                Ok(restored_path) => restored.push(restored_path),
+                Err(err) => {
+                    let name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+                    failed.push(format!("\"{}\": {:#}", name, err));
+                    self.last_deleted.push((path, trashed_path));
+                }
+            }
+        }
+        self.refresh_entries();
         
-        let content = format!("# {}\n\n", self.title_input);
-        fs::write(&filepath, content)?;
+        // Reselect the (first) restored entry
+        if let Some(path) = restored.first() {
+            self.select_path(path);
+        }
+        let what = match restored.len() {
+            1 => "Entry restored".to_string(),
+            n => format!("{} entries restored", n),
+        };
+        self.status_message = Some(match failed.as_slice() {
+            [] => what,
+            _ if restored.is_empty() => format!("Error: couldn't restore {}", failed.join(", ")),
+            _ => format!("{}; couldn't restore {}", what, failed.join(", ")),
+        });
         
-        // Suspend raw mode but don't clear screen
-        disable_raw_mode()?;
+        Ok(())
+    }
+    
+    fn open_trash(&mut self) -> Result<()> {
+        self.trash_entries = self.journal.trashed()?;
+        self.trash_state.select(if self.trash_entries.is_empty() { None } else { Some(0) });
+        self.mode = AppMode::Trash;
+        Ok(())
+    }
+    
+    fn selected_trash_entry(&self) -> Option<&trash::TrashedEntry> {
+        self.trash_state.selected().and_then(|i| self.trash_entries.get(i))
+    }
+    
+    fn restore_from_trash(&mut self) -> Result<()> {
+        let Some(entry) = self.selected_trash_entry() else {
+            return Ok(());
+        };
+        let title = entry.title.clone();
         
-        let editor = std::env::var("EDITOR").unwrap_or_else(|_| "vim".to_string());
-        Command::new(editor)
-            .arg(&filepath)
-            .arg("+2")
-            .status()?;
+        let trashed = entry.path.clone();
+        self.journal.restore_from_trash(&trashed)?;
+        // The undo buffer may point at the file we just restored
+        self.last_deleted.clear();
+        self.refresh_entries();
+        self.reload_trash()?;
+        self.status_message = Some(format!("Restored \"{}\"", title));
         
-        // Re-enable raw mode
-        enable_raw_mode()?;
+        Ok(())
+    }
+    
+    fn purge_from_trash(&mut self) -> Result<()> {
+        let Some(entry) = self.selected_trash_entry() else {
+            return Ok(());
+        };
+        let title = entry.title.clone();
         
-        self.title_input.clear();
-        self.mode = AppMode::Normal;
-        self.load_entries()?;
+        trash::purge(&entry.path)?;
+        self.last_deleted.clear();
+        self.reload_trash()?;
+        self.status_message = Some(format!("Permanently deleted \"{}\"", title));
         
         Ok(())
     }
     
-    fn open_entry(&mut self) -> Result<()> {
-        if let Some(selected) = self.list_state.selected() {
-            if selected > 0 && selected <= self.entries.len() {
-                let entry = &self.entries[selected - 1];
-                
-                // Leave alternate screen and disable raw mode for the editor
-                disable_raw_mode()?;
-                execute!(
-                    io::stdout(),
-                    LeaveAlternateScreen,
-                    crossterm::cursor::Show
-                )?;
-                
-                let editor = std::env::var("EDITOR").unwrap_or_else(|_| "vim".to_string());
-                Command::new(editor)
-                    .arg(&entry.path)
-                    .status()?;
-                
-                // Re-enter alternate screen and re-enable raw mode
-                execute!(
-                    io::stdout(),
-                    EnterAlternateScreen,
-                    crossterm::cursor::Hide
-                )?;
-                enable_raw_mode()?;
-                
-                self.load_entries()?;
-            }
+    fn reload_trash(&mut self) -> Result<()> {
+        self.trash_entries = self.journal.trashed()?;
+        let current = self.trash_state.selected().unwrap_or(0);
+        self.trash_state.select(if self.trash_entries.is_empty() {
+            None
+        } else {
+            Some(current.min(self.trash_entries.len() - 1))
+        });
+        Ok(())
+    }
+    
+    fn open_duplicates(&mut self) {
+        self.duplicate_groups = self.journal.find_duplicates();
+        if self.duplicate_groups.is_empty() {
+            self.status_message = Some("No duplicate entries found".to_string());
+            return;
+        }
+        self.duplicate_state.select(Some(0));
+        self.mode = AppMode::Duplicates;
+    }
+    
+    // Put the selected entry's text on the system clipboard
+    fn copy_entry(&mut self) {
+        if let Some(path) = self.selected_entry().map(|e| e.path.clone())
+            && !self.unlock_entry(&path)
+        {
+            return;
+        }
+        let Some(entry) = self.selected_entry() else {
+            return;
+        };
+        let Some(content) = entry.content.as_deref() else {
+            self.status_message = Some("Nothing to copy: the entry couldn't be read".to_string());
+            return;
+        };
+        let text = if self.config.entries.copy_without_title {
+            frontmatter::body(content).to_string()
+        } else {
+            content.to_string()
+        };
+        let title = entry.title.clone();
+        
+        // Headless sessions (ssh, no display server) have no clipboard at all
+        let result = match &mut self.clipboard {
+            Some(clipboard) => clipboard.set_text(text),
+            None => arboard::Clipboard::new().and_then(|mut clipboard| {
+                let result = clipboard.set_text(text);
+                self.clipboard = Some(clipboard);
+                result
+            }),
+        };
+        self.status_message = Some(match result {
+            Ok(()) => format!("Copied \"{}\" to the clipboard", title),
+            Err(err) => format!("Clipboard unavailable: {}", err),
+        });
+    }
+    
+    fn open_word_report(&mut self) {
+        if let Some(path) = self.selected_entry().map(|e| e.path.clone())
+            && !self.unlock_entry(&path)
+        {
+            return;
         }
+        let Some(entry) = self.selected_entry() else {
+            return;
+        };
+        self.word_report = words::word_frequencies(entry.content.as_deref().unwrap_or(""));
+        self.word_report.truncate(WORD_REPORT_SIZE);
+        self.mode = AppMode::WordReport;
+    }
+    
+    // Keep the oldest copy in the selected group and move the rest to the trash
+    fn resolve_duplicate_group(&mut self) -> Result<()> {
+        let Some(index) = self.duplicate_state.selected() else {
+            return Ok(());
+        };
+        let Some(group) = self.duplicate_groups.get(index) else {
+            return Ok(());
+        };
+        
+        let extras = group[1..].to_vec();
+        for path in &extras {
+            self.journal.delete(path)?;
+        }
+        let removed = extras.len();
+        
+        self.last_deleted.clear();
+        self.refresh_entries();
+        self.duplicate_groups = self.journal.find_duplicates();
+        if self.duplicate_groups.is_empty() {
+            self.mode = AppMode::Normal;
+        } else {
+            self.duplicate_state.select(Some(index.min(self.duplicate_groups.len() - 1)));
+        }
+        self.status_message = Some(format!("Moved {} duplicate(s) to trash", removed));
+        
         Ok(())
     }
     
+    fn resize_list(&mut self, delta: i16) {
+        let width = (self.config.display.list_width as i16 + delta).clamp(MIN_LIST_WIDTH as i16, MAX_LIST_WIDTH as i16);
+        self.config.display.list_width = width as u16;
+        self.save_config();
+    }
+    
+    fn toggle_preview(&mut self) {
+        self.config.display.show_preview = !self.config.display.show_preview;
+        self.save_config();
+    }
+    
+    fn save_config(&mut self) {
+        if let Err(err) = self.config.save_layout() {
+            self.status_message = Some(format!("Failed to save config: {}", err));
+        }
+    }
+    
+    fn clamp_selection(&mut self) {
+        let current = self.list_state.selected().unwrap_or(0);
+        self.list_state.select(Some(current.min(self.visible.len())));
+    }
+    
     fn move_selection_up(&mut self) {
         let current = self.list_state.selected().unwrap_or(0);
         if current > 0 {
@@ -187,28 +1460,229 @@ impl App {
     
     fn move_selection_down(&mut self) {
         let current = self.list_state.selected().unwrap_or(0);
-        let max = self.entries.len();
+        let max = self.visible.len();
         if current < max {
             self.list_state.select(Some(current + 1));
         }
     }
+    
+    // Move by as many rows as fit on screen; rows differ in height, so count what was drawn
+    fn page_selection(&mut self, forward: bool) {
+        let current = self.list_state.selected().unwrap_or(0);
+        let max = self.visible.len();
+        let mut target = current;
+        let mut used = 0;
+        loop {
+            let next = if forward { target + 1 } else { target.wrapping_sub(1) };
+            if next > max {
+                break;
+            }
+            used += self.list_row_heights.get(next).copied().unwrap_or(1);
+            // Always move at least one row, even if it's taller than the page
+            if used > self.list_page_height && target != current {
+                break;
+            }
+            target = next;
+        }
+        self.list_state.select(Some(target));
+    }
+    
+    // While a search is applied the list holds exactly its matches, so n/N step through
+    // the list rows, wrapping at either end
+    fn cycle_match(&mut self, forward: bool) {
+        if self.search_query.is_empty() {
+            self.status_message = Some("No search to step through; press / to search".to_string());
+            return;
+        }
+        let count = self.visible.len();
+        if count == 0 {
+            return;
+        }
+        // Rows 1..=count are the matches; from the create row, start at either end
+        let next = match (self.list_state.selected().filter(|&row| row > 0), forward) {
+            (None, true) => 1,
+            (None, false) => count,
+            (Some(row), true) => row % count + 1,
+            (Some(row), false) => (row + count - 2) % count + 1,
+        };
+        self.list_state.select(Some(next));
+    }
+    
+    fn visible_date(&self, position: usize) -> NaiveDate {
+        self.journal.entries()[self.visible[position]].created.date_naive()
+    }
+    
+    // Select a random entry from the current list, never the one already selected
+    fn jump_to_random(&mut self) {
+        use rand::Rng;
+        let count = self.visible.len();
+        if count == 0 {
+            return;
+        }
+        let current = self.list_state.selected().and_then(|s| s.checked_sub(1));
+        let mut pick = rand::thread_rng().gen_range(0..count);
+        if count > 1 && Some(pick) == current {
+            pick = (pick + 1) % count;
+        }
+        self.list_state.select(Some(pick + 1));
+    }
+    
+    // Jump down the list to the first entry of the next (older) day
+    fn jump_to_next_day(&mut self) {
+        if self.visible.is_empty() {
+            return;
+        }
+        let Some(current) = self.list_state.selected().and_then(|s| s.checked_sub(1)) else {
+            // From the create row, the first entry starts the first day
+            self.list_state.select(Some(1));
+            return;
+        };
+        
+        let date = self.visible_date(current);
+        let next = (current + 1..self.visible.len())
+            .find(|&i| self.visible_date(i) != date)
+            .unwrap_or(self.visible.len() - 1);
+        self.list_state.select(Some(next + 1));
+    }
+    
+    // Jump to the first entry of the current day, or of the previous (newer) day if already there
+    fn jump_to_previous_day(&mut self) {
+        let Some(current) = self.list_state.selected().and_then(|s| s.checked_sub(1)) else {
+            return;
+        };
+        if current >= self.visible.len() {
+            return;
+        }
+        
+        let day_start = |app: &Self, i: usize| {
+            let date = app.visible_date(i);
+            (0..=i).rev().take_while(|&j| app.visible_date(j) == date).last().unwrap_or(i)
+        };
+        let start = day_start(self, current);
+        let target = if start < current || start == 0 {
+            start
+        } else {
+            day_start(self, start - 1)
+        };
+        self.list_state.select(Some(target + 1));
+    }
+}
+
+// Locked entries only match on their title, so a search can't reveal words in the hidden text
+fn matches_search(entry: &JournalEntry, query: &str) -> bool {
+    query.is_empty()
+        || search::contains(&entry.title, query)
+        || (!entry.locked && entry.content.as_deref().is_some_and(|c| search::contains(c, query)))
+}
+
+// Undo the TUI's escape-sequence setup: bracketed paste off, back to the main screen and
+// the cursor shown. Raw mode is a terminal setting rather than output, see restore_terminal.
+fn write_restore_sequence(out: &mut impl io::Write) -> io::Result<()> {
+    // Legacy Windows consoles reject bracketed paste, which mustn't stop the rest
+    let _ = execute!(out, DisableBracketedPaste);
+    execute!(out, LeaveAlternateScreen, crossterm::cursor::Show)
+}
+
+// Best-effort restore for the Ctrl-C handler and the panic hook, which have nowhere to report errors
+fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = write_restore_sequence(&mut io::stdout());
 }
 
 fn main() -> Result<()> {
+    // Handle informational flags before touching the terminal or the vault
+    let cli = cli::Cli::parse()?;
+    match cli.action {
+        cli::Action::Help => {
+            println!("{}", cli::USAGE);
+            return Ok(());
+        }
+        cli::Action::Version => {
+            println!("journal-tui {}", env!("CARGO_PKG_VERSION"));
+            return Ok(());
+        }
+        cli::Action::List(format) => return list_entries(format),
+        cli::Action::ChangePassword => return change_vault_password(),
+        cli::Action::NewFromStdin(title) => return create_from_stdin(&title),
+        cli::Action::ExportHtml { path, entry } => return export_html(&path, entry.as_deref()),
+        cli::Action::Check { fix } => return check_journal(fix),
+        cli::Action::Import(path) => return import_entries(&path),
+        cli::Action::Run => {}
+    }
+    
     // Set up panic hook to restore terminal on panic
     let original_hook = std::panic::take_hook();
     std::panic::set_hook(Box::new(move |panic_info| {
-        // Restore terminal
-        let _ = disable_raw_mode();
-        let _ = execute!(
-            io::stdout(),
-            LeaveAlternateScreen,
-            crossterm::cursor::Show
-        );
+        restore_terminal();
         original_hook(panic_info);
     }));
+    // Read before authentication since it also configures the unlock animation
+    let config = Config::load()?;
+    // --accessible isn't written back to the config when layout changes are saved
+    let theme = if cli.accessible {
+        Theme::from_config(&ThemeConfig { preset: "accessible".to_string(), ..ThemeConfig::default() })?
+    } else {
+        Theme::from_config(&config.theme)?
+    };
+    
+    // A brand-new install (no config file, no vault) chooses where and how before anything is created
+    let config = if !Config::path()?.exists() && !VolumeManager::from_config(&config.vault)?.dmg_exists() {
+        match onboarding::run(&config, &theme)? {
+            Some(mut config) => {
+                // Without a disk image, entry encryption is what keeps the journal private
+                config.encryption.enabled |= !volume::DISK_IMAGE;
+                config.save()?;
+                config
+            }
+            None => {
+                println!("Setup cancelled; nothing was created");
+                return Ok(());
+            }
+        }
+    } else {
+        config
+    };
+    
+    // A vault folder isn't encrypted itself, so its entries have to be
+    if !volume::DISK_IMAGE && !config.encryption.enabled {
+        bail!(
+            "On this platform the vault is a plain folder; set [encryption] enabled = true in {} so entries are encrypted",
+            Config::path()?.display()
+        );
+    }
+    
     // Initialize volume manager to check if setup is needed
-    let volume_manager = VolumeManager::new();
+    let mut volume_manager = VolumeManager::from_config(&config.vault)?;
+    // A second window would mount and unmount the same image out from under this one
+    // Kept until exit; the OS releases it even when the process is killed
+    let _instance_lock = match InstanceLock::acquire(InstanceLock::path_for(volume_manager.dmg_path())) {
+        Ok(lock) => lock,
+        Err(err) => {
+            eprintln!("{:#}", err);
+            std::process::exit(1);
+        }
+    };
+    // Asked before the alternate screen, since the prompt is a plain terminal line
+    if volume::DISK_IMAGE && !volume_manager.remembers_password() && !volume_manager.is_mounted() {
+        let password = if volume_manager.dmg_exists() {
+            read_hidden("Vault password: ")?
+        } else {
+            println!("Choose a vault password. It is never stored, so keep a record of it:");
+            println!("without it the journal can't be opened.");
+            read_new_password()?
+        };
+        volume_manager.set_session_password(password);
+    }
+    
+    // SIGINT/SIGTERM from outside raw mode (or another process) gets the same teardown as
+    // quitting: restore the terminal and lock the vault
+    let vm = volume_manager.clone();
+    ctrlc::set_handler(move || {
+        restore_terminal();
+        let _ = vm.unmount();
+        crypto::remove_private_temps();
+        std::process::exit(130);
+    })?;
     
     // Check if vault needs to be created and use appropriate authentication
     let is_first_time = !volume_manager.dmg_exists();
@@ -218,27 +1692,52 @@ fn main() -> Result<()> {
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen)?;
     
+    // Mount as soon as authentication succeeds so the decode message can count real entries.
+    // A failed mount falls back to the generic message and is reported by the mount below.
+    let vm = volume_manager.clone();
+    let decode_config = config.clone();
+    let decode_message = move || {
+        let extensions = &decode_config.entries.extensions;
+        let message = match vm.mount_with_keychain().and_then(|_| vm.count_entries(extensions)) {
+            Ok(1) => "ACCESS GRANTED - DECRYPTING 1 MEMORY BLOCK".to_string(),
+            Ok(count) => format!("ACCESS GRANTED - DECRYPTING {} MEMORY BLOCKS", count),
+            Err(_) => return ("ACCESS GRANTED - DECRYPTING JOURNAL".to_string(), Vec::new()),
+        };
+        let titles = if decode_config.animation.decode_titles { newest_titles(&vm, &decode_config) } else { Vec::new() };
+        (message, titles)
+    };
+    
     // Run authentication in the alternate screen
     // For first time, also create the vault during the animation
+    let auth_config = config.auth.clone();
+    let auth_timeout = Duration::from_secs(config.auth.timeout_secs);
     let authenticated = if is_first_time {
         let vm = volume_manager.clone();
-        matrix::run_matrix_authentication_keep_screen(move || {
-            // First authenticate
-            if !auth::authenticate()? {
-                return Ok(false);
-            }
-            // Then create the vault while animation continues
-            vm.create_encrypted_volume()?;
-            Ok(true)
-        }, is_first_time)?
+        matrix::run_matrix_vault_creation_keep_screen(move |progress| {
+            let result = create_vault(&vm, &auth_config, progress);
+            // Nothing existed before, so anything short of a finished vault goes
+            if !matches!(result, Ok(true)) || progress.cancelled() {
+                let _ = vm.discard_vault();
+            }
+            result
+        }, &config.animation, &theme)?
     } else {
-        matrix::run_matrix_authentication_keep_screen(|| auth::authenticate(), is_first_time)?
+        matrix::run_matrix_authentication_keep_screen(move || auth::authenticate(&auth_config), decode_message, auth_timeout, &config.animation, &theme)?
     };
     
     if !authenticated {
         disable_raw_mode()?;
         execute!(stdout, LeaveAlternateScreen, crossterm::cursor::Show)?;
-        println!("Authentication required to access journal");
+        if is_first_time {
+            println!("Journal setup didn't finish; no vault was created");
+        } else {
+            // A timed-out or cancelled unlock never mounts, but the vault must not be left
+            // open whatever got it mounted
+            if volume_manager.is_mounted() {
+                let _ = volume_manager.unmount();
+            }
+            println!("Authentication required to access journal");
+        }
         return Ok(());
     }
     
@@ -255,8 +1754,7 @@ fn main() -> Result<()> {
             
             // Check for existing entries to migrate (only relevant for first time)
             if is_first_time {
-                let home_dir = dirs::home_dir().expect("Could not find home directory");
-                let old_entries = home_dir.join(".journal").join("entries");
+                let old_entries = config::home_dir()?.join(".journal").join("entries");
                 
                 if old_entries.exists() {
                     match volume_manager.migrate_entries(&old_entries) {
@@ -279,6 +1777,8 @@ fn main() -> Result<()> {
             if is_first_time {
                 println!("\n⚠️  Vault created but couldn't mount automatically.");
                 println!("You may need to approve keychain access on next run.");
+            } else if !volume_manager.remembers_password() {
+                println!("\n⚠️  Unable to unlock the vault; check the password and try again.");
             } else {
                 println!("\n⚠️  Unable to unlock vault automatically.");
                 println!("\nPossible solutions:");
@@ -294,21 +1794,44 @@ fn main() -> Result<()> {
     let backend = CrosstermBackend::new(io::stdout());
     let mut terminal = Terminal::new(backend)?;
     
-    let app = App::new(volume_manager)?;
+    // Always use encrypted volume path
+    let journal = Journal::open(volume_manager.get_entries_path(), volume_manager.get_trash_path(), &config)?;
+    let app = App::new(journal, volume_manager, &config, theme)?;
+    // Pastes arrive as one event, so a newline in pasted text can't submit a prompt.
+    // Legacy Windows consoles don't support it and keep delivering keys.
+    let _ = execute!(io::stdout(), EnableBracketedPaste);
     let res = run_app(&mut terminal, app);
+    let _ = execute!(io::stdout(), DisableBracketedPaste);
+    crypto::remove_private_temps();
     
     // Handle the result and show animation if needed
     let exit_result = match res {
+        // Nothing from the journal may remain visible, including in the scrollback
+        Err(e) if e.to_string() == "PANIC_EXIT" => {
+            terminal.clear()?;
+            disable_raw_mode()?;
+            execute!(
+                io::stdout(),
+                LeaveAlternateScreen,
+                crossterm::terminal::Clear(ClearType::All),
+                crossterm::terminal::Clear(ClearType::Purge),
+                crossterm::cursor::MoveTo(0, 0),
+                crossterm::cursor::Show
+            )?;
+            std::process::exit(0);
+        }
         Err(e) if e.to_string() == "ENCRYPT_EXIT" => {
             // Don't leave alternate screen here - reuse it for encrypting animation
             terminal.clear()?;
             
             // Run encrypting animation using the same screen
-            matrix::run_matrix_encrypting_animation_keep_screen()?;
+            matrix::run_matrix_encrypting_animation_keep_screen(&config.animation, &theme)?;
             Ok(())
         }
         Err(err) => {
-            eprintln!("Error: {err:?}");
+            // Show the error in the matrix theme before tearing down the screen
+            terminal.clear()?;
+            matrix::run_matrix_error_screen(&err, &config.animation, &theme)?;
             Err(err)
         }
         Ok(_) => Ok(())
@@ -322,11 +1845,214 @@ fn main() -> Result<()> {
         crossterm::cursor::Show
     )?;
     
-    exit_result?;
+    // The error has already been shown on the error screen, so skip the raw dump
+    if exit_result.is_err() {
+        std::process::exit(1);
+    }
+    
+    Ok(())
+}
+
+// Non-interactive `--list`: unlock, print, and leave the vault as we found it
+fn list_entries(format: listing::OutputFormat) -> Result<()> {
+    let config = Config::load()?;
+    let output = with_journal(&config, config.cli.list_requires_auth, |journal| {
+        journal.load(false)?;
+        for error in journal.load_errors() {
+            eprintln!("warning: skipped {}", error);
+        }
+        listing::format_entries(journal.entries(), format)
+    })?;
+    println!("{}", output);
+    Ok(())
+}
+
+// `--new-from-stdin`: write piped text as an entry without opening an editor. Adding an
+// entry reveals nothing from the journal, so unlike --list this never asks to authenticate.
+fn create_from_stdin(title: &str) -> Result<()> {
+    use std::io::{IsTerminal, Read};
+    if io::stdin().is_terminal() {
+        bail!("--new-from-stdin reads the entry body from a pipe, e.g. echo \"text\" | journal-tui --new-from-stdin \"Title\"");
+    }
+    let mut body = String::new();
+    io::stdin().read_to_string(&mut body).context("Failed to read stdin")?;
+    
+    let config = Config::load()?;
+    let path = with_journal(&config, false, |journal| journal.create(title.trim(), body.trim_end()))?;
+    println!("{}", path.display());
+    Ok(())
+}
+
+// `--import`: every usable record becomes an entry dated as in the export
+fn import_entries(path: &Path) -> Result<()> {
+    let json = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let (records, skipped) = journal_tui::import::parse(&json).with_context(|| format!("Can't import {}", path.display()))?;
+    for reason in &skipped {
+        eprintln!("warning: skipped {}", reason);
+    }
+    
+    let config = Config::load()?;
+    let imported = with_journal(&config, false, |journal| {
+        for record in &records {
+            journal.create_at(&record.title, &record.text, record.created)?;
+        }
+        Ok(records.len())
+    })?;
+    println!("Imported {} {} from {}, skipped {}", imported, if imported == 1 { "entry" } else { "entries" }, path.display(), skipped.len());
+    Ok(())
+}
+
+// `--export-html`: like --list this reveals entry text, so it always authenticates
+fn export_html(output: &Path, title: Option<&str>) -> Result<()> {
+    let config = Config::load()?;
+    let theme = Theme::from_config(&config.theme)?;
+    let html = with_journal(&config, true, |journal| {
+        journal.load(false)?;
+        let entries = journal.entries();
+        let entry = match title {
+            None => entries.first().ok_or_else(|| anyhow!("The journal has no entries to export"))?,
+            Some(title) => {
+                let wanted = title.to_lowercase();
+                let matches: Vec<&JournalEntry> = entries.iter().filter(|e| e.title.to_lowercase().contains(&wanted)).collect();
+                match matches.iter().find(|e| e.title.to_lowercase() == wanted).or(matches.first()) {
+                    Some(entry) if matches.len() == 1 || entry.title.to_lowercase() == wanted => *entry,
+                    Some(_) => bail!("'{}' matches {} entries; use a longer title", title, matches.len()),
+                    None => bail!("No entry titled '{}'", title),
+                }
+            }
+        };
+        Ok(export::entry_to_html(&journal.read(&entry.path)?, &theme))
+    })?;
+    fs::write(output, html).with_context(|| format!("Failed to write {}", output.display()))?;
+    println!("{}", output.display());
+    Ok(())
+}
+
+fn check_journal(fix: bool) -> Result<()> {
+    let config = Config::load()?;
+    let found = with_journal(&config, true, |journal| {
+        let issues = journal.audit();
+        for issue in &issues {
+            println!("{}: {}", issue.path.display(), issue.kind);
+            if fix {
+                match journal.fix(issue) {
+                    Ok(Some(done)) => println!("  fixed: {}", done),
+                    Ok(None) => println!("  left as is; needs fixing by hand"),
+                    Err(err) => println!("  not fixed: {:#}", err),
+                }
+            }
+        }
+        Ok(issues.len())
+    })?;
+    if found == 0 {
+        println!("No problems found");
+    }
+    Ok(())
+}
+
+// First-run setup, step by step so the animation can show progress. Returns false if
+// authentication fails or the user cancels between steps.
+fn create_vault(vm: &VolumeManager, auth_config: &config::AuthConfig, progress: &matrix::Progress) -> Result<bool> {
+    progress.phase("AWAITING AUTHENTICATION");
+    if !auth::authenticate(auth_config)? || progress.cancelled() {
+        return Ok(false);
+    }
+    progress.phase("CREATING ENCRYPTED VOLUME");
+    vm.create_encrypted_volume()?;
+    if progress.cancelled() {
+        return Ok(false);
+    }
+    progress.phase("MOUNTING VAULT");
+    vm.mount_with_keychain()?;
+    if progress.cancelled() {
+        return Ok(false);
+    }
+    progress.phase("FINALIZING");
+    fs::create_dir_all(vm.get_entries_path())?;
+    Ok(true)
+}
+
+// Mount the vault if needed, run `f` on the journal, and leave the vault as we found it
+fn with_journal<T>(config: &Config, authenticate: bool, f: impl FnOnce(&mut Journal) -> Result<T>) -> Result<T> {
+    let mut volume_manager = VolumeManager::from_config(&config.vault)?;
+    if !volume_manager.dmg_exists() {
+        bail!("No journal vault found; run journal-tui once to create it");
+    }
+    // Held until we return, so the vault isn't unmounted under an open window or vice versa
+    let _instance_lock = InstanceLock::acquire(InstanceLock::path_for(volume_manager.dmg_path()))?;
+    if authenticate && !auth::authenticate(&config.auth)? {
+        bail!("Authentication required to access the journal");
+    }
+    
+    let was_mounted = volume_manager.is_mounted();
+    if !was_mounted && !volume_manager.remembers_password() {
+        volume_manager.set_session_password(read_hidden("Vault password: ")?);
+    }
+    volume_manager.mount_with_keychain()?;
+    
+    let result = Journal::open(volume_manager.get_entries_path(), volume_manager.get_trash_path(), config)
+        .and_then(|mut journal| f(&mut journal));
+    
+    if !was_mounted {
+        let _ = volume_manager.unmount();
+    }
+    result
+}
+
+fn change_vault_password() -> Result<()> {
+    let config = Config::load()?;
+    let volume_manager = VolumeManager::from_config(&config.vault)?;
+    if !auth::authenticate(&config.auth)? {
+        bail!("Authentication required to change the vault password");
+    }
     
+    if !volume_manager.remembers_password() {
+        let current = read_hidden("Current vault password: ")?;
+        volume_manager.change_password(Some(&current), &read_new_password()?)?;
+        println!("Vault password changed; it isn't stored, so update your own record of it");
+        return Ok(());
+    }
+    volume_manager.change_password(None, &read_new_password()?)?;
+    println!("Vault password changed and saved to the keychain");
     Ok(())
 }
 
+fn read_new_password() -> Result<String> {
+    let new = read_hidden("New vault password: ")?;
+    if read_hidden("Repeat new password: ")? != new {
+        bail!("Passwords didn't match; nothing was changed");
+    }
+    Ok(new)
+}
+
+// Read a line without echoing it
+fn read_hidden(prompt: &str) -> Result<String> {
+    use std::io::Write;
+    print!("{}", prompt);
+    io::stdout().flush()?;
+    
+    enable_raw_mode()?;
+    let mut input = String::new();
+    let result = loop {
+        match event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Enter => break Ok(()),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break Err(anyhow::anyhow!("Cancelled")),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            },
+            Ok(_) => {}
+            Err(err) => break Err(err.into()),
+        }
+    };
+    disable_raw_mode()?;
+    println!();
+    result.map(|_| input)
+}
+
 fn run_app<B: ratatui::backend::Backend>(
     terminal: &mut Terminal<B>,
     mut app: App,
@@ -335,41 +2061,113 @@ fn run_app<B: ratatui::backend::Backend>(
     terminal.draw(|f| ui(f, &mut app))?;
     
     loop {
+        // Pick up edits made outside the app; our own writes reload nothing new and stay quiet
+        if app.watcher.as_mut().is_some_and(|w| w.poll()) && app.reload_from_disk()? {
+            app.refreshed_at = Some(Instant::now());
+        }
+        
         // Poll for events with a timeout to prevent blocking
-        if event::poll(Duration::from_millis(50))? {
-            if let Event::Key(key) = event::read()? {
-                // Only process key press events, ignore key release events
-                if key.kind != KeyEventKind::Press {
+        let event = if event::poll(Duration::from_millis(50))? { Some(event::read()?) } else { None };
+        // Before the key is handled, so a `y` arriving after the deadline doesn't count
+        app.expire_confirmation();
+        if event.is_some() {
+            app.last_input = Instant::now();
+            // The key that dismisses the screensaver does nothing else
+            if app.screensaver.take().is_some() {
+                terminal.draw(|f| ui(f, &mut app))?;
+                continue;
+            }
+        } else {
+            app.tick_screensaver(terminal.size()?);
+        }
+        if let Some(Event::Paste(text)) = &event {
+            app.paste(text);
+        }
+        if let Some(Event::Key(key)) = event {
+            // Only process key press events, ignore key release events
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            
+            // Status messages only last until the next key press
+            app.status_message = None;
+            
+            // The panic key beats everything: lock, wipe and leave without a confirmation or animation
+            if app.keymap.action_for(&key) == Some(Action::Panic) {
+                let _ = app.volume_manager.unmount();
+                crypto::remove_private_temps();
+                return Err(anyhow::anyhow!("PANIC_EXIT"));
+            }
+            
+            // Force quit works from any mode: Ctrl-Q and Ctrl-C always (raw mode delivers Ctrl-C
+            // as a key rather than SIGINT), Q whenever keys aren't being typed
+            let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+            let force_quit = (ctrl && matches!(key.code, KeyCode::Char('q') | KeyCode::Char('c')))
+                || (key.code == KeyCode::Char('Q') && !app.mode.is_text_input());
+            if force_quit {
+                // Unsaved edits in the built-in editor take a second press, as with Esc
+                if let Some(editor) = app.editor.as_mut().filter(|e| e.modified && !e.confirm_discard) {
+                    editor.confirm_discard = true;
+                    app.status_message = Some("Unsaved changes: Ctrl-S to save, Ctrl-Q again to quit without saving".to_string());
+                    terminal.draw(|f| ui(f, &mut app))?;
                     continue;
                 }
+                return lock_and_exit(&app);
+            }
+            
+            // Alt-1..9 jump to bookmarks; they aren't rebindable like the keymap's actions
+            let bookmark_slot = match key.code {
+                KeyCode::Char(c @ '1'..='9') if key.modifiers.contains(KeyModifiers::ALT) => c.to_digit(10),
+                _ => None,
+            };
             
             let needs_refresh = match app.mode {
-                AppMode::Normal => match key.code {
-                    KeyCode::Char('q') => {
-                        // Always unmount encrypted volume
-                        let _ = app.volume_manager.unmount();
-                        return Err(anyhow::anyhow!("ENCRYPT_EXIT"));
+                AppMode::Normal if let Some(slot) = bookmark_slot => {
+                    app.jump_to_bookmark(slot as usize);
+                    false
+                }
+                AppMode::Normal => match app.keymap.action_for(&key) {
+                    Some(Action::Quit) => {
+                        // Only worth asking when quitting actually locks a mounted vault
+                        if app.config.display.confirm_quit && app.volume_manager.is_mounted() {
+                            app.mode = AppMode::ConfirmQuit;
+                            false
+                        } else {
+                            return lock_and_exit(&app);
+                        }
                     }
-                    KeyCode::Char('j') | KeyCode::Down => {
+                    Some(Action::Down) => {
                         app.move_selection_down();
                         false
                     }
-                    KeyCode::Char('k') | KeyCode::Up => {
+                    Some(Action::Up) => {
                         app.move_selection_up();
                         false
                     }
-                    KeyCode::Char('g') => {
-                        if key.modifiers.contains(KeyModifiers::NONE) {
-                            app.list_state.select(Some(0));
-                        }
+                    Some(Action::Top) => {
+                        app.list_state.select(Some(0));
                         false
                     }
-                    KeyCode::Char('G') => {
-                        let max = app.entries.len();
+                    Some(Action::Bottom) => {
+                        let max = app.visible.len();
                         app.list_state.select(Some(max));
                         false
                     }
-                    KeyCode::Enter => {
+                    Some(Action::PageDown) => {
+                        app.page_selection(true);
+                        false
+                    }
+                    Some(Action::PageUp) => {
+                        app.page_selection(false);
+                        false
+                    }
+                    Some(Action::Create) => {
+                        app.mode = AppMode::TitleInput;
+                        false
+                    }
+                    // Need full refresh after vim, unless we stopped to ask
+                    Some(Action::CreateDated) => app.create_dated_entry()?,
+                    Some(Action::Open) => {
                         if let Some(0) = app.list_state.selected() {
                             app.mode = AppMode::TitleInput;
                             false
@@ -379,8 +2177,471 @@ fn run_app<B: ratatui::backend::Backend>(
                             true
                         }
                     }
+                    Some(Action::Delete) => {
+                        if !app.targets().is_empty() {
+                            app.confirm(AppMode::ConfirmDelete);
+                        }
+                        false
+                    }
+                    Some(Action::Undo) => {
+                        app.undo_delete()?;
+                        false
+                    }
+                    Some(Action::Trash) => {
+                        app.open_trash()?;
+                        false
+                    }
+                    Some(Action::ToggleTimestamps) => {
+                        app.relative_timestamps = !app.relative_timestamps;
+                        false
+                    }
+                    Some(Action::CompactList) => {
+                        app.compact = !app.compact;
+                        false
+                    }
+                    Some(Action::Capture) => {
+                        app.mode = AppMode::QuickCapture;
+                        false
+                    }
+                    Some(Action::Search) => {
+                        app.mode = AppMode::Search;
+                        false
+                    }
+                    Some(Action::ShrinkList) => {
+                        app.resize_list(-5);
+                        false
+                    }
+                    Some(Action::GrowList) => {
+                        app.resize_list(5);
+                        false
+                    }
+                    Some(Action::TogglePreview) => {
+                        app.toggle_preview();
+                        false
+                    }
+                    Some(Action::ToggleWrap) => {
+                        app.preview_wrap = !app.preview_wrap;
+                        app.preview_hscroll = 0;
+                        false
+                    }
+                    Some(Action::PanLeft) => {
+                        app.preview_hscroll = app.preview_hscroll.saturating_sub(PAN_STEP);
+                        false
+                    }
+                    Some(Action::PanRight) => {
+                        // Panning only means something when lines aren't wrapped
+                        if !app.preview_wrap {
+                            app.preview_hscroll = app.preview_hscroll.saturating_add(PAN_STEP);
+                        }
+                        false
+                    }
+                    Some(Action::Duplicates) => {
+                        app.open_duplicates();
+                        false
+                    }
+                    // Need full refresh after vim
+                    Some(Action::EditLast) => app.open_last_entry()? && !app.config.entries.builtin_editor,
+                    Some(Action::EditInApp) => {
+                        if let Some(path) = app.selected_entry().map(|e| e.path.clone())
+                            && let Err(err) = app.edit_in_app(&path, 0)
+                        {
+                            app.status_message = Some(format!("Error: {:#}", err));
+                        }
+                        false
+                    }
+                    Some(Action::View) => {
+                        app.view_entry();
+                        false
+                    }
+                    Some(Action::TodayFilter) => {
+                        app.toggle_date_filter(DateFilter::today());
+                        false
+                    }
+                    Some(Action::Archive) => {
+                        app.toggle_archive()?;
+                        false
+                    }
+                    Some(Action::ToggleArchived) => {
+                        app.toggle_show_archived()?;
+                        false
+                    }
+                    Some(Action::NextDay) => {
+                        app.jump_to_next_day();
+                        false
+                    }
+                    Some(Action::WordReport) => {
+                        app.open_word_report();
+                        false
+                    }
+                    Some(Action::OpenDir) => {
+                        app.open_dir_picker();
+                        false
+                    }
+                    Some(Action::Tags) => {
+                        app.open_tag_browser();
+                        false
+                    }
+                    Some(Action::CopyEntry) => {
+                        app.copy_entry();
+                        false
+                    }
+                    Some(Action::Refresh) => {
+                        let changed = app.reload_from_disk()?;
+                        app.refreshed_at = Some(Instant::now());
+                        app.status_message = Some(if changed {
+                            format!("Reloaded {} entries from disk", app.journal.entries().len())
+                        } else {
+                            "Already up to date".to_string()
+                        });
+                        false
+                    }
+                    Some(Action::PreviousDay) => {
+                        app.jump_to_previous_day();
+                        false
+                    }
+                    Some(Action::ClearSearch) => {
+                        if !app.search_query.is_empty() {
+                            app.set_search_query(String::new());
+                        } else if app.tag_filter.is_some() {
+                            app.set_tag_filter(None);
+                        } else {
+                            app.marked.clear();
+                        }
+                        false
+                    }
+                    Some(Action::Random) => {
+                        app.jump_to_random();
+                        false
+                    }
+                    Some(Action::NextMatch) => {
+                        app.cycle_match(true);
+                        false
+                    }
+                    Some(Action::PreviousMatch) => {
+                        app.cycle_match(false);
+                        false
+                    }
+                    Some(Action::Mark) => {
+                        app.toggle_mark();
+                        false
+                    }
+                    Some(Action::Bookmark) => {
+                        if app.selected_entry().is_some() {
+                            app.mode = AppMode::BookmarkSlot;
+                        } else {
+                            app.status_message = Some("Select an entry to bookmark".to_string());
+                        }
+                        false
+                    }
+                    // Handled above, before the mode dispatch
+                    Some(Action::Panic) | None => false
+                },
+                AppMode::Search => match key.code {
+                    KeyCode::Esc => {
+                        app.set_search_query(String::new());
+                        app.mode = AppMode::Normal;
+                        false
+                    }
+                    KeyCode::Enter => {
+                        // Keep the filter applied while browsing the results
+                        app.mode = AppMode::Normal;
+                        false
+                    }
+                    KeyCode::Backspace => {
+                        let mut query = app.search_query.clone();
+                        query.pop();
+                        app.set_search_query(query);
+                        false
+                    }
+                    KeyCode::Char(c) => {
+                        let query = format!("{}{}", app.search_query, c);
+                        app.set_search_query(query);
+                        false
+                    }
+                    _ => false
+                },
+                AppMode::QuickCapture => match key.code {
+                    KeyCode::Esc => {
+                        app.capture_input.clear();
+                        app.mode = AppMode::Normal;
+                        false
+                    }
+                    KeyCode::Enter => {
+                        let line = std::mem::take(&mut app.capture_input);
+                        app.mode = AppMode::Normal;
+                        app.append_to_today(&line)?;
+                        false
+                    }
+                    KeyCode::Backspace => {
+                        app.capture_input.pop();
+                        false
+                    }
+                    KeyCode::Char(c) => {
+                        app.capture_input.push(c);
+                        false
+                    }
+                    _ => false
+                },
+                AppMode::ConfirmDelete => {
+                    if key.code == KeyCode::Char('y') {
+                        app.trash_entry()?;
+                    }
+                    // Any other key cancels
+                    app.mode = AppMode::Normal;
+                    false
+                }
+                AppMode::TagBrowser => {
+                    match key.code {
+                        KeyCode::Esc | KeyCode::Char('q') => app.mode = AppMode::Normal,
+                        KeyCode::Char('j') | KeyCode::Down => {
+                            if let Some(i) = app.tag_state.selected()
+                                && i + 1 < app.tag_counts.len()
+                            {
+                                app.tag_state.select(Some(i + 1));
+                            }
+                        }
+                        KeyCode::Char('k') | KeyCode::Up => {
+                            if let Some(i) = app.tag_state.selected() {
+                                app.tag_state.select(Some(i.saturating_sub(1)));
+                            }
+                        }
+                        KeyCode::Enter => {
+                            let tag = app.tag_state.selected().and_then(|i| app.tag_counts.get(i)).map(|(tag, _)| tag.clone());
+                            if tag.is_some() {
+                                app.set_tag_filter(tag);
+                            }
+                            app.mode = AppMode::Normal;
+                        }
+                        _ => {}
+                    }
+                    false
+                }
+                AppMode::Trash => match key.code {
+                    KeyCode::Esc | KeyCode::Char('q') => {
+                        app.mode = AppMode::Normal;
+                        false
+                    }
+                    KeyCode::Char('j') | KeyCode::Down => {
+                        if let Some(i) = app.trash_state.selected()
+                            && i + 1 < app.trash_entries.len()
+                        {
+                            app.trash_state.select(Some(i + 1));
+                        }
+                        false
+                    }
+                    KeyCode::Char('k') | KeyCode::Up => {
+                        if let Some(i) = app.trash_state.selected() {
+                            app.trash_state.select(Some(i.saturating_sub(1)));
+                        }
+                        false
+                    }
+                    KeyCode::Char('r') => {
+                        app.restore_from_trash()?;
+                        false
+                    }
+                    KeyCode::Char('x') => {
+                        if app.selected_trash_entry().is_some() {
+                            app.confirm(AppMode::ConfirmPurge);
+                        }
+                        false
+                    }
+                    _ => false
+                },
+                // Any key skips a running reveal
+                AppMode::View if app.view_reveal.is_some() => {
+                    app.view_reveal = None;
+                    false
+                }
+                AppMode::View => {
+                    let page = app.view_height.max(1);
+                    match key.code {
+                        // Back out of the diff first, then the viewer
+                        KeyCode::Esc | KeyCode::Char('q') if app.view_diff.is_some() => app.toggle_view_diff(),
+                        KeyCode::Esc if !app.view_search.is_empty() => app.clear_view_search(),
+                        KeyCode::Char('/') => {
+                            app.clear_view_search();
+                            app.mode = AppMode::ViewSearch;
+                        }
+                        KeyCode::Char('n') if !app.view_matches.is_empty() => app.cycle_view_match(true),
+                        KeyCode::Char('N') if !app.view_matches.is_empty() => app.cycle_view_match(false),
+                        KeyCode::Esc | KeyCode::Char('q') => {
+                            app.remember_view_position();
+                            app.mode = AppMode::Normal;
+                        }
+                        KeyCode::Char('d') => app.toggle_view_diff(),
+                        KeyCode::Char('j') | KeyCode::Down => app.view_scroll += 1,
+                        KeyCode::Char('k') | KeyCode::Up => app.view_scroll = app.view_scroll.saturating_sub(1),
+                        KeyCode::PageDown | KeyCode::Char(' ') => app.view_scroll += page,
+                        KeyCode::PageUp => app.view_scroll = app.view_scroll.saturating_sub(page),
+                        KeyCode::Char('g') | KeyCode::Home => app.view_scroll = 0,
+                        // Clamped to the last page when rendering
+                        KeyCode::Char('G') | KeyCode::End => app.view_scroll = usize::MAX,
+                        KeyCode::Tab if app.view_diff.is_none() => app.cycle_link(true),
+                        KeyCode::BackTab if app.view_diff.is_none() => app.cycle_link(false),
+                        KeyCode::Enter if app.view_diff.is_none() => app.follow_link(),
+                        _ => {}
+                    }
+                    false
+                }
+                AppMode::Edit => {
+                    let Some(editor) = app.editor.as_mut() else {
+                        app.mode = AppMode::Normal;
+                        continue;
+                    };
+                    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+                    match key.code {
+                        KeyCode::Char('s') if ctrl => {
+                            if let Err(err) = app.save_editor() {
+                                app.status_message = Some(format!("Error: {:#}", err));
+                            }
+                        }
+                        // Unsaved changes take a second Esc to throw away
+                        KeyCode::Esc if editor.modified && !editor.confirm_discard => {
+                            editor.confirm_discard = true;
+                            app.status_message = Some("Unsaved changes: Ctrl-S to save, Esc again to discard".to_string());
+                        }
+                        KeyCode::Esc => {
+                            app.editor = None;
+                            app.mode = AppMode::Normal;
+                        }
+                        KeyCode::Enter => editor.newline(),
+                        KeyCode::Backspace => editor.backspace(),
+                        KeyCode::Delete => editor.delete(),
+                        KeyCode::Left => editor.left(),
+                        KeyCode::Right => editor.right(),
+                        KeyCode::Up => editor.up(),
+                        KeyCode::Down => editor.down(),
+                        KeyCode::Home => editor.home(),
+                        KeyCode::End => editor.end(),
+                        KeyCode::Char(c) if !ctrl => editor.insert(c),
+                        _ => {}
+                    }
+                    false
+                }
+                AppMode::ViewSearch => {
+                    match key.code {
+                        KeyCode::Esc => {
+                            app.clear_view_search();
+                            app.mode = AppMode::View;
+                        }
+                        KeyCode::Enter => {
+                            app.find_in_view();
+                            app.mode = AppMode::View;
+                        }
+                        KeyCode::Backspace => {
+                            app.view_search.pop();
+                        }
+                        KeyCode::Char(c) => app.view_search.push(c),
+                        _ => {}
+                    }
+                    false
+                }
+                // Any key dismisses the startup panel
+                AppMode::OnThisDay => {
+                    app.mode = AppMode::Normal;
+                    false
+                }
+                AppMode::WordReport => {
+                    if matches!(key.code, KeyCode::Esc | KeyCode::Char('q')) {
+                        app.mode = AppMode::Normal;
+                    }
+                    false
+                }
+                AppMode::Duplicates => match key.code {
+                    KeyCode::Esc | KeyCode::Char('q') => {
+                        app.mode = AppMode::Normal;
+                        false
+                    }
+                    KeyCode::Char('j') | KeyCode::Down => {
+                        if let Some(i) = app.duplicate_state.selected()
+                            && i + 1 < app.duplicate_groups.len()
+                        {
+                            app.duplicate_state.select(Some(i + 1));
+                        }
+                        false
+                    }
+                    KeyCode::Char('k') | KeyCode::Up => {
+                        if let Some(i) = app.duplicate_state.selected() {
+                            app.duplicate_state.select(Some(i.saturating_sub(1)));
+                        }
+                        false
+                    }
+                    KeyCode::Char('x') => {
+                        app.resolve_duplicate_group()?;
+                        false
+                    }
                     _ => false
                 },
+                AppMode::ConfirmPurge => {
+                    if key.code == KeyCode::Char('y') {
+                        app.purge_from_trash()?;
+                    }
+                    app.mode = AppMode::Trash;
+                    false
+                }
+                AppMode::ConfirmQuit => {
+                    if key.code == KeyCode::Char('y') {
+                        return lock_and_exit(&app);
+                    }
+                    app.mode = AppMode::Normal;
+                    false
+                }
+                AppMode::ConfirmOpenDated => {
+                    app.mode = AppMode::Normal;
+                    match key.code {
+                        KeyCode::Char('y') => {
+                            app.open_entry()?;
+                            true
+                        }
+                        KeyCode::Char('n') => {
+                            app.create_entry(&dated_title())?;
+                            true
+                        }
+                        _ => false,
+                    }
+                }
+                AppMode::BookmarkSlot => {
+                    app.mode = AppMode::Normal;
+                    if let KeyCode::Char(c @ '0'..='9') = key.code
+                        && let Err(err) = app.assign_bookmark(c as usize - '0' as usize)
+                    {
+                        app.status_message = Some(format!("Error: {:#}", err));
+                    }
+                    false
+                }
+                AppMode::DirPicker => {
+                    match key.code {
+                        KeyCode::Esc => {
+                            app.dir_input.clear();
+                            app.mode = AppMode::Normal;
+                        }
+                        KeyCode::Enter => {
+                            if let Err(err) = app.submit_dir() {
+                                app.status_message = Some(format!("Error: {:#}", err));
+                            }
+                        }
+                        KeyCode::Backspace => {
+                            app.dir_input.pop();
+                        }
+                        KeyCode::Char(c) => app.dir_input.push(c),
+                        _ => {}
+                    }
+                    false
+                }
+                AppMode::ConfirmCreateDir => {
+                    app.mode = AppMode::Normal;
+                    if let Some(dir) = app.pending_dir.take()
+                        && key.code == KeyCode::Char('y')
+                    {
+                        let created = fs::create_dir_all(&dir)
+                            .with_context(|| format!("Failed to create {}", dir.display()))
+                            .and_then(|_| app.switch_dir(dir));
+                        if let Err(err) = created {
+                            app.status_message = Some(format!("Error: {:#}", err));
+                        }
+                    }
+                    false
+                }
                 AppMode::TitleInput => match key.code {
                     KeyCode::Esc => {
                         app.title_input.clear();
@@ -408,7 +2669,9 @@ fn run_app<B: ratatui::backend::Backend>(
                 // Clear and resize terminal after vim
                 terminal.clear()?;
             }
-            }
+            // Idle time counts from when a key's work finished, so a long session in an
+            // external editor doesn't start the screensaver the moment it returns
+            app.last_input = Instant::now();
         }
         
         // Always redraw
@@ -416,7 +2679,117 @@ fn run_app<B: ratatui::backend::Backend>(
     }
 }
 
-fn render_preview_pane(f: &mut Frame, app: &App, area: Rect) {
+// " (cancels in 4s)" while a confirmation with a timeout is open
+fn confirm_countdown(app: &App) -> String {
+    app.confirm_remaining()
+        .map(|left| format!(" (cancels in {}s)", left.as_secs_f32().ceil() as u64))
+        .unwrap_or_default()
+}
+
+fn dated_title() -> String {
+    Local::now().format("%Y-%m-%d").to_string()
+}
+
+fn match_count(count: usize) -> String {
+    match count {
+        0 => "no matches".to_string(),
+        1 => "1 match".to_string(),
+        n => format!("{} matches", n),
+    }
+}
+
+// The newest few entries as "title · date" for the unlock screen, newest first. Only
+// the filenames are sorted so a large journal isn't read before it opens.
+fn newest_titles(vm: &VolumeManager, config: &Config) -> Vec<String> {
+    // Reading encrypted entries means deriving their key on the animation thread, and the
+    // journal opened after unlocking derives it again, so those just show the count
+    if config.encryption.enabled {
+        return Vec::new();
+    }
+    let Ok(files) = fs::read_dir(vm.get_entries_path()) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = files
+        .flatten()
+        .map(|file| file.path())
+        .filter(|path| journal_tui::journal::has_entry_extension(path, &config.entries.extensions))
+        .collect();
+    paths.sort_by(|a, b| b.file_name().cmp(&a.file_name()));
+    
+    paths.iter()
+        .take(DECODE_TITLES)
+        .map(|path| {
+            let content = fs::read_to_string(path).ok();
+            let title = journal_tui::journal::entry_title(path, content.as_deref(), &config.entries.filename_date_format);
+            let date = path.file_stem()
+                .and_then(|s| s.to_str())
+                .and_then(|stem| format::parse_filename_date(stem, &config.entries.filename_date_format));
+            match date {
+                Some((date, _)) => format!("{} · {}", title, date.format("%Y-%m-%d")),
+                None => title,
+            }
+        })
+        .collect()
+}
+
+// vi-style `+N` is understood by most terminal editors but would be opened as a file by others
+fn accepts_line_arg(program: &str) -> bool {
+    matches!(program_name(program), "vi" | "vim" | "nvim" | "nano" | "emacs" | "micro" | "kak")
+}
+
+// The flag that makes a GUI editor block until the file is closed
+fn wait_flag(program: &str) -> Option<&'static str> {
+    match program_name(program) {
+        "code" | "code-insiders" | "codium" | "cursor" | "windsurf" | "zed" | "atom" => Some("--wait"),
+        "subl" | "mate" => Some("-w"),
+        _ => None,
+    }
+}
+
+fn program_name(program: &str) -> &str {
+    let name = Path::new(program).file_name().and_then(|s| s.to_str()).unwrap_or(program);
+    name.strip_suffix(".exe").or_else(|| name.strip_suffix(".cmd")).unwrap_or(name)
+}
+
+// `editor` split into a command for `path`. `{file}` and `{line}` in it are filled in;
+// otherwise the path goes last, after `+line` for terminal editors known to take one.
+fn editor_command(editor: &str, path: &Path, line: Option<usize>, wait: bool) -> Command {
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vim");
+    let args: Vec<&str> = parts.collect();
+    let mut command = Command::new(program);
+    if wait
+        && let Some(flag) = wait_flag(program)
+        && !args.iter().any(|a| *a == flag || *a == "--wait" || *a == "-w")
+    {
+        command.arg(flag);
+    }
+    
+    let file = path.display().to_string();
+    let line_number = line.unwrap_or(1).to_string();
+    let templated = args.iter().any(|a| a.contains("{file}"));
+    for arg in &args {
+        command.arg(arg.replace("{file}", &file).replace("{line}", &line_number));
+    }
+    if !templated {
+        if let Some(line) = line
+            && accepts_line_arg(program)
+        {
+            command.arg(format!("+{}", line));
+        }
+        command.arg(path);
+    }
+    command
+}
+
+fn lock_and_exit(app: &App) -> Result<()> {
+    // Always unmount encrypted volume
+    let _ = app.volume_manager.unmount();
+    Err(anyhow::anyhow!("ENCRYPT_EXIT"))
+}
+
+fn render_preview_pane(f: &mut Frame, app: &App, area: Rect, query: &str) {
+    let theme = app.theme;
     let selected = app.list_state.selected().unwrap_or(0);
     
     // ASCII art header for preview
@@ -430,14 +2803,14 @@ fn render_preview_pane(f: &mut Frame, app: &App, area: Rect) {
     
     // Render preview header
     let header = vec![
-        Line::from(vec![Span::styled("╔═══════════════════════════════╗", Style::default().fg(Color::Cyan))]),
-        Line::from(vec![Span::styled("║  ░▒▓ MEMORY  PREVIEW ▓▒░     ║", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))]),
-        Line::from(vec![Span::styled("║  ░▒▓ DATA    STREAM  ▓▒░     ║", Style::default().fg(Color::LightGreen))]),
-        Line::from(vec![Span::styled("╚═══════════════════════════════╝", Style::default().fg(Color::Cyan))]),
+        Line::from(vec![Span::styled("╔═══════════════════════════════╗", Style::default().fg(theme.secondary))]),
+        Line::from(vec![Span::styled("║  ░▒▓ MEMORY  PREVIEW ▓▒░     ║", Style::default().fg(theme.secondary).add_modifier(Modifier::BOLD))]),
+        Line::from(vec![Span::styled("║  ░▒▓ DATA    STREAM  ▓▒░     ║", Style::default().fg(theme.accent))]),
+        Line::from(vec![Span::styled("╚═══════════════════════════════╝", Style::default().fg(theme.secondary))]),
     ];
     let header_widget = Paragraph::new(header)
         .alignment(Alignment::Center)
-        .style(Style::default().bg(Color::Rgb(0, 0, 0)));
+        .style(Style::default().bg(theme.background));
     f.render_widget(header_widget, preview_layout[0]);
     
     // Render preview content
@@ -445,75 +2818,157 @@ fn render_preview_pane(f: &mut Frame, app: &App, area: Rect) {
         vec![
             Line::from(""),
             Line::from(vec![
-                Span::styled("▓▒░ ", Style::default().fg(Color::LightGreen)),
-                Span::styled("READY TO INITIALIZE", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+                Span::styled("▓▒░ ", Style::default().fg(theme.accent)),
+                Span::styled("READY TO INITIALIZE", Style::default().fg(theme.secondary).add_modifier(Modifier::BOLD)),
             ]),
             Line::from(""),
             Line::from(vec![
-                Span::styled("► ", Style::default().fg(Color::LightGreen)),
-                Span::styled("Press ENTER to begin memory capture", Style::default().fg(Color::Gray)),
+                Span::styled("► ", Style::default().fg(theme.accent)),
+                Span::styled("Press ENTER to begin memory capture", Style::default().fg(theme.muted)),
             ]),
             Line::from(vec![
-                Span::styled("► ", Style::default().fg(Color::LightGreen)),
-                Span::styled("System will launch neural interface", Style::default().fg(Color::Gray)),
+                Span::styled("► ", Style::default().fg(theme.accent)),
+                Span::styled("System will launch neural interface", Style::default().fg(theme.muted)),
             ]),
             Line::from(vec![
-                Span::styled("► ", Style::default().fg(Color::LightGreen)),
-                Span::styled("Memory will be encrypted and stored", Style::default().fg(Color::Gray)),
+                Span::styled("► ", Style::default().fg(theme.accent)),
+                Span::styled("Memory will be encrypted and stored", Style::default().fg(theme.muted)),
             ]),
             Line::from(""),
             Line::from(vec![
-                Span::styled("[SYSTEM] ", Style::default().fg(Color::DarkGray)),
-                Span::styled("Awaiting input...", Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC)),
+                Span::styled("[SYSTEM] ", Style::default().fg(theme.dim)),
+                Span::styled("Awaiting input...", Style::default().fg(theme.dim).add_modifier(Modifier::ITALIC)),
             ]),
         ]
-    } else if selected > 0 && selected <= app.entries.len() {
-        let entry = &app.entries[selected - 1];
+    } else if let Some(entry) = app.selected_entry() {
         let mut lines = vec![
             Line::from(""),
             Line::from(vec![
-                Span::styled("▓▒░ MEMORY BLOCK #", Style::default().fg(Color::LightGreen)),
-                Span::styled(format!("{:04}", selected), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+                Span::styled("▓▒░ MEMORY BLOCK", Style::default().fg(theme.accent)),
+                Span::styled(match app.config.display.list_prefix {
+                    ListPrefix::Number => format!(" #{:04}", selected),
+                    ListPrefix::Date => format!(" {}", entry.created.format("%Y-%m-%d")),
+                    ListPrefix::None => String::new(),
+                }, Style::default().fg(theme.secondary).add_modifier(Modifier::BOLD)),
             ]),
             Line::from(""),
         ];
         
-        // Try to read the file content
-        if let Ok(content) = fs::read_to_string(&entry.path) {
-            let preview_lines: Vec<&str> = content.lines().skip(2).take(20).collect();
+        let date_format = &app.config.display.display_date_format;
+        lines.insert(2, Line::from(vec![
+            Span::styled("CREATED  ", Style::default().fg(theme.dim)),
+            Span::styled(entry.created.format(date_format).to_string(), Style::default().fg(theme.muted)),
+            Span::styled("  SIZE ", Style::default().fg(theme.dim)),
+            Span::styled(format::human_size(entry.size), Style::default().fg(theme.muted)),
+        ]));
+        lines.insert(3, Line::from(vec![
+            Span::styled("MODIFIED ", Style::default().fg(theme.dim)),
+            Span::styled(entry.modified.format(date_format).to_string(), Style::default().fg(theme.muted)),
+        ]));
+        
+        // A locked entry shows nothing of its text until it's opened
+        let content = entry.content.as_ref().filter(|_| !entry.locked);
+        if let Some(content) = content
+            && let Some(goal) = words::goal(content)
+        {
+            lines.insert(4, goal_progress(words::word_count(content), goal, &theme));
+        }
+        
+        if let Some(content) = content {
+            let minutes = format::reading_time_minutes(content.split_whitespace().count());
+            lines[1].spans.push(Span::styled(
+                format!("  ~{} min read", minutes),
+                Style::default().fg(theme.dim),
+            ));
+        }
+        
+        if content.is_some()
+            && let Some(error) = &entry.error
+        {
+            lines.push(Line::from(vec![
+                Span::styled("[WARN] ", Style::default().fg(theme.warning)),
+                Span::styled(error.clone(), Style::default().fg(theme.dim).add_modifier(Modifier::ITALIC)),
+            ]));
+        }
+        
+        if let Some(content) = content {
+            let backlinks = app.selected_index().and_then(|i| app.backlinks.get(&i));
+            // Rows left inside the borders after the metadata above, the backlinks and the
+            // closing hint below, so the hint is never cut off
+            let trailing = 2 + backlinks.map_or(0, |sources| sources.len() + 2);
+            let room = (preview_layout[1].height as usize).saturating_sub(2 + lines.len() + trailing);
+            let limit = app.config.display.preview_lines.min(room.max(1));
+            // The title is already in the list, and frontmatter is noise here
+            let body = frontmatter::body(content);
+            let body_lines = body.lines().count();
+            let preview_lines: Vec<&str> = body.lines().take(limit).collect();
+            let hidden = body_lines - preview_lines.len();
             
             if preview_lines.is_empty() {
                 lines.push(Line::from(vec![
-                    Span::styled("[EMPTY] ", Style::default().fg(Color::DarkGray)),
-                    Span::styled("No data recorded", Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC)),
+                    Span::styled("[EMPTY] ", Style::default().fg(theme.dim)),
+                    Span::styled("No data recorded", Style::default().fg(theme.dim).add_modifier(Modifier::ITALIC)),
                 ]));
             } else {
                 for line in preview_lines {
-                    if line.len() > 60 {
-                        let truncated = format!("{}...", &line[..57]);
+                    // Image references show as a placeholder rather than raw markdown
+                    if !attachments::find_images(line).is_empty() {
+                        let line = attachments::replace_images(line);
                         lines.push(Line::from(vec![
-                            Span::styled("│ ", Style::default().fg(Color::DarkGray)),
-                            Span::styled(truncated, Style::default().fg(Color::Green)),
+                            Span::styled("│ ", Style::default().fg(theme.dim)),
+                            Span::styled(line, Style::default().fg(theme.secondary).add_modifier(Modifier::ITALIC)),
                         ]));
-                    } else {
+                        continue;
+                    }
+                    // Without wrapping, the whole line stays reachable by panning
+                    let text = if app.preview_wrap { format::truncate_display(line, 60) } else { line.to_string() };
+                    
+                    let mut spans = vec![Span::styled("│ ", Style::default().fg(theme.dim))];
+                    spans.extend(highlight_spans(&text, query, Style::default().fg(theme.accent_dim)));
+                    lines.push(Line::from(spans));
+                }
+                
+                if let Some(sources) = backlinks {
+                    lines.push(Line::from(""));
+                    lines.push(Line::from(Span::styled(
+                        format!("▓▒░ LINKED FROM {} ", sources.len()),
+                        Style::default().fg(theme.accent),
+                    )));
+                    for &source in sources {
                         lines.push(Line::from(vec![
-                            Span::styled("│ ", Style::default().fg(Color::DarkGray)),
-                            Span::styled(line.to_string(), Style::default().fg(Color::Green)),
+                            Span::styled("  ◄ ", Style::default().fg(theme.dim)),
+                            Span::styled(app.journal.entries()[source].title.clone(), Style::default().fg(theme.secondary)),
                         ]));
                     }
                 }
                 
                 lines.push(Line::from(""));
-                lines.push(Line::from(vec![
-                    Span::styled("[EOF] ", Style::default().fg(Color::DarkGray)),
-                    Span::styled("Press ENTER to access full memory", Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC)),
-                ]));
+                if hidden > 0 {
+                    let more = if hidden == 1 { "1 more line".to_string() } else { format!("{} more lines", hidden) };
+                    lines.push(Line::from(vec![
+                        Span::styled(format!("… {} ", more), Style::default().fg(theme.secondary)),
+                        Span::styled("(press v to view)", Style::default().fg(theme.dim).add_modifier(Modifier::ITALIC)),
+                    ]));
+                } else {
+                    lines.push(Line::from(vec![
+                        Span::styled("[EOF] ", Style::default().fg(theme.dim)),
+                        Span::styled("Press ENTER to access full memory", Style::default().fg(theme.dim).add_modifier(Modifier::ITALIC)),
+                    ]));
+                }
             }
+        } else if entry.locked {
+            lines.push(Line::from(vec![
+                Span::styled("[LOCKED] ", Style::default().fg(theme.warning)),
+                Span::styled("🔒 Authenticate to access this memory block (ENTER or v)", Style::default().fg(theme.dim).add_modifier(Modifier::ITALIC)),
+            ]));
         } else {
             lines.push(Line::from(vec![
-                Span::styled("[ERROR] ", Style::default().fg(Color::Red)),
-                Span::styled("Failed to decode memory block", Style::default().fg(Color::Red).add_modifier(Modifier::ITALIC)),
+                Span::styled("[ERROR] ", Style::default().fg(theme.error)),
+                Span::styled("Failed to decode memory block", Style::default().fg(theme.error).add_modifier(Modifier::ITALIC)),
             ]));
+            if let Some(error) = &entry.error {
+                lines.push(Line::from(Span::styled(format!("        {}", error), Style::default().fg(theme.dim))));
+            }
         }
         
         lines
@@ -526,26 +2981,132 @@ fn render_preview_pane(f: &mut Frame, app: &App, area: Rect) {
             Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Double)
-                .border_style(Style::default().fg(Color::Cyan).bg(Color::Rgb(0, 0, 0)))
-                .style(Style::default().bg(Color::Rgb(0, 0, 0)))
+                .border_style(Style::default().fg(theme.secondary).bg(theme.background))
+                .style(Style::default().bg(theme.background))
         )
-        .style(Style::default().fg(Color::Green).bg(Color::Rgb(0, 0, 0)))
-        .wrap(Wrap { trim: false });
+        .style(Style::default().fg(theme.accent_dim).bg(theme.background));
+    let preview = if app.preview_wrap {
+        preview.wrap(Wrap { trim: false })
+    } else {
+        preview.scroll((0, app.preview_hscroll))
+    };
     
     f.render_widget(preview, preview_layout[1]);
 }
 
+// A styled line with every match of `query` reversed, keeping each span's own style.
+// The match starting at byte `active` is picked out in the warning color instead.
+fn highlight_line(line: Line<'static>, query: &str, active: Option<usize>, theme: &Theme) -> Line<'static> {
+    let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+    let matches = search::find_matches(&text, query);
+    if matches.is_empty() {
+        return line;
+    }
+    
+    let (line_style, alignment) = (line.style, line.alignment);
+    let mut spans = Vec::new();
+    let mut offset = 0;
+    for span in line.spans {
+        let (span_start, span_end) = (offset, offset + span.content.len());
+        offset = span_end;
+        // Cut points inside this span, from the match boundaries that fall within it
+        let mut cuts = vec![span_start];
+        for &(start, end) in &matches {
+            cuts.extend([start, end].into_iter().filter(|&c| c > span_start && c < span_end));
+        }
+        cuts.push(span_end);
+        
+        for piece in cuts.windows(2) {
+            let (from, to) = (piece[0], piece[1]);
+            let matched = matches.iter().find(|&&(start, end)| start <= from && to <= end);
+            let style = match matched {
+                Some(&(start, _)) if active == Some(start) => span.style.fg(theme.background).bg(theme.warning),
+                Some(_) => span.style.add_modifier(Modifier::REVERSED),
+                None => span.style,
+            };
+            spans.push(Span::styled(text[from..to].to_string(), style));
+        }
+    }
+    Line { spans, style: line_style, alignment }
+}
+
+// "GOAL     [██████░░░░] 320/500 words", or a highlighted line once it's reached
+fn goal_progress(count: usize, goal: usize, theme: &Theme) -> Line<'static> {
+    const BAR_WIDTH: usize = 20;
+    let label = format!(" {}/{} words", count, goal);
+    if count >= goal {
+        return Line::from(vec![
+            Span::styled("GOAL     ", Style::default().fg(theme.dim)),
+            Span::styled("★ GOAL REACHED ★", Style::default().fg(theme.warning).add_modifier(Modifier::BOLD)),
+            Span::styled(label, Style::default().fg(theme.accent)),
+        ]);
+    }
+    
+    let filled = count * BAR_WIDTH / goal;
+    Line::from(vec![
+        Span::styled("GOAL     [", Style::default().fg(theme.dim)),
+        Span::styled("█".repeat(filled), Style::default().fg(theme.accent)),
+        Span::styled("░".repeat(BAR_WIDTH - filled), Style::default().fg(theme.dim)),
+        Span::styled("]", Style::default().fg(theme.dim)),
+        Span::styled(label, Style::default().fg(theme.muted)),
+    ])
+}
+
+// Split text into spans with every search match shown in inverse video
+fn highlight_spans(text: &str, query: &str, style: Style) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut last = 0;
+    
+    for (start, end) in search::find_matches(text, query) {
+        if start > last {
+            spans.push(Span::styled(text[last..start].to_string(), style));
+        }
+        spans.push(Span::styled(text[start..end].to_string(), style.add_modifier(Modifier::REVERSED)));
+        last = end;
+    }
+    
+    if last < text.len() || spans.is_empty() {
+        spans.push(Span::styled(text[last..].to_string(), style));
+    }
+    spans
+}
+
 fn ui(f: &mut Frame, app: &mut App) {
-    // Set black background for entire frame
+    if let Some(animation) = &app.screensaver {
+        matrix::draw_screensaver(f, animation);
+        return;
+    }
+    let theme = app.theme;
+    // Paint the theme background for entire frame
     let area = f.area();
-    f.buffer_mut().set_style(area, Style::default().bg(Color::Rgb(0, 0, 0)));
+    f.buffer_mut().set_style(area, Style::default().bg(theme.background));
+    
+    // Fixed headers and percentage splits fall apart in tiny panes; redrawn on every resize
+    let (min_width, min_height) = (app.config.display.min_width, app.config.display.min_height);
+    if area.width < min_width || area.height < min_height {
+        let message = format!("Terminal too small (need {}x{}, have {}x{})", min_width, min_height, area.width, area.height);
+        let rows = Rect { y: area.y + area.height.saturating_sub(1) / 2, height: area.height.min(3), ..area };
+        f.render_widget(
+            Paragraph::new(message)
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true })
+                .style(Style::default().fg(theme.warning).bg(theme.background)),
+            rows,
+        );
+        return;
+    }
     
-    // Create layout with preview pane
+    // Create layout with preview pane, or a full-width list when it's hidden
+    let list_width = if app.config.display.show_preview {
+        app.config.display.list_width.clamp(MIN_LIST_WIDTH, MAX_LIST_WIDTH)
+    } else {
+        100
+    };
     let main_layout = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
-            Constraint::Percentage(40),  // Entry list
-            Constraint::Percentage(60),  // Preview pane
+            Constraint::Percentage(list_width),        // Entry list
+            Constraint::Percentage(100 - list_width),  // Preview pane
         ])
         .split(area);
     
@@ -559,67 +3120,144 @@ fn ui(f: &mut Frame, app: &mut App) {
         .split(main_layout[0]);
     
     // Render ASCII header with permanent encryption indicator
-    let header = vec![
-        Line::from(vec![Span::styled("╔═══════════════════════════════╗", Style::default().fg(Color::LightGreen))]),
-        Line::from(vec![Span::styled("║  ░▒▓ NEURAL  JOURNAL ▓▒░     ║", Style::default().fg(Color::LightGreen).add_modifier(Modifier::BOLD))]),
-        Line::from(vec![Span::styled("║  ░▒▓ MEMORY  ARCHIVE ▓▒░     ║", Style::default().fg(Color::Cyan))]),
+    let mut header = vec![
+        Line::from(vec![Span::styled("╔═══════════════════════════════╗", Style::default().fg(theme.accent))]),
+        Line::from(vec![Span::styled("║  ░▒▓ NEURAL  JOURNAL ▓▒░     ║", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD))]),
+        Line::from(vec![Span::styled("║  ░▒▓ MEMORY  ARCHIVE ▓▒░     ║", Style::default().fg(theme.secondary))]),
         Line::from(vec![
-            Span::styled("║  🔒 ", Style::default().fg(Color::LightGreen)),
-            Span::styled("ENCRYPTED VAULT", Style::default().fg(Color::LightGreen).add_modifier(Modifier::BOLD)),
-            Span::styled(" 🔒     ║", Style::default().fg(Color::LightGreen))
+            Span::styled("║  🔒 ", Style::default().fg(theme.accent)),
+            Span::styled("ENCRYPTED VAULT", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            Span::styled(" 🔒     ║", Style::default().fg(theme.accent))
         ]),
-        Line::from(vec![Span::styled("╚═══════════════════════════════╝", Style::default().fg(Color::LightGreen))]),
+        Line::from(vec![Span::styled("╚═══════════════════════════════╝", Style::default().fg(theme.accent))]),
     ];
+    // Bookmarks on the header's spare row, e.g. "1 Ideas  2 Reading list"
+    let bookmark_bar: Vec<String> = app.bookmarks.iter()
+        .filter_map(|(slot, name)| {
+            let entry = app.journal.entries().iter().find(|e| e.path.file_name().is_some_and(|n| n == name))?;
+            Some(format!("{} {}", slot, format::truncate_display(&entry.title, 14)))
+        })
+        .collect();
+    if !bookmark_bar.is_empty() {
+        let bar = format::truncate_display(&format!("» {}", bookmark_bar.join("  ")), list_layout[0].width as usize);
+        header.push(Line::from(Span::styled(bar, Style::default().fg(theme.secondary))));
+    }
     let header_widget = Paragraph::new(header)
         .alignment(Alignment::Center)
-        .style(Style::default().bg(Color::Rgb(0, 0, 0)));
+        .style(Style::default().bg(theme.background));
     f.render_widget(header_widget, list_layout[0]);
     
-    // Create list items with larger text
-    let mut items: Vec<ListItem> = vec![
-        ListItem::new(vec![
-            Line::from(""),
+    // Cards with larger text: a headline, a detail line and spacing; compact mode keeps
+    // just the headline
+    let compact = app.compact;
+    let card = |headline: Line<'static>, detail: Line<'static>| {
+        if compact {
+            ListItem::new(headline)
+        } else {
+            ListItem::new(vec![Line::from(""), headline, detail, Line::from("")])
+        }
+    };
+    let mut items: Vec<ListItem> = vec![card(
+        Line::from(vec![
+            Span::styled("[+] ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            Span::styled("CREATE NEW ENTRY", Style::default().fg(theme.secondary).add_modifier(Modifier::BOLD)),
+        ]),
+        Line::from(vec![
+            Span::styled("    └─> ", Style::default().fg(theme.dim)),
+            Span::styled("Initialize new memory block", Style::default().fg(theme.dim).add_modifier(Modifier::ITALIC)),
+        ]),
+    )];
+    
+    // An empty result says so, rather than looking like an empty journal
+    if app.visible.is_empty() && !app.search_query.is_empty() {
+        items.push(card(
             Line::from(vec![
-                Span::styled("[+] ", Style::default().fg(Color::LightGreen).add_modifier(Modifier::BOLD)),
-                Span::styled("CREATE NEW ENTRY", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+                Span::styled("[∅] ", Style::default().fg(theme.warning)),
+                Span::styled(format!("NO MATCHES FOR \"{}\"", app.search_query), Style::default().fg(theme.warning).add_modifier(Modifier::BOLD)),
             ]),
             Line::from(vec![
-                Span::styled("    └─> ", Style::default().fg(Color::DarkGray)),
-                Span::styled("Initialize new memory block", Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC)),
+                Span::styled("    └─> ", Style::default().fg(theme.dim)),
+                Span::styled("Esc clears the search", Style::default().fg(theme.dim).add_modifier(Modifier::ITALIC)),
             ]),
-            Line::from(""),
-        ])
-    ];
+        ));
+    }
     
-    for (i, entry) in app.entries.iter().enumerate() {
-        let date_str = entry.created.format("%Y-%m-%d %H:%M").to_string();
-        let item = ListItem::new(vec![
-            Line::from(""),
-            Line::from(vec![
-                Span::styled(format!("[{}] ", i + 1), Style::default().fg(Color::DarkGray)),
-                Span::styled(&entry.title, Style::default().fg(Color::LightGreen)),
-            ]),
+    // Borrow the fields directly so the list state stays mutably borrowable below
+    for (i, entry) in app.visible.iter().map(|&i| &app.journal.entries()[i]).enumerate() {
+        let absolute = entry.created.format(&app.config.display.display_date_format).to_string();
+        let date_str = if app.relative_timestamps {
+            format::humanize_duration(Local::now() - entry.created).unwrap_or(absolute)
+        } else {
+            absolute
+        };
+        let prefix = match app.config.display.list_prefix {
+            ListPrefix::Number => format!("[{}]", i + 1),
+            ListPrefix::Date => format!("[{}]", entry.created.format("%Y-%m-%d")),
+            ListPrefix::None => String::new(),
+        };
+        let number = if app.marked.contains(&entry.path) {
+            let marker = if prefix.is_empty() { "◆ " } else { "◆" };
+            Span::styled(format!("{}{}", prefix, marker), Style::default().fg(theme.warning).add_modifier(Modifier::BOLD))
+        } else if prefix.is_empty() {
+            Span::raw("")
+        } else {
+            Span::styled(format!("{} ", prefix), Style::default().fg(theme.dim))
+        };
+        let mut headline = vec![
+            number,
+            match &entry.icon {
+                Some(icon) => Span::raw(format!("{} ", icon)),
+                None => Span::raw(""),
+            },
+            Span::raw(if entry.locked { "🔒 " } else { "" }),
+            Span::styled(if entry.external { "↗ " } else { "" }, Style::default().fg(theme.secondary)),
+            if entry.archived {
+                Span::styled(format!("{} [ARCHIVED]", entry.title), Style::default().fg(theme.dim))
+            } else {
+                // Unknown color names fall back to the theme rather than failing the entry
+                let color = entry.color.as_deref().and_then(|name| Color::from_str(name).ok());
+                Span::styled(entry.title.clone(), Style::default().fg(color.unwrap_or(theme.accent)))
+            },
+        ];
+        if compact {
+            headline.push(Span::styled(format!(" · {}", date_str), Style::default().fg(theme.muted)));
+        }
+        items.push(card(
+            Line::from(headline),
             Line::from(vec![
-                Span::styled("    ├─> ", Style::default().fg(Color::DarkGray)),
-                Span::styled(format!("Timestamp: {}", date_str), Style::default().fg(Color::Gray)),
+                Span::styled("    ├─> ", Style::default().fg(theme.dim)),
+                Span::styled(format!("Timestamp: {}", date_str), Style::default().fg(theme.muted)),
             ]),
-            Line::from(""),
-        ]);
-        items.push(item);
+        ));
     }
     
+    let mut list_block = Block::default();
+    if let Some(warning) = &app.load_warning {
+        list_block = list_block.title(Span::styled(
+            format!(" ⚠ {} ", warning),
+            Style::default().fg(theme.error).add_modifier(Modifier::BOLD),
+        ));
+    } else if app.refreshed_at.is_some_and(|at| at.elapsed() < REFRESH_FLASH) {
+        list_block = list_block.title(Span::styled(
+            " ⟳ REFRESHED ",
+            Style::default().fg(theme.secondary).add_modifier(Modifier::BOLD),
+        ));
+    }
+    app.list_row_heights = items.iter().map(ListItem::height).collect();
+    // Borders take a row top and bottom
+    app.list_page_height = list_layout[1].height.saturating_sub(2) as usize;
     let list = List::new(items)
         .block(
-            Block::default()
+            list_block
                 .borders(Borders::ALL)
                 .border_type(BorderType::Double)
-                .border_style(Style::default().fg(Color::LightGreen).bg(Color::Rgb(0, 0, 0)))
-                .style(Style::default().bg(Color::Rgb(0, 0, 0)))
+                .border_style(Style::default().fg(theme.accent).bg(theme.background))
+                .style(Style::default().bg(theme.background))
         )
         .highlight_style(
             Style::default()
-                .bg(Color::Rgb(0, 40, 0))
-                .fg(Color::LightGreen)
+                .bg(theme.highlight)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD)
         )
         .highlight_symbol("█▓▒░ ");
@@ -627,52 +3265,129 @@ fn ui(f: &mut Frame, app: &mut App) {
     f.render_stateful_widget(list, list_layout[1], &mut app.list_state);
     
     // Render preview pane
-    render_preview_pane(f, app, main_layout[1]);
+    if app.config.display.show_preview {
+        render_preview_pane(f, app, main_layout[1], &app.search_text);
+    }
     
-    if matches!(app.mode, AppMode::TitleInput) {
-        let popup_area = centered_rect(60, 20, f.area());
-        
-        // Fill popup area with black
-        let buf = f.buffer_mut();
-        for y in popup_area.top()..popup_area.bottom() {
-            for x in popup_area.left()..popup_area.right() {
-                let cell = &mut buf[(x, y)];
-                cell.set_symbol(" ");
-                cell.set_style(Style::default().bg(Color::Rgb(0, 0, 0)));
-            }
-        }
-        
-        let input_block = Block::default()
-            .title("╔═ INITIALIZE MEMORY BLOCK ═╗")
-            .borders(Borders::ALL)
-            .border_type(BorderType::Double)
-            .border_style(Style::default().fg(Color::LightGreen).bg(Color::Rgb(0, 0, 0)));
-        
-        let input_area = popup_area.inner(Margin::new(1, 1));
-        
-        let cursor = "█";
-        let input = Paragraph::new(format!("> {}{}", app.title_input, cursor))
-            .style(Style::default().fg(Color::LightGreen).bg(Color::Rgb(0, 0, 0)))
-            .wrap(Wrap { trim: false });
-        
-        f.render_widget(input_block, popup_area);
-        f.render_widget(input, input_area);
-        
-        f.set_cursor_position((
-            input_area.x + 2 + app.title_input.len() as u16,  // +2 for "> " prefix
-            input_area.y,
-        ));
+    match app.mode {
+        AppMode::TitleInput => render_input_popup(f, &theme, "╔═ INITIALIZE MEMORY BLOCK ═╗", &app.title_input),
+        AppMode::DirPicker => render_input_popup(f, &theme, "╔═ OPEN JOURNAL FOLDER ═╗", &app.dir_input),
+        AppMode::QuickCapture => render_input_popup(f, &theme, "╔═ QUICK CAPTURE · TODAY'S LOG ═╗", &app.capture_input),
+        _ => {}
     }
     
-    let help_text = if matches!(app.mode, AppMode::Normal) {
-        " j/k: navigate | Enter: select | q: quit "
-    } else {
-        " Enter: create | Esc: cancel "
+    if matches!(app.mode, AppMode::ConfirmDelete) {
+        let prompt = match app.targets().as_slice() {
+            [entry] => format!("Move \"{}\" to trash?", entry.title),
+            targets => format!("Move {} marked entries to trash?", targets.len()),
+        };
+        render_confirm_popup(f, &theme, &format!("{}{}", prompt, confirm_countdown(app)));
+    }
+    
+    if matches!(app.mode, AppMode::Trash | AppMode::ConfirmPurge) {
+        render_trash_browser(f, app);
+    }
+    
+    if matches!(app.mode, AppMode::WordReport) {
+        render_word_report(f, app);
+    }
+    
+    if matches!(app.mode, AppMode::TagBrowser) {
+        render_tag_browser(f, app);
+    }
+    
+    if matches!(app.mode, AppMode::OnThisDay) {
+        render_on_this_day(f, app);
+    }
+    
+    if matches!(app.mode, AppMode::Duplicates) {
+        render_duplicate_review(f, app);
+    }
+    
+    if matches!(app.mode, AppMode::View | AppMode::ViewSearch) {
+        render_viewer(f, app);
+    }
+    
+    if matches!(app.mode, AppMode::Edit) {
+        render_editor(f, app);
+    }
+    
+    if matches!(app.mode, AppMode::ConfirmPurge) {
+        let title = app.selected_trash_entry().map(|e| e.title.as_str()).unwrap_or("");
+        render_confirm_popup(f, &theme, &format!("Permanently delete \"{}\"?{}", title, confirm_countdown(app)));
+    }
+    
+    if matches!(app.mode, AppMode::ConfirmQuit) {
+        render_confirm_popup(f, &theme, "Lock vault and exit?");
+    }
+    
+    if matches!(app.mode, AppMode::ConfirmOpenDated) {
+        render_confirm_popup(f, &theme, &format!("\"{}\" already exists. Open it?", dated_title()));
+    }
+    
+    if let (AppMode::ConfirmCreateDir, Some(dir)) = (&app.mode, &app.pending_dir) {
+        render_confirm_popup(f, &theme, &format!("{} doesn't exist. Create it?", dir.display()));
+    }
+    
+    let help_text = match app.mode {
+        AppMode::Normal if !app.search_query.is_empty() => format!(
+            " [/{}] {} | n/N: next/previous match | Esc: clear search | j/k: navigate | Enter: select | q: quit ",
+            app.search_query,
+            match_count(app.visible.len()),
+        ),
+        AppMode::Normal => " j/k: navigate | PgUp/PgDn: page | Enter: select | .: edit newest | e: write in app | c: new | C: new dated | /: search | a: capture | v: view | Space: mark | {/}: day | T: today | ?: random | A: archive | H: show archived | d: delete | u: undo | X: trash | t: time format | m: compact | </>: resize | p: preview | w: wrap | h/l: pan | D: duplicates | Y: copy | W: word report | #: tags | b: bookmark | Alt-1..9: jump to bookmark | O: open folder | R: refresh | q: quit ".to_string(),
+        AppMode::Search => format!(
+            " /{}█ {} | @date: filter by date | Enter: apply | Esc: clear ",
+            app.search_query,
+            match_count(app.visible.len()),
+        ),
+        AppMode::TitleInput => " Enter: create | Esc: cancel | Ctrl-Q: quit ".to_string(),
+        AppMode::DirPicker => " Enter: open folder (empty: back to the vault) | Esc: cancel | Ctrl-Q: quit ".to_string(),
+        AppMode::QuickCapture => " Enter: append to today | Esc: cancel | Ctrl-Q: quit ".to_string(),
+        AppMode::ConfirmDelete | AppMode::ConfirmPurge | AppMode::ConfirmQuit | AppMode::ConfirmCreateDir => " y: confirm | any other key: cancel | Q: quit ".to_string(),
+        AppMode::ConfirmOpenDated => " y: open it | n: create another | any other key: cancel | Q: quit ".to_string(),
+        AppMode::BookmarkSlot => " 1-9: bookmark the selected entry on that slot | 0: remove its bookmark | any other key: cancel ".to_string(),
+        AppMode::Trash => " j/k: navigate | r: restore | x: purge | q/Esc: back | Q: quit ".to_string(),
+        AppMode::TagBrowser => " j/k: navigate | Enter: show entries with this tag | q/Esc: back | Q: quit ".to_string(),
+        AppMode::View if !app.view_matches.is_empty() => format!(
+            " [/{}] {}/{} | n/N: next/previous match | Esc: clear search | j/k: scroll | q: back ",
+            app.view_search,
+            app.view_match + 1,
+            app.view_matches.len(),
+        ),
+        AppMode::View => " j/k: scroll | PgUp/PgDn: page | g/G: top/bottom | /: find | Tab: next link | Enter: follow/open | d: diff | q/Esc: back | Q: quit ".to_string(),
+        AppMode::ViewSearch => format!(" /{}█ | Enter: find in entry | Esc: cancel ", app.view_search),
+        AppMode::WordReport => " q/Esc: back | Q: quit ".to_string(),
+        AppMode::Edit => " Ctrl-S: save | Esc: close | arrows/Home/End: move | Ctrl-Q: quit ".to_string(),
+        AppMode::OnThisDay => " any key: continue | Q: quit ".to_string(),
+        AppMode::Duplicates => " j/k: navigate | x: keep oldest, trash the rest | q/Esc: back | Q: quit ".to_string(),
+    };
+    let help_text = match (&app.mode, &app.date_filter) {
+        (AppMode::Normal, Some(filter)) => format!(" FILTER: {} ({}) |{}", filter.label, app.visible.len(), help_text),
+        _ => help_text,
+    };
+    let help_text = match (&app.mode, &app.tag_filter) {
+        (AppMode::Normal, Some(tag)) => format!(" TAG: #{} ({}, Esc: clear) |{}", tag, app.visible.len(), help_text),
+        _ => help_text,
+    };
+    let help_text = match app.mode {
+        AppMode::Normal if !app.marked.is_empty() => format!(" {} MARKED (d/A: all, Esc: clear) |{}", app.marked.len(), help_text),
+        _ => help_text,
+    };
+    // Where the selection is among the listed entries; row 0 is the create row
+    let help_text = match (&app.mode, app.list_state.selected()) {
+        (AppMode::Normal, Some(0) | None) => format!(" [NEW] |{}", help_text),
+        (AppMode::Normal, Some(row)) => format!(" [{}/{}] |{}", row, app.visible.len(), help_text),
+        _ => help_text,
     };
     
-    let help = Paragraph::new(help_text)
-        .style(Style::default().fg(Color::DarkGray))
-        .alignment(Alignment::Center);
+    let help = match &app.status_message {
+        Some(message) => Paragraph::new(format!(" {} ", message))
+            .style(Style::default().fg(theme.secondary)),
+        None => Paragraph::new(help_text)
+            .style(Style::default().fg(theme.dim)),
+    }
+    .alignment(Alignment::Center);
     
     let help_area = Rect {
         x: area.x,
@@ -684,6 +3399,412 @@ fn ui(f: &mut Frame, app: &mut App) {
     f.render_widget(help, help_area);
 }
 
+fn render_input_popup(f: &mut Frame, theme: &Theme, title: &str, text: &str) {
+    let popup_area = centered_rect(60, 20, f.area());
+    
+    // Fill popup area with the background
+    let buf = f.buffer_mut();
+    for y in popup_area.top()..popup_area.bottom() {
+        for x in popup_area.left()..popup_area.right() {
+            let cell = &mut buf[(x, y)];
+            cell.set_symbol(" ");
+            cell.set_style(Style::default().bg(theme.background));
+        }
+    }
+    
+    let input_block = Block::default()
+        .title(title.to_string())
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(theme.accent).bg(theme.background));
+    
+    let input_area = popup_area.inner(Margin::new(1, 1));
+    
+    let cursor = "█";
+    let input = Paragraph::new(format!("> {}{}", text, cursor))
+        .style(Style::default().fg(theme.accent).bg(theme.background))
+        .wrap(Wrap { trim: false });
+    
+    f.render_widget(input_block, popup_area);
+    f.render_widget(input, input_area);
+    
+    f.set_cursor_position((
+        input_area.x + 2 + text.len() as u16,  // +2 for "> " prefix
+        input_area.y,
+    ));
+}
+
+fn render_trash_browser(f: &mut Frame, app: &mut App) {
+    let theme = app.theme;
+    let popup_area = centered_rect(70, 70, f.area());
+    
+    f.render_widget(Clear, popup_area);
+    
+    let purge = match app.config.retention.purge_trash_after_days {
+        0 => "AUTO-PURGE OFF".to_string(),
+        days => format!("AUTO-PURGE AFTER {} DAYS", days),
+    };
+    let block = Block::default()
+        .title(format!("╔═ TRASH · {} ENTRIES · {} ═╗", app.trash_entries.len(), purge))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(theme.error).bg(theme.background))
+        .style(Style::default().bg(theme.background));
+    
+    if app.trash_entries.is_empty() {
+        let empty = Paragraph::new(vec![
+            Line::from(""),
+            Line::from(Span::styled("[EMPTY] Trash contains no memory blocks", Style::default().fg(theme.dim).add_modifier(Modifier::ITALIC))),
+        ])
+        .block(block)
+        .alignment(Alignment::Center);
+        f.render_widget(empty, popup_area);
+        return;
+    }
+    
+    let items: Vec<ListItem> = app.trash_entries.iter().map(|entry| {
+        ListItem::new(vec![
+            Line::from(Span::styled(entry.title.clone(), Style::default().fg(theme.error))),
+            Line::from(vec![
+                Span::styled("    └─> ", Style::default().fg(theme.dim)),
+                Span::styled(format!("Trashed: {}", entry.trashed.format(&app.config.display.display_date_format)), Style::default().fg(theme.muted)),
+            ]),
+        ])
+    }).collect();
+    
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(
+            Style::default()
+                .bg(theme.highlight)
+                .fg(theme.error)
+                .add_modifier(Modifier::BOLD)
+        )
+        .highlight_symbol("█▓▒░ ");
+    
+    f.render_stateful_widget(list, popup_area, &mut app.trash_state);
+}
+
+// A single centered column with nothing else on screen but the footer
+fn render_editor(f: &mut Frame, app: &mut App) {
+    let theme = app.theme;
+    let area = f.area();
+    let area = Rect { height: area.height.saturating_sub(1), ..area };
+    f.render_widget(Clear, area);
+    f.buffer_mut().set_style(area, Style::default().bg(theme.background));
+    
+    let Some(editor) = app.editor.as_mut() else {
+        return;
+    };
+    let width = area.width.saturating_sub(4).min(EDITOR_COLUMN_WIDTH);
+    let column = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + 1,
+        width,
+        height: area.height.saturating_sub(2),
+    };
+    
+    // From the text being edited, so a retitle shows before it's saved
+    let title = frontmatter::title(&editor.text())
+        .unwrap_or_else(|| editor.path.file_stem().and_then(|s| s.to_str()).unwrap_or("").to_string());
+    let marker = if editor.modified { " ●" } else { "" };
+    f.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled(title, Style::default().fg(theme.dim)),
+            Span::styled(marker, Style::default().fg(theme.warning)),
+        ]))
+        .alignment(Alignment::Center),
+        Rect { height: 1, ..area },
+    );
+    
+    let (rows, (x, y)) = editor.view(column.width as usize, column.height as usize);
+    let text: Vec<Line> = rows.into_iter().map(Line::from).collect();
+    f.render_widget(Paragraph::new(text).style(Style::default().fg(theme.text).bg(theme.background)), column);
+    f.set_cursor_position((column.x + x as u16, column.y + y as u16));
+}
+
+fn render_viewer(f: &mut Frame, app: &mut App) {
+    let theme = app.theme;
+    let area = centered_rect(90, 90, f.area());
+    
+    f.render_widget(Clear, area);
+    
+    let Some(entry) = app.selected_entry() else {
+        return;
+    };
+    // Resolvable links are underlined, broken ones and missing images red, and the Tab-selected one reversed
+    let link_style = |index: usize, link: &markdown::LinkRef| {
+        let style = if link.image {
+            let found = attachments::is_remote(&link.target)
+                || attachments::resolve(&link.target, app.journal.dir()).exists();
+            Style::default().fg(if found { theme.secondary } else { theme.error }).add_modifier(Modifier::ITALIC)
+        } else if app.resolve_link(&link.target).is_some() {
+            Style::default().fg(theme.secondary).add_modifier(Modifier::UNDERLINED)
+        } else {
+            Style::default().fg(theme.error)
+        };
+        if app.view_link == Some(index) {
+            style.add_modifier(Modifier::REVERSED)
+        } else {
+            style
+        }
+    };
+    let (mut lines, title) = match &app.view_diff {
+        Some((diff, _)) => (diff::render(diff, &theme), format!("╔═ {} · CHANGES SINCE LAST COMMIT ═╗", entry.title)),
+        None => (
+            markdown::render(entry.content.as_deref().unwrap_or(""), &theme, &link_style).lines,
+            format!("╔═ {} ═╗", entry.title),
+        ),
+    };
+    
+    if !app.view_matches.is_empty() {
+        let active = app.view_matches.get(app.view_match).copied();
+        lines = lines
+            .into_iter()
+            .enumerate()
+            .map(|(i, line)| highlight_line(line, &app.view_search, active.filter(|&(l, _)| l == i).map(|(_, start)| start), &theme))
+            .collect();
+    }
+    
+    if let Some(started) = app.view_reveal {
+        let (revealed, done) = matrix::reveal_lines(lines, started, &theme);
+        lines = revealed;
+        if done {
+            app.view_reveal = None;
+        }
+    }
+    
+    // Borders take a row top and bottom
+    let height = area.height.saturating_sub(2) as usize;
+    let max_scroll = lines.len().saturating_sub(height);
+    app.view_height = height;
+    app.view_scroll = app.view_scroll.min(max_scroll);
+    
+    let position = if lines.is_empty() {
+        String::new()
+    } else {
+        format!(" {}/{} ", (app.view_scroll + height).min(lines.len()), lines.len())
+    };
+    
+    let block = Block::default()
+        .title(title)
+        .title_bottom(Line::from(position).right_aligned())
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(theme.secondary).bg(theme.background))
+        .style(Style::default().bg(theme.background));
+    
+    let viewer = Paragraph::new(lines)
+        .block(block)
+        .scroll((app.view_scroll.min(u16::MAX as usize) as u16, 0));
+    f.render_widget(viewer, area);
+}
+
+fn render_duplicate_review(f: &mut Frame, app: &mut App) {
+    let theme = app.theme;
+    let popup_area = centered_rect(70, 70, f.area());
+    
+    f.render_widget(Clear, popup_area);
+    
+    let block = Block::default()
+        .title(format!("╔═ DUPLICATES · {} GROUPS ═╗", app.duplicate_groups.len()))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(theme.warning).bg(theme.background))
+        .style(Style::default().bg(theme.background));
+    
+    let items: Vec<ListItem> = app.duplicate_groups.iter().map(|group| {
+        let mut lines = vec![Line::from(Span::styled(
+            format!("{} identical copies", group.len()),
+            Style::default().fg(theme.warning),
+        ))];
+        for (i, path) in group.iter().enumerate() {
+            let name = path.file_name().and_then(|s| s.to_str()).unwrap_or("");
+            let (marker, style) = if i == 0 {
+                ("    ├─ keep  ", Style::default().fg(theme.accent))
+            } else {
+                ("    ├─ trash ", Style::default().fg(theme.muted))
+            };
+            lines.push(Line::from(vec![
+                Span::styled(marker, Style::default().fg(theme.dim)),
+                Span::styled(name.to_string(), style),
+            ]));
+        }
+        lines.push(Line::from(""));
+        ListItem::new(lines)
+    }).collect();
+    
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(
+            Style::default()
+                .bg(theme.highlight)
+                .add_modifier(Modifier::BOLD)
+        )
+        .highlight_symbol("█▓▒░ ");
+    
+    f.render_stateful_widget(list, popup_area, &mut app.duplicate_state);
+}
+
+fn render_on_this_day(f: &mut Frame, app: &App) {
+    let theme = app.theme;
+    let popup_area = centered_rect(60, 50, f.area());
+    
+    f.render_widget(Clear, popup_area);
+    
+    let block = Block::default()
+        .title("╔═ ON THIS DAY ═╗")
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(theme.secondary).bg(theme.background))
+        .style(Style::default().bg(theme.background));
+    
+    let mut lines = vec![Line::from("")];
+    for (years, title, first_line) in &app.on_this_day {
+        let ago = if *years == 1 { "1 year ago".to_string() } else { format!("{} years ago", years) };
+        lines.push(Line::from(vec![
+            Span::styled(format!(" {} you wrote: ", ago), Style::default().fg(theme.muted)),
+            Span::styled(title.clone(), Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+        ]));
+        if !first_line.is_empty() {
+            lines.push(Line::from(Span::styled(
+                format!("    └─ {}", first_line),
+                Style::default().fg(theme.dim).add_modifier(Modifier::ITALIC),
+            )));
+        }
+        lines.push(Line::from(""));
+    }
+    
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false });
+    f.render_widget(paragraph, popup_area);
+}
+
+fn render_word_report(f: &mut Frame, app: &App) {
+    let theme = app.theme;
+    let popup_area = centered_rect(50, 70, f.area());
+    
+    f.render_widget(Clear, popup_area);
+    
+    let title = app.selected_entry().map(|e| e.title.as_str()).unwrap_or("");
+    let block = Block::default()
+        .title(format!("╔═ WORD FREQUENCY · {} ═╗", title))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(theme.secondary).bg(theme.background))
+        .style(Style::default().bg(theme.background));
+    
+    if app.word_report.is_empty() {
+        let empty = Paragraph::new(Span::styled("No words to report", Style::default().fg(theme.dim)))
+            .block(block)
+            .alignment(Alignment::Center);
+        f.render_widget(empty, popup_area);
+        return;
+    }
+    
+    // Bars are scaled to the most frequent word
+    let width = app.word_report.iter().map(|(word, _)| word.chars().count()).max().unwrap_or(0);
+    let top = app.word_report[0].1;
+    let bar_room = (popup_area.width as usize).saturating_sub(width + 12).max(1);
+    let lines: Vec<Line> = app.word_report.iter().map(|(word, count)| {
+        let bar = (count * bar_room).div_ceil(top);
+        Line::from(vec![
+            Span::styled(format!(" {:<width$} ", word, width = width), Style::default().fg(theme.accent)),
+            Span::styled("█".repeat(bar), Style::default().fg(theme.accent_dim)),
+            Span::styled(format!(" {}", count), Style::default().fg(theme.muted)),
+        ])
+    }).collect();
+    
+    f.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
+
+// Tags by how often they're used, with bars scaled to the most used one
+fn render_tag_browser(f: &mut Frame, app: &mut App) {
+    let theme = app.theme;
+    let popup_area = centered_rect(50, 70, f.area());
+    
+    f.render_widget(Clear, popup_area);
+    
+    let block = Block::default()
+        .title(format!("╔═ TAGS · {} ═╗", app.tag_counts.len()))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(theme.secondary).bg(theme.background))
+        .style(Style::default().bg(theme.background));
+    
+    if app.tag_counts.is_empty() {
+        let empty = Paragraph::new(vec![
+            Line::from(""),
+            Line::from(Span::styled("No tags yet; add `tags: [work, ideas]` to an entry's frontmatter", Style::default().fg(theme.dim).add_modifier(Modifier::ITALIC))),
+        ])
+        .block(block)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+        f.render_widget(empty, popup_area);
+        return;
+    }
+    
+    let width = app.tag_counts.iter().map(|(tag, _)| tag.chars().count() + 1).max().unwrap_or(0);
+    let top = app.tag_counts[0].1;
+    let bar_room = (popup_area.width as usize).saturating_sub(width + 18).max(1);
+    let items: Vec<ListItem> = app.tag_counts.iter().map(|(tag, count)| {
+        let style = if app.tag_filter.as_ref() == Some(tag) {
+            Style::default().fg(theme.warning)
+        } else {
+            Style::default().fg(theme.accent)
+        };
+        ListItem::new(Line::from(vec![
+            Span::styled(format!("{:<width$} ", format!("#{}", tag), width = width), style),
+            Span::styled("█".repeat((count * bar_room).div_ceil(top)), Style::default().fg(theme.accent_dim)),
+            Span::styled(format!(" {}", count), Style::default().fg(theme.muted)),
+        ]))
+    }).collect();
+    
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(
+            Style::default()
+                .bg(theme.highlight)
+                .add_modifier(Modifier::BOLD)
+        )
+        .highlight_symbol("█▓▒░ ");
+    
+    f.render_stateful_widget(list, popup_area, &mut app.tag_state);
+}
+
+fn render_confirm_popup(f: &mut Frame, theme: &Theme, prompt: &str) {
+    let popup_area = centered_rect(50, 20, f.area());
+    
+    f.render_widget(Clear, popup_area);
+    
+    let block = Block::default()
+        .title("╔═ CONFIRM ═╗")
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(theme.error).bg(theme.background))
+        .style(Style::default().bg(theme.background));
+    
+    let text = vec![
+        Line::from(""),
+        Line::from(Span::styled(prompt.to_string(), Style::default().fg(theme.error).add_modifier(Modifier::BOLD))),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("[y] ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            Span::styled("yes   ", Style::default().fg(theme.muted)),
+            Span::styled("[n] ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            Span::styled("no", Style::default().fg(theme.muted)),
+        ]),
+    ];
+    
+    let paragraph = Paragraph::new(text)
+        .block(block)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: false });
+    
+    f.render_widget(paragraph, popup_area);
+}
+
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
@@ -702,4 +3823,34 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
             Constraint::Percentage((100 - percent_x) / 2),
         ])
         .split(popup_layout[1])[1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn restore_sequence_leaves_paste_mode_and_the_alternate_screen_and_shows_the_cursor() {
+        let mut out = Vec::new();
+        write_restore_sequence(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "\x1b[?2004l\x1b[?1049l\x1b[?25h");
+    }
+    
+    #[test]
+    fn locked_entries_are_only_found_by_their_title() {
+        let root = tempfile::tempdir().unwrap();
+        let mut journal = Journal::open(root.path().join("entries"), root.path().join(".trash"), &Config::default()).unwrap();
+        fs::write(journal.dir().join("20240101_120000_Secret.md"), "---\nlocked: true\n---\n# Secret\n\nplutonium\n").unwrap();
+        fs::write(journal.dir().join("20240102_120000_Open.md"), "# Open\n\nplutonium\n").unwrap();
+        journal.load(false).unwrap();
+        
+        let found: Vec<&str> = journal.entries().iter()
+            .filter(|e| matches_search(e, "plutonium"))
+            .map(|e| e.title.as_str())
+            .collect();
+        assert_eq!(found, ["Open"]);
+        let locked = journal.entries().iter().find(|e| e.locked).unwrap();
+        assert!(matches_search(locked, "secret"));
+        assert!(matches_search(locked, ""));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,187 @@
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+
+use journal_tui::{attachments, frontmatter, links};
+use journal_tui::theme::Theme;
+
+pub struct Rendered {
+    pub lines: Vec<Line<'static>>,
+    // Wiki links and images in document order, for keyboard navigation
+    pub links: Vec<LinkRef>,
+}
+
+pub struct LinkRef {
+    pub line: usize,
+    pub target: String,
+    // An `![alt](path)` attachment rather than a `[[link]]` to another entry
+    pub image: bool,
+}
+
+// Styles the n-th link in the document
+pub type LinkStyle<'a> = &'a dyn Fn(usize, &LinkRef) -> Style;
+
+// Turns entry markdown into styled lines for the in-app viewer. This is deliberately
+// line-oriented: headings, lists, quotes, rules and fenced code, plus inline emphasis.
+pub fn render(content: &str, theme: &Theme, link_style: LinkStyle) -> Rendered {
+    let (_, body) = frontmatter::parse(content);
+    let mut rendered = Rendered { lines: Vec::new(), links: Vec::new() };
+    let mut in_code_block = false;
+    
+    for raw in body.lines() {
+        let trimmed = raw.trim_start();
+        
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code_block = !in_code_block;
+            rendered.lines.push(Line::from(Span::styled(raw.to_string(), Style::default().fg(theme.dim))));
+            continue;
+        }
+        
+        if in_code_block {
+            rendered.lines.push(Line::from(Span::styled(raw.to_string(), Style::default().fg(theme.warning))));
+            continue;
+        }
+        
+        let line = block_line(raw, trimmed, &mut Inline { rendered: &mut rendered, theme, link_style });
+        rendered.lines.push(line);
+    }
+    
+    rendered
+}
+
+// What inline parsing needs to record and style links
+struct Inline<'a, 'b> {
+    rendered: &'a mut Rendered,
+    theme: &'b Theme,
+    link_style: LinkStyle<'b>,
+}
+
+fn block_line(raw: &str, trimmed: &str, ctx: &mut Inline) -> Line<'static> {
+    let theme = ctx.theme;
+    let text_style = Style::default().fg(theme.text);
+    
+    // Headings: # through ######
+    let level = trimmed.chars().take_while(|&c| c == '#').count();
+    if (1..=6).contains(&level)
+        && let Some(title) = trimmed[level..].strip_prefix(' ')
+    {
+        let color = if level == 1 { theme.secondary } else { theme.accent };
+        let style = Style::default().fg(color).add_modifier(Modifier::BOLD);
+        return Line::from(inline_spans(title, style, ctx));
+    }
+    
+    if is_rule(trimmed) {
+        return Line::from(Span::styled("─".repeat(40), Style::default().fg(theme.dim)));
+    }
+    
+    if let Some(quote) = trimmed.strip_prefix('>') {
+        let mut spans = vec![Span::styled("▌ ", Style::default().fg(theme.dim))];
+        spans.extend(inline_spans(quote.trim_start(), Style::default().fg(theme.muted).add_modifier(Modifier::ITALIC), ctx));
+        return Line::from(spans);
+    }
+    
+    let indent = &raw[..raw.len() - trimmed.len()];
+    if let Some(item) = trimmed.strip_prefix("- ").or_else(|| trimmed.strip_prefix("* ")).or_else(|| trimmed.strip_prefix("+ ")) {
+        let mut spans = vec![
+            Span::raw(indent.to_string()),
+            Span::styled("• ", Style::default().fg(theme.accent)),
+        ];
+        spans.extend(inline_spans(item, text_style, ctx));
+        return Line::from(spans);
+    }
+    
+    Line::from(inline_spans(raw, text_style, ctx))
+}
+
+fn is_rule(line: &str) -> bool {
+    let compact: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    let first = compact.chars().next();
+    compact.len() >= 3
+        && matches!(first, Some('-' | '*' | '_'))
+        && compact.chars().all(|c| Some(c) == first)
+}
+
+// Handles ![images], [[links]], `code`, **bold** and *italic*/_italic_; unmatched markers are kept literally
+fn inline_spans(text: &str, base: Style, ctx: &mut Inline) -> Vec<Span<'static>> {
+    let theme = ctx.theme;
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut rest = text;
+    
+    while let Some(c) = rest.chars().next() {
+        // Images show as a placeholder naming the file
+        if rest.starts_with("![")
+            && let Some(image) = attachments::find_images(rest).into_iter().next().filter(|image| image.start == 0)
+        {
+            if !plain.is_empty() {
+                spans.push(Span::styled(std::mem::take(&mut plain), base));
+            }
+            let link = LinkRef { line: ctx.rendered.lines.len(), target: image.target.to_string(), image: true };
+            let index = ctx.rendered.links.len();
+            spans.push(Span::styled(attachments::placeholder(&image), (ctx.link_style)(index, &link)));
+            ctx.rendered.links.push(link);
+            rest = &rest[image.end..];
+            continue;
+        }
+        
+        if rest.starts_with("[[")
+            && let Some(link) = links::find(rest).into_iter().next().filter(|link| link.start == 0)
+        {
+            if !plain.is_empty() {
+                spans.push(Span::styled(std::mem::take(&mut plain), base));
+            }
+            let link_ref = LinkRef { line: ctx.rendered.lines.len(), target: link.target.to_string(), image: false };
+            let index = ctx.rendered.links.len();
+            spans.push(Span::styled(link.label.to_string(), (ctx.link_style)(index, &link_ref)));
+            ctx.rendered.links.push(link_ref);
+            rest = &rest[link.end..];
+            continue;
+        }
+        
+        // Underscores inside words (snake_case) aren't emphasis
+        let mid_word = c == '_' && plain.chars().last().is_some_and(char::is_alphanumeric);
+        let styled = match c {
+            _ if mid_word => None,
+            '`' => delimited(rest, "`").map(|(inner, len)| {
+                (inner, len, Style::default().fg(theme.warning))
+            }),
+            '*' | '_' if rest.starts_with("**") || rest.starts_with("__") => delimited(rest, &rest[..2]).map(|(inner, len)| {
+                (inner, len, base.add_modifier(Modifier::BOLD))
+            }),
+            '*' | '_' => delimited(rest, &rest[..1]).map(|(inner, len)| {
+                (inner, len, base.add_modifier(Modifier::ITALIC))
+            }),
+            _ => None,
+        };
+        
+        match styled {
+            Some((inner, len, style)) => {
+                if !plain.is_empty() {
+                    spans.push(Span::styled(std::mem::take(&mut plain), base));
+                }
+                spans.push(Span::styled(inner.to_string(), style));
+                rest = &rest[len..];
+            }
+            None => {
+                plain.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    
+    if !plain.is_empty() || spans.is_empty() {
+        spans.push(Span::styled(plain, base));
+    }
+    spans
+}
+
+// If `text` starts with `marker`, returns the non-empty inner text and the total length consumed
+fn delimited<'a>(text: &'a str, marker: &str) -> Option<(&'a str, usize)> {
+    let after = &text[marker.len()..];
+    let end = after.find(marker)?;
+    if end == 0 {
+        return None;
+    }
+    Some((&after[..end], marker.len() * 2 + end))
+}
\ No newline at end of file
@@ -1,26 +1,96 @@
 use anyhow::Result;
 use crossterm::{
-    event::{self, Event, KeyCode},
+    event::{self, Event, KeyCode, KeyEventKind},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use rand::Rng;
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
-    text::Span,
-    widgets::{Block, Borders, Paragraph},
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
     Frame, Terminal,
 };
 use std::{
     io,
+    sync::atomic::{AtomicBool, Ordering},
+    sync::mpsc::{self, Sender},
+    sync::Arc,
     thread,
     time::{Duration, Instant},
 };
 
+use journal_tui::config::AnimationConfig;
+use journal_tui::theme::Theme;
+use unicode_width::UnicodeWidthChar;
+
+const GLYPHS: &str = "アイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリルレロワヲン0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ!@#$%^&*(){}[]|\\:;<>?,./";
+
+fn random_glyph(rng: &mut impl Rng) -> char {
+    let glyphs: Vec<char> = GLYPHS.chars().collect();
+    glyphs[rng.gen_range(0..glyphs.len())]
+}
+
+// Characters per second each line of the viewer's decrypt reveal resolves at
+const REVEAL_RATE: f32 = 160.0;
+// How many scrambled glyphs run ahead of the resolved text
+const REVEAL_BAND: usize = 12;
+
+// Entry text mid-"decryption": every line shows its resolved prefix, then a band of rain
+// glyphs, then nothing. The bool is true once every line has fully resolved.
+pub fn reveal_lines(lines: Vec<Line<'static>>, started: Instant, theme: &Theme) -> (Vec<Line<'static>>, bool) {
+    let resolved = (started.elapsed().as_secs_f32() * REVEAL_RATE) as usize;
+    let longest = lines.iter().map(|l| l.spans.iter().map(|s| s.content.chars().count()).sum::<usize>()).max().unwrap_or(0);
+    if resolved >= longest {
+        return (lines, true);
+    }
+    
+    let mut rng = rand::thread_rng();
+    // Only single-width glyphs, so scrambled text takes the same columns as the real thing
+    let glyphs: Vec<char> = GLYPHS.chars().filter(|c| c.width() == Some(1)).collect();
+    let scramble_style = Style::default().fg(theme.accent_dim);
+    
+    let revealed = lines.into_iter().map(|line| {
+        let mut spans = Vec::new();
+        let mut column = 0;
+        for span in line.spans {
+            let len = span.content.chars().count();
+            if column + len <= resolved {
+                column += len;
+                spans.push(span);
+                continue;
+            }
+            
+            let keep = resolved.saturating_sub(column);
+            let clear: String = span.content.chars().take(keep).collect();
+            if !clear.is_empty() {
+                spans.push(Span::styled(clear, span.style));
+            }
+            let scrambled: String = span.content.chars()
+                .skip(keep)
+                .take((resolved + REVEAL_BAND).saturating_sub(column + keep))
+                .map(|c| if c.is_whitespace() { c } else { glyphs[rng.gen_range(0..glyphs.len())] })
+                .collect();
+            if !scrambled.is_empty() {
+                spans.push(Span::styled(scrambled, scramble_style));
+            }
+            column += len;
+            if column >= resolved + REVEAL_BAND {
+                break;
+            }
+        }
+        Line::from(spans)
+    }).collect();
+    
+    (revealed, false)
+}
+
 #[derive(Clone)]
 struct MatrixColumn {
+    // Screen column this rain stream falls in
+    x: u16,
     chars: Vec<char>,
     position: f32,
     speed: f32,
@@ -29,17 +99,16 @@ struct MatrixColumn {
 }
 
 impl MatrixColumn {
-    fn new(height: usize) -> Self {
+    fn new(x: u16, height: usize) -> Self {
         let mut rng = rand::thread_rng();
         let chars: Vec<char> = (0..height)
             .map(|_| {
-                let chars = "アイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリルレロワヲン0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ!@#$%^&*(){}[]|\\:;<>?,./";
-                let chars_vec: Vec<char> = chars.chars().collect();
-                chars_vec[rng.gen_range(0..chars_vec.len())]
+                random_glyph(&mut rng)
             })
             .collect();
         
         Self {
+            x,
             chars,
             position: rng.gen_range(-20.0..0.0),
             speed: rng.gen_range(0.3..1.5),
@@ -48,8 +117,8 @@ impl MatrixColumn {
         }
     }
     
-    fn update(&mut self) {
-        self.position += self.speed;
+    fn update(&mut self, speed_multiplier: f32) {
+        self.position += self.speed * speed_multiplier;
         let mut rng = rand::thread_rng();
         
         // Update brightness
@@ -65,11 +134,9 @@ impl MatrixColumn {
         
         // Randomly change some characters
         if rng.gen_bool(0.1) {
-            let chars = "アイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリルレロワヲン0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ!@#$%^&*(){}[]|\\:;<>?,./";
-            let chars_vec: Vec<char> = chars.chars().collect();
             for c in &mut self.chars {
                 if rng.gen_bool(0.02) {
-                    *c = chars_vec[rng.gen_range(0..chars_vec.len())];
+                    *c = random_glyph(&mut rng);
                 }
             }
         }
@@ -85,11 +152,19 @@ impl MatrixColumn {
 
 pub struct MatrixAnimation {
     columns: Vec<MatrixColumn>,
+    speed_multiplier: f32,
     phase: AnimationPhase,
     start_time: Instant,
     message: String,
     decoded_chars: usize,
+    // Lines listed under the message once it's typed, one every few frames
+    decrypted: Vec<String>,
+    revealed: usize,
+    reveal_frames: usize,
     decode_complete_time: Option<Instant>,
+    // When a pending authentication gives up
+    auth_deadline: Option<Instant>,
+    theme: Theme,
 }
 
 #[derive(Clone, PartialEq)]
@@ -102,72 +177,101 @@ enum AnimationPhase {
 }
 
 impl MatrixAnimation {
-    pub fn new(width: u16, height: u16) -> Self {
+    pub fn new(width: u16, height: u16, config: &AnimationConfig, theme: &Theme) -> Self {
+        // Only a random subset of columns gets rain at lower densities
+        let mut rng = rand::thread_rng();
+        let density = config.rain_density.clamp(0.0, 1.0) as f64;
         let columns: Vec<MatrixColumn> = (0..width)
-            .map(|_| MatrixColumn::new(height as usize))
+            .filter(|_| rng.gen_bool(density))
+            .map(|x| MatrixColumn::new(x, height as usize))
             .collect();
         
         Self {
             columns,
+            speed_multiplier: config.rain_speed,
             phase: AnimationPhase::MatrixRain,
             start_time: Instant::now(),
             message: String::new(),
             decoded_chars: 0,
+            decrypted: Vec::new(),
+            revealed: 0,
+            reveal_frames: 0,
             decode_complete_time: None,
+            auth_deadline: None,
+            theme: *theme,
         }
     }
     
-    pub fn start_authentication(&mut self) {
+    pub fn start_authentication(&mut self, deadline: Instant) {
         self.phase = AnimationPhase::Authenticating;
         self.message = "BIOMETRIC SCAN INITIATED...".to_string();
+        self.auth_deadline = Some(deadline);
     }
     
-    pub fn authentication_success(&mut self) {
+    pub fn authentication_success(&mut self, message: String, decrypted: Vec<String>) {
         self.phase = AnimationPhase::Decoding;
-        self.message = "ACCESS GRANTED - DECRYPTING JOURNAL".to_string();
+        self.message = message;
         self.decoded_chars = 0;
+        self.decrypted = decrypted;
+        self.revealed = 0;
     }
     
     pub fn authentication_failed(&mut self) {
         self.phase = AnimationPhase::Failed;
-        self.message = "ACCESS DENIED".to_string();
+        self.message = "ACCESS DENIED - AUTHENTICATION FAILED".to_string();
+    }
+    
+    pub fn authentication_timed_out(&mut self) {
+        self.phase = AnimationPhase::Failed;
+        self.message = "ACCESS DENIED - AUTHENTICATION TIMED OUT".to_string();
     }
     
     pub fn update(&mut self) {
         for col in &mut self.columns {
-            col.update();
+            col.update(self.speed_multiplier);
         }
         
         if self.phase == AnimationPhase::Decoding {
             // Type out the message character by character
-            if self.decoded_chars < self.message.len() {
-                self.decoded_chars = (self.decoded_chars + 1).min(self.message.len());
+            if self.decoded_chars < self.message.chars().count() {
+                self.decoded_chars = (self.decoded_chars + 1).min(self.message.chars().count());
+            } else if self.revealed < self.decrypted.len() {
+                self.reveal_frames += 1;
+                if self.reveal_frames.is_multiple_of(4) {
+                    self.revealed += 1;
+                }
             } else if self.decode_complete_time.is_none() {
                 // Mark when typing is complete
                 self.decode_complete_time = Some(Instant::now());
             }
             
             // Wait 1 second after typing is complete before transitioning to journal
-            if let Some(complete_time) = self.decode_complete_time {
-                if complete_time.elapsed() > Duration::from_secs(1) {
-                    self.phase = AnimationPhase::Success;
-                }
+            if let Some(complete_time) = self.decode_complete_time
+                && complete_time.elapsed() > Duration::from_secs(1)
+            {
+                self.phase = AnimationPhase::Success;
             }
         }
     }
 }
 
 // Version that doesn't leave alternate screen (for seamless transition to journal)
-pub fn run_matrix_authentication_keep_screen<F>(auth_fn: F, first_time: bool) -> Result<bool>
+// `decode_message` runs once a successful unlock has been accepted and supplies the
+// text typed out during the decoding phase, with lines listed under it as they're
+// "decrypted". Any key skips ahead to the journal. An `auth_fn` still running after
+// `timeout` counts as a failure.
+pub fn run_matrix_authentication_keep_screen<F, M>(auth_fn: F, decode_message: M, timeout: Duration, rain: &AnimationConfig, theme: &Theme) -> Result<bool>
 where
     F: FnOnce() -> Result<bool> + Send + 'static,
+    M: FnOnce() -> (String, Vec<String>) + Send + 'static,
 {
-    run_matrix_authentication_with_mode(auth_fn, first_time, false)
+    run_matrix_authentication_with_mode(auth_fn, decode_message, timeout, false, rain, theme)
 }
 
-fn run_matrix_authentication_with_mode<F>(auth_fn: F, first_time: bool, leave_screen: bool) -> Result<bool>
+fn run_matrix_authentication_with_mode<F, M>(auth_fn: F, decode_message: M, timeout: Duration, leave_screen: bool, rain: &AnimationConfig, theme: &Theme) -> Result<bool>
 where
     F: FnOnce() -> Result<bool> + Send + 'static,
+    M: FnOnce() -> (String, Vec<String>) + Send + 'static,
 {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -175,7 +279,7 @@ where
         stdout, 
         EnterAlternateScreen,
         crossterm::cursor::Hide,
-        crossterm::style::SetBackgroundColor(crossterm::style::Color::Rgb{r: 0, g: 0, b: 0}),
+        crossterm::style::SetBackgroundColor(theme.background.into()),
         crossterm::terminal::Clear(crossterm::terminal::ClearType::All)
     )?;
     
@@ -183,77 +287,13 @@ where
     let mut terminal = Terminal::new(backend)?;
     
     let (width, height) = terminal.size().map(|r| (r.width, r.height))?;
-    let mut animation = MatrixAnimation::new(width, height);
+    let mut animation = MatrixAnimation::new(width, height, rain, theme);
     
-    // For first-time setup, skip directly to decoding
-    if first_time {
-        animation.phase = AnimationPhase::Decoding;
-        animation.message = "INITIALIZING ENCRYPTED VAULT".to_string();
-        animation.decoded_chars = 0;
-        
-        // Run authentication immediately for first-time
-        let auth_result = thread::spawn(move || {
-            auth_fn()
-        });
-        
-        // Show typing animation for first-time setup
-        while !auth_result.is_finished() || animation.decoded_chars < animation.message.len() {
-            animation.update();
-            
-            if animation.decoded_chars < animation.message.len() {
-                animation.decoded_chars = (animation.decoded_chars + 1).min(animation.message.len());
-            }
-            
-            terminal.draw(|f| draw_matrix(f, &animation))?;
-            thread::sleep(Duration::from_millis(50));
-        }
-        
-        // Get the result
-        match auth_result.join().unwrap() {
-            Ok(true) => {
-                // Brief pause to show the complete message
-                thread::sleep(Duration::from_millis(500));
-                
-                if leave_screen {
-                    disable_raw_mode()?;
-                    execute!(
-                        terminal.backend_mut(), 
-                        crossterm::cursor::Show,
-                        LeaveAlternateScreen
-                    )?;
-                } else {
-                    // Keep raw mode enabled for seamless transition
-                    execute!(
-                        terminal.backend_mut(), 
-                        crossterm::cursor::Hide
-                    )?;
-                }
-                return Ok(true);
-            }
-            _ => {
-                if leave_screen {
-                    disable_raw_mode()?;
-                    execute!(
-                        terminal.backend_mut(),
-                        crossterm::cursor::Show, 
-                        LeaveAlternateScreen
-                    )?;
-                } else {
-                    // Keep raw mode enabled
-                    execute!(
-                        terminal.backend_mut(),
-                        crossterm::cursor::Hide
-                    )?;
-                }
-                return Ok(false);
-            }
-        }
-    }
-    
-    // Normal flow for non-first-time
-    animation.start_authentication();
+    // The clock includes the 3 second lead-in so the countdown on screen is honest
+    animation.start_authentication(Instant::now() + Duration::from_secs(3) + timeout);
     
-    // Run authentication in background with 3 second delay
+    // Run authentication in background with 3 second delay. The timeout only covers the
+    // prompt itself, not the mount that follows a successful unlock.
     let auth_result = thread::spawn(move || {
         thread::sleep(Duration::from_secs(3));
         auth_fn()
@@ -272,17 +312,54 @@ where
         
         terminal.draw(|f| draw_matrix(f, &animation))?;
         
+        // A prompt that never answers is abandoned on its thread. Its result is never joined,
+        // so a late success can't go on to mount the vault.
+        let timed_out = !auth_result.is_finished() && animation.auth_deadline.is_some_and(|d| Instant::now() >= d);
+        if timed_out {
+            animation.authentication_timed_out();
+            let fail_start = Instant::now();
+            while fail_start.elapsed() < Duration::from_secs(2) {
+                animation.update();
+                terminal.draw(|f| draw_matrix(f, &animation))?;
+                thread::sleep(Duration::from_millis(50));
+            }
+            if leave_screen {
+                disable_raw_mode()?;
+                execute!(terminal.backend_mut(), crossterm::cursor::Show, LeaveAlternateScreen)?;
+            } else {
+                execute!(terminal.backend_mut(), crossterm::cursor::Hide)?;
+            }
+            return Ok(false);
+        }
+        
         // Check for auth result
         if auth_result.is_finished() {
             match auth_result.join().unwrap() {
                 Ok(true) => {
-                    animation.authentication_success();
+                    // Only now that the result has been accepted: an unlock abandoned with Esc
+                    // or by the timeout must never mount the vault behind us
+                    animation.auth_deadline = None;
+                    let decoding = thread::spawn(decode_message);
+                    while !decoding.is_finished() {
+                        animation.update();
+                        terminal.draw(|f| draw_matrix(f, &animation))?;
+                        thread::sleep(Duration::from_millis(50));
+                    }
+                    let (message, decrypted) = decoding.join()
+                        .unwrap_or_else(|_| ("ACCESS GRANTED - DECRYPTING JOURNAL".to_string(), Vec::new()));
+                    animation.authentication_success(message, decrypted);
                     
-                    // Keep running until the animation completes (typing + 5 second wait)
+                    // Keep running until the animation completes (typing + 1 second wait);
+                    // waiting for input doubles as the frame delay so a key skips at once
                     while animation.phase != AnimationPhase::Success {
                         animation.update();
                         terminal.draw(|f| draw_matrix(f, &animation))?;
-                        thread::sleep(Duration::from_millis(50));
+                        if event::poll(Duration::from_millis(50))?
+                            && let Event::Key(key) = event::read()?
+                            && key.kind == KeyEventKind::Press
+                        {
+                            break;
+                        }
                     }
                     
                     if leave_screen {
@@ -332,40 +409,139 @@ where
         }
         
         // Check for ESC key
-        if event::poll(Duration::from_millis(50))? {
-            if let Event::Key(key) = event::read()? {
-                if key.code == KeyCode::Esc {
-                    if leave_screen {
-                        disable_raw_mode()?;
-                        execute!(
-                            terminal.backend_mut(),
-                            crossterm::cursor::Show, 
-                            LeaveAlternateScreen
-                        )?;
-                    } else {
-                        // Keep raw mode enabled
-                        execute!(
-                            terminal.backend_mut(),
-                            crossterm::cursor::Hide
-                        )?;
-                    }
-                    return Ok(false);
-                }
+        if event::poll(Duration::from_millis(50))?
+            && let Event::Key(key) = event::read()?
+            && key.code == KeyCode::Esc
+        {
+            if leave_screen {
+                disable_raw_mode()?;
+                execute!(
+                    terminal.backend_mut(),
+                    crossterm::cursor::Show, 
+                    LeaveAlternateScreen
+                )?;
+            } else {
+                // Keep raw mode enabled
+                execute!(
+                    terminal.backend_mut(),
+                    crossterm::cursor::Hide
+                )?;
             }
+            return Ok(false);
         }
         
         thread::sleep(Duration::from_millis(50));
     }
 }
 
-pub fn run_matrix_encrypting_animation_keep_screen() -> Result<()> {
+// Handed to the first-run worker so the animation can follow along and ask it to stop
+pub struct Progress {
+    phases: Sender<String>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Progress {
+    // Typed out as the current step, e.g. "CREATING ENCRYPTED VOLUME"
+    pub fn phase(&self, message: &str) {
+        let _ = self.phases.send(message.to_string());
+    }
+    
+    // Set once Esc is pressed; the worker checks between steps and cleans up after itself
+    pub fn cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+// First run: authenticates and builds the vault on a worker thread while the rain types out
+// each step the worker reports. Esc asks the worker to stop; the screen stays up until it
+// has finished cleaning up. Keeps the alternate screen for the journal, like the unlock flow.
+pub fn run_matrix_vault_creation_keep_screen<F>(work: F, rain: &AnimationConfig, theme: &Theme) -> Result<bool>
+where
+    F: FnOnce(&Progress) -> Result<bool> + Send + 'static,
+{
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(
+        stdout,
+        EnterAlternateScreen,
+        crossterm::cursor::Hide,
+        crossterm::style::SetBackgroundColor(theme.background.into()),
+        crossterm::terminal::Clear(crossterm::terminal::ClearType::All)
+    )?;
+    
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+    
+    let (width, height) = terminal.size().map(|r| (r.width, r.height))?;
+    let mut animation = MatrixAnimation::new(width, height, rain, theme);
+    animation.phase = AnimationPhase::Decoding;
+    animation.message = "INITIALIZING ENCRYPTED VAULT".to_string();
+    animation.decoded_chars = 0;
+    
+    let (sender, phases) = mpsc::channel();
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let progress = Progress { phases: sender, cancelled: Arc::clone(&cancelled) };
+    let worker = thread::spawn(move || work(&progress));
+    
+    while !worker.is_finished() || animation.decoded_chars < animation.message.chars().count() {
+        // Once cancelling, later steps are the worker's cleanup and not worth showing
+        while let Ok(message) = phases.try_recv() {
+            if !cancelled.load(Ordering::SeqCst) {
+                animation.message = message;
+                animation.decoded_chars = 0;
+            }
+        }
+        
+        animation.update();
+        if animation.decoded_chars < animation.message.chars().count() {
+            animation.decoded_chars += 1;
+        }
+        terminal.draw(|f| draw_matrix(f, &animation))?;
+        
+        if event::poll(Duration::from_millis(50))?
+            && let Event::Key(key) = event::read()?
+            && key.code == KeyCode::Esc
+            && !cancelled.swap(true, Ordering::SeqCst)
+        {
+            animation.message = "CANCELLING - REMOVING PARTIAL VAULT".to_string();
+            animation.decoded_chars = 0;
+        }
+    }
+    
+    let result = worker.join().unwrap_or_else(|_| Err(anyhow::anyhow!("Vault creation crashed")));
+    match result {
+        Ok(true) if !cancelled.load(Ordering::SeqCst) => {
+            // Brief pause to show the complete message
+            thread::sleep(Duration::from_millis(500));
+            execute!(terminal.backend_mut(), crossterm::cursor::Hide)?;
+            Ok(true)
+        }
+        Ok(_) => {
+            execute!(terminal.backend_mut(), crossterm::cursor::Hide)?;
+            Ok(false)
+        }
+        // Leave the screen so the error can be read
+        Err(err) => {
+            disable_raw_mode()?;
+            execute!(terminal.backend_mut(), crossterm::cursor::Show, LeaveAlternateScreen)?;
+            Err(err)
+        }
+    }
+}
+
+// Runs for `exit_duration_ms`; Esc (or any key) ends it at the next frame
+pub fn run_matrix_encrypting_animation_keep_screen(rain: &AnimationConfig, theme: &Theme) -> Result<()> {
+    if !rain.exit_animation {
+        return Ok(());
+    }
+    
     // Reuse the existing alternate screen - don't enter a new one
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(
         stdout, 
         crossterm::cursor::Hide,
-        crossterm::style::SetBackgroundColor(crossterm::style::Color::Rgb{r: 0, g: 0, b: 0}),
+        crossterm::style::SetBackgroundColor(theme.background.into()),
         crossterm::terminal::Clear(crossterm::terminal::ClearType::All)
     )?;
     
@@ -373,34 +549,30 @@ pub fn run_matrix_encrypting_animation_keep_screen() -> Result<()> {
     let mut terminal = Terminal::new(backend)?;
     
     let (width, height) = terminal.size().map(|r| (r.width, r.height))?;
-    let mut animation = MatrixAnimation::new(width, height);
+    let mut animation = MatrixAnimation::new(width, height, rain, theme);
     
     // Set up for encrypting message
     animation.phase = AnimationPhase::Decoding;
-    animation.message = "ENCRYPTING VAULT - SECURING MEMORIES".to_string();
+    animation.message = rain.exit_message.clone();
     animation.decoded_chars = 0;
     
     let start = Instant::now();
+    let duration = Duration::from_millis(rain.exit_duration_ms);
     
-    // Show the typing animation for 2 seconds
-    while start.elapsed() < Duration::from_secs(2) {
+    while start.elapsed() < duration {
         animation.update();
         
         // Type out the message
-        if animation.decoded_chars < animation.message.len() {
-            animation.decoded_chars = (animation.decoded_chars + 1).min(animation.message.len());
+        if animation.decoded_chars < animation.message.chars().count() {
+            animation.decoded_chars = (animation.decoded_chars + 1).min(animation.message.chars().count());
         }
         
         terminal.draw(|f| draw_matrix(f, &animation))?;
-        thread::sleep(Duration::from_millis(50));
         
-        // Check for ESC key to skip
-        if event::poll(Duration::from_millis(1))? {
-            if let Event::Key(key) = event::read()? {
-                if key.code == KeyCode::Esc {
-                    break;
-                }
-            }
+        // Waiting for input doubles as the frame delay, so a key press skips at once
+        // rather than after the next sleep
+        if event::poll(Duration::from_millis(50))? && skips_exit_animation(&event::read()?) {
+            break;
         }
     }
     
@@ -414,47 +586,176 @@ pub fn run_matrix_encrypting_animation_keep_screen() -> Result<()> {
     Ok(())
 }
 
-fn draw_matrix(f: &mut Frame, animation: &MatrixAnimation) {
+// Esc or any other key press; releases and repeats (from the key that quit) don't count
+fn skips_exit_animation(event: &Event) -> bool {
+    matches!(event, Event::Key(key) if key.kind == KeyEventKind::Press)
+}
+
+pub fn run_matrix_error_screen(err: &anyhow::Error, rain: &AnimationConfig, theme: &Theme) -> Result<()> {
+    // Reuse the existing alternate screen so the error appears in place of the journal
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(
+        stdout, 
+        crossterm::cursor::Hide,
+        crossterm::style::SetBackgroundColor(theme.background.into()),
+        crossterm::terminal::Clear(crossterm::terminal::ClearType::All)
+    )?;
+    
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+    
+    let (width, height) = terminal.size().map(|r| (r.width, r.height))?;
+    let mut animation = MatrixAnimation::new(width, height, rain, theme);
+    animation.phase = AnimationPhase::Failed;
+    
+    // Outermost context first, root cause last
+    let chain: Vec<String> = err.chain().map(|cause| cause.to_string()).collect();
+    
+    // Keep the rain running until any key is pressed
+    loop {
+        animation.update();
+        terminal.draw(|f| draw_error_screen(f, &animation, &chain))?;
+        
+        if event::poll(Duration::from_millis(50))?
+            && let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            break;
+        }
+    }
+    
+    Ok(())
+}
+
+fn draw_error_screen(f: &mut Frame, animation: &MatrixAnimation, chain: &[String]) {
+    let theme = animation.theme;
+    let area = f.area();
+    draw_rain(f, animation);
+    
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            "❌ SYSTEM FAILURE - JOURNAL CONNECTION LOST",
+            Style::default().fg(theme.error).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+    
+    for (i, cause) in chain.iter().enumerate() {
+        let (prefix, color) = if i == 0 {
+            ("> ", theme.error)
+        } else {
+            ("  └─ caused by: ", theme.muted)
+        };
+        lines.push(Line::from(vec![
+            Span::styled(prefix, Style::default().fg(theme.dim)),
+            Span::styled(cause.clone(), Style::default().fg(color)),
+        ]));
+    }
+    
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "[ PRESS ANY KEY TO DISCONNECT ]",
+        Style::default().fg(theme.dim).add_modifier(Modifier::ITALIC),
+    )));
+    
+    // Size the panel to the error chain, leaving room for borders and padding
+    let panel_height = (lines.len() as u16 + 2).min(area.height);
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Fill(1),
+            Constraint::Length(panel_height),
+            Constraint::Fill(1),
+        ])
+        .split(area);
+    
+    let panel_area = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(15),
+            Constraint::Percentage(70),
+            Constraint::Percentage(15),
+        ])
+        .split(vertical[1])[1];
+    
+    f.render_widget(Clear, panel_area);
+    
+    let block = Block::default()
+        .title(" ░▒▓ ERROR ▓▒░ ")
+        .title_alignment(Alignment::Center)
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(theme.error))
+        .style(Style::new().bg(theme.background));
+    
+    let paragraph = Paragraph::new(lines)
+        .style(Style::new().bg(theme.background))
+        .block(block)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: false });
+    
+    f.render_widget(paragraph, panel_area);
+}
+
+// Rain alone over the whole screen, hiding the journal while it sits idle
+pub fn draw_screensaver(f: &mut Frame, animation: &MatrixAnimation) {
+    draw_rain(f, animation);
+}
+
+fn draw_rain(f: &mut Frame, animation: &MatrixAnimation) {
+    let theme = animation.theme;
     let area = f.area();
     
-    // Explicitly set every cell to have black background with content
+    // Explicitly set every cell to have the background color with content
     let buf = f.buffer_mut();
     for y in area.top()..area.bottom() {
         for x in area.left()..area.right() {
             let cell = &mut buf[(x, y)];
             cell.set_symbol(" ");  // Set a space character
-            cell.set_style(Style::new().bg(Color::Rgb(0, 0, 0)));
+            cell.set_style(Style::new().bg(theme.background));
         }
     }
     
-    // Draw matrix rain
-    for (x, col) in animation.columns.iter().enumerate() {
+    // Draw matrix rain straight into the buffer, one cell per lit character
+    for col in &animation.columns {
+        if col.x >= area.width {
+            continue;
+        }
         for (y, &brightness) in col.brightness.iter().enumerate() {
             if brightness > 0.01 && y < area.height as usize {
                 let color = if brightness > 0.8 {
-                    Color::White
+                    theme.text
                 } else if brightness > 0.4 {
-                    Color::LightGreen
+                    theme.accent
                 } else {
-                    Color::Green
+                    theme.accent_dim
                 };
                 
                 let style = if brightness > 0.9 {
-                    Style::new().fg(color).bg(Color::Rgb(0, 0, 0)).add_modifier(Modifier::BOLD)
+                    Style::new().fg(color).bg(theme.background).add_modifier(Modifier::BOLD)
                 } else {
-                    Style::new().fg(color).bg(Color::Rgb(0, 0, 0))
+                    Style::new().fg(color).bg(theme.background)
                 };
                 
                 let char_idx = y.min(col.chars.len().saturating_sub(1));
-                let text = Span::styled(col.chars[char_idx].to_string(), style);
-                
-                if x < area.width as usize && y < area.height as usize {
-                    let rect = Rect::new(x as u16, y as u16, 1, 1);
-                    f.render_widget(Paragraph::new(text), rect);
+                let c = col.chars[char_idx];
+                let cell = &mut buf[(area.left() + col.x, area.top() + y as u16)];
+                // A one-cell Paragraph never drew double-width glyphs; keep the rain looking the same
+                if c.width() == Some(1) {
+                    cell.set_char(c);
                 }
+                cell.set_style(style);
             }
         }
     }
+}
+
+fn draw_matrix(f: &mut Frame, animation: &MatrixAnimation) {
+    let theme = animation.theme;
+    let area = f.area();
+    draw_rain(f, animation);
     
     // Draw center message based on phase
     let center = Layout::default()
@@ -481,14 +782,14 @@ fn draw_matrix(f: &mut Frame, animation: &MatrixAnimation) {
             let dots = ".".repeat((animation.start_time.elapsed().as_millis() / 500 % 4) as usize);
             (
                 format!("🔐 BIOMETRIC SCAN IN PROGRESS{}", dots),
-                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+                Style::default().fg(theme.secondary).add_modifier(Modifier::BOLD)
             )
         }
         AnimationPhase::Decoding => {
             // Show typed message with blinking cursor only while typing
-            let typed_message = &animation.message[..animation.decoded_chars];
-            let show_cursor = animation.start_time.elapsed().as_millis() / 500 % 2 == 0;
-            let cursor = if animation.decoded_chars < animation.message.len() && show_cursor {
+            let typed_message: String = animation.message.chars().take(animation.decoded_chars).collect();
+            let show_cursor = (animation.start_time.elapsed().as_millis() / 500).is_multiple_of(2);
+            let cursor = if animation.decoded_chars < animation.message.chars().count() && show_cursor {
                 "█"
             } else {
                 ""  // No cursor after message is complete
@@ -496,45 +797,97 @@ fn draw_matrix(f: &mut Frame, animation: &MatrixAnimation) {
             
             (
                 format!("> {}{}", typed_message, cursor),
-                Style::default().fg(Color::LightGreen)
+                Style::default().fg(theme.accent)
             )
         }
         AnimationPhase::Success => {
             // Keep showing the completed decrypting message
             (
                 format!("> {}", animation.message),
-                Style::default().fg(Color::LightGreen)
+                Style::default().fg(theme.accent)
             )
         },
         AnimationPhase::Failed => (
-            "❌ ACCESS DENIED - AUTHENTICATION FAILED".to_string(),
-            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD | Modifier::SLOW_BLINK)
+            format!("❌ {}", animation.message),
+            Style::default().fg(theme.error).add_modifier(Modifier::BOLD | Modifier::SLOW_BLINK)
         ),
     };
     
-    // Explicitly set every cell in message area to black with content
+    // Explicitly set every cell in message area to the background with content
     let buf = f.buffer_mut();
     for y in message_area.top()..message_area.bottom() {
         for x in message_area.left()..message_area.right() {
             let cell = &mut buf[(x, y)];
             cell.set_symbol(" ");  // Set a space character
-            cell.set_style(Style::new().bg(Color::Rgb(0, 0, 0)));
+            cell.set_style(Style::new().bg(theme.background));
         }
     }
     
     let block = Block::default()
         .borders(Borders::ALL)
         .border_style(match animation.phase {
-            AnimationPhase::Failed => Style::default().fg(Color::Red),
-            AnimationPhase::Success | AnimationPhase::Decoding => Style::default().fg(Color::LightGreen),
-            _ => Style::default().fg(Color::Cyan),
+            AnimationPhase::Failed => Style::default().fg(theme.error),
+            AnimationPhase::Success | AnimationPhase::Decoding => Style::default().fg(theme.accent),
+            _ => Style::default().fg(theme.secondary),
         })
-        .style(Style::new().bg(Color::Rgb(0, 0, 0)));
+        .style(Style::new().bg(theme.background));
     
-    let paragraph = Paragraph::new(message)
-        .style(style.bg(Color::Rgb(0, 0, 0)))  // Ensure message text also has black background
+    let mut lines = vec![Line::from(message)];
+    if matches!(animation.phase, AnimationPhase::Decoding | AnimationPhase::Success) && animation.revealed > 0 {
+        lines.push(Line::from(""));
+        for line in &animation.decrypted[..animation.revealed] {
+            lines.push(Line::from(Span::styled(format!("> DECRYPTED: {}", line), Style::default().fg(theme.muted))));
+        }
+    }
+    // Say how to get out while waiting on a prompt that may never appear
+    if animation.phase == AnimationPhase::Authenticating
+        && let Some(deadline) = animation.auth_deadline
+    {
+        let remaining = deadline.saturating_duration_since(Instant::now()).as_secs();
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("ESC to cancel · times out in {}s", remaining),
+            Style::default().fg(theme.dim).remove_modifier(Modifier::BOLD),
+        )));
+    }
+    
+    let paragraph = Paragraph::new(lines)
+        .style(style.bg(theme.background))  // Ensure message text also has the background color
         .block(block)
         .alignment(Alignment::Center);
     
     f.render_widget(paragraph, message_area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyEvent, KeyEventState, KeyModifiers};
+    
+    fn key(code: KeyCode, kind: KeyEventKind) -> Event {
+        Event::Key(KeyEvent { code, modifiers: KeyModifiers::NONE, kind, state: KeyEventState::NONE })
+    }
+    
+    #[test]
+    fn esc_or_any_key_press_skips_the_exit_animation() {
+        assert!(skips_exit_animation(&key(KeyCode::Esc, KeyEventKind::Press)));
+        assert!(skips_exit_animation(&key(KeyCode::Char('q'), KeyEventKind::Press)));
+        assert!(skips_exit_animation(&key(KeyCode::Enter, KeyEventKind::Press)));
+    }
+    
+    #[test]
+    fn releases_repeats_and_other_events_dont_skip_it() {
+        assert!(!skips_exit_animation(&key(KeyCode::Char('q'), KeyEventKind::Release)));
+        assert!(!skips_exit_animation(&key(KeyCode::Esc, KeyEventKind::Repeat)));
+        assert!(!skips_exit_animation(&Event::Resize(80, 24)));
+        assert!(!skips_exit_animation(&Event::FocusLost));
+    }
+    
+    #[test]
+    fn disabled_exit_animation_returns_without_touching_the_terminal() {
+        let rain = AnimationConfig { exit_animation: false, ..AnimationConfig::default() };
+        let start = Instant::now();
+        run_matrix_encrypting_animation_keep_screen(&rain, &Theme::no_color()).unwrap();
+        assert!(start.elapsed() < Duration::from_millis(rain.exit_duration_ms));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,304 @@
+use anyhow::Result;
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Paragraph, Wrap},
+    Frame, Terminal,
+};
+use std::io;
+use std::path::PathBuf;
+
+use journal_tui::config::{self, Config};
+use journal_tui::theme::Theme;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Step {
+    Welcome,
+    Location,
+    Password,
+    Unlock,
+    Command,
+    Review,
+}
+
+struct Wizard {
+    step: Step,
+    default_vault: PathBuf,
+    vault_path: String,
+    // Choice rows: 0 is the first option on the step
+    remember_password: usize,
+    unlock: usize,
+    command: String,
+    error: Option<String>,
+}
+
+// Steps through the choices a new install needs before its vault is created: where the vault
+// goes, whether its password is kept in the keychain, and how unlocking is checked. Returns
+// `config` with those choices filled in, or None if the user backed out.
+pub fn run(config: &Config, theme: &Theme) -> Result<Option<Config>> {
+    let default_vault = config::home_dir()?.join(".journal").join("vault.dmg");
+    let vault_path = config.vault.dmg_path.clone().unwrap_or_else(|| default_vault.clone());
+    let mut wizard = Wizard {
+        step: Step::Welcome,
+        default_vault,
+        vault_path: vault_path.display().to_string(),
+        remember_password: if config.vault.remember_password { 0 } else { 1 },
+        unlock: if config.auth.command.is_some() { 1 } else { 0 },
+        command: config.auth.command.clone().unwrap_or_default(),
+        error: None,
+    };
+    
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+    let result = wizard.run(&mut terminal, theme);
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen, crossterm::cursor::Show)?;
+    
+    if !result? {
+        return Ok(None);
+    }
+    let mut config = config.clone();
+    let vault_path = wizard.vault_path();
+    config.vault.dmg_path = (vault_path != wizard.default_vault).then_some(vault_path);
+    config.vault.remember_password = wizard.remember_password == 0;
+    config.auth.command = (wizard.unlock == 1).then(|| wizard.command.trim().to_string());
+    Ok(Some(config))
+}
+
+impl Wizard {
+    // True once the review is confirmed
+    fn run(&mut self, terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, theme: &Theme) -> Result<bool> {
+        loop {
+            terminal.draw(|f| self.draw(f, theme))?;
+            
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                return Ok(false);
+            }
+            
+            self.error = None;
+            let typing = matches!(self.step, Step::Location | Step::Command);
+            match key.code {
+                KeyCode::Esc => match self.previous() {
+                    Some(step) => self.step = step,
+                    None => return Ok(false),
+                },
+                KeyCode::Enter if self.step == Step::Review => return Ok(true),
+                KeyCode::Enter => {
+                    if let Err(message) = self.check() {
+                        self.error = Some(message);
+                    } else {
+                        self.step = self.next();
+                    }
+                }
+                KeyCode::Backspace if typing => {
+                    self.input().pop();
+                }
+                KeyCode::Char(c) if typing => self.input().push(c),
+                KeyCode::Up | KeyCode::Down | KeyCode::Char('j') | KeyCode::Char('k') => {
+                    if let Some(choice) = self.choice() {
+                        *choice = 1 - *choice;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    
+    fn next(&self) -> Step {
+        match self.step {
+            Step::Welcome => Step::Location,
+            Step::Location => Step::Password,
+            Step::Password => Step::Unlock,
+            Step::Unlock if self.unlock == 1 => Step::Command,
+            Step::Unlock | Step::Command | Step::Review => Step::Review,
+        }
+    }
+    
+    fn previous(&self) -> Option<Step> {
+        match self.step {
+            Step::Welcome => None,
+            Step::Location => Some(Step::Welcome),
+            Step::Password => Some(Step::Location),
+            Step::Unlock => Some(Step::Password),
+            Step::Command => Some(Step::Unlock),
+            Step::Review if self.unlock == 1 => Some(Step::Command),
+            Step::Review => Some(Step::Unlock),
+        }
+    }
+    
+    // Whether the current step's answer can be moved on from
+    fn check(&self) -> std::result::Result<(), String> {
+        match self.step {
+            Step::Location if self.vault_path.trim().is_empty() => Err("Enter a path for the vault".to_string()),
+            Step::Location if self.vault_path().is_dir() => Err("That's a folder; name the vault file inside it, e.g. vault.dmg".to_string()),
+            Step::Command if self.command.trim().is_empty() => Err("Enter a command, or go back and use the system check".to_string()),
+            _ => Ok(()),
+        }
+    }
+    
+    fn input(&mut self) -> &mut String {
+        if self.step == Step::Command { &mut self.command } else { &mut self.vault_path }
+    }
+    
+    fn choice(&mut self) -> Option<&mut usize> {
+        match self.step {
+            Step::Password => Some(&mut self.remember_password),
+            Step::Unlock => Some(&mut self.unlock),
+            _ => None,
+        }
+    }
+    
+    fn vault_path(&self) -> PathBuf {
+        let path = self.vault_path.trim();
+        match (path.strip_prefix("~/"), config::home_dir()) {
+            (Some(rest), Ok(home)) => home.join(rest),
+            _ => PathBuf::from(path),
+        }
+    }
+    
+    fn draw(&self, f: &mut Frame, theme: &Theme) {
+        f.render_widget(Block::default().style(Style::default().bg(theme.background)), f.area());
+        
+        let (number, title) = match self.step {
+            Step::Welcome => (1, "WELCOME"),
+            Step::Location => (2, "VAULT LOCATION"),
+            Step::Password => (3, "VAULT PASSWORD"),
+            Step::Unlock | Step::Command => (4, "UNLOCKING"),
+            Step::Review => (5, "REVIEW"),
+        };
+        let text = Style::default().fg(theme.text);
+        let muted = Style::default().fg(theme.muted);
+        
+        let mut lines = match self.step {
+            Step::Welcome => vec![
+                Line::from(Span::styled("No journal was found, so let's set one up.", text)),
+                Line::from(""),
+                Line::from(Span::styled("Entries are kept in an encrypted disk image (the vault), which is", text)),
+                Line::from(Span::styled("unlocked while the journal is open and locked again when you quit.", text)),
+                Line::from(""),
+                Line::from(Span::styled("Every answer is saved to the config file and can be changed there later.", muted)),
+            ],
+            Step::Location => vec![
+                Line::from(Span::styled("Where should the vault file live?", text)),
+                Line::from(""),
+                self.input_line(&self.vault_path, theme),
+                Line::from(""),
+                Line::from(Span::styled("An existing vault at that path is opened instead of creating a new one.", muted)),
+            ],
+            Step::Password => {
+                let mut lines = vec![Line::from(Span::styled("How should the vault password be kept?", text)), Line::from("")];
+                lines.extend(self.choice_lines(self.remember_password, &[
+                    ("In the keychain", "generated for you and unlocked automatically after the check below"),
+                    ("Nowhere", "you choose it and type it on every launch; lose it and the journal is gone"),
+                ], theme));
+                lines
+            }
+            Step::Unlock => {
+                let mut lines = vec![Line::from(Span::styled("What should confirm it's you before the journal opens?", text)), Line::from("")];
+                lines.extend(self.choice_lines(self.unlock, &[
+                    ("The system check", "Touch ID or your login password on macOS, Windows Hello on Windows"),
+                    ("A command", "anything that exits 0 to unlock, e.g. a YubiKey or OTP script"),
+                ], theme));
+                lines
+            }
+            Step::Command => vec![
+                Line::from(Span::styled("Command to run (through the shell) before unlocking:", text)),
+                Line::from(""),
+                self.input_line(&self.command, theme),
+                Line::from(""),
+                Line::from(Span::styled("Its output is hidden, so prompts need their own window or /dev/tty.", muted)),
+            ],
+            Step::Review => {
+                let vault = self.vault_path();
+                let action = if vault.exists() { "open the existing one at" } else { "create a new one at" };
+                let password = if self.remember_password == 0 { "kept in the keychain" } else { "typed on every launch" };
+                let unlock = if self.unlock == 0 { "the system check".to_string() } else { format!("`{}`", self.command.trim()) };
+                let row = |label: &str, value: String| Line::from(vec![
+                    Span::styled(format!("{:<10}", label), muted),
+                    Span::styled(value, Style::default().fg(theme.accent)),
+                ]);
+                vec![
+                    row("Vault", format!("{} {}", action, vault.display())),
+                    row("Password", password.to_string()),
+                    row("Unlock", unlock),
+                    Line::from(""),
+                    Line::from(Span::styled(format!("The config is written to {}.", Config::path().map(|p| p.display().to_string()).unwrap_or_default()), muted)),
+                ]
+            }
+        };
+        if let Some(error) = &self.error {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(error.clone(), Style::default().fg(theme.error))));
+        }
+        
+        let help = match self.step {
+            Step::Welcome => "Enter: begin | Esc: quit",
+            Step::Password | Step::Unlock => "j/k: choose | Enter: next | Esc: back",
+            Step::Review => "Enter: save and continue | Esc: back",
+            _ => "Enter: next | Esc: back",
+        };
+        
+        let area = wizard_rect(f.area(), lines.len() as u16 + 6);
+        let block = Block::default()
+            .title(format!("╔═ SETUP {}/5 · {} ═╗", number, title))
+            .title_bottom(Line::from(format!(" {} ", help)).alignment(Alignment::Center))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Double)
+            .border_style(Style::default().fg(theme.accent).bg(theme.background))
+            .style(Style::default().bg(theme.background));
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+        f.render_widget(
+            Paragraph::new(lines).wrap(Wrap { trim: false }),
+            Rect { x: inner.x + 2, y: inner.y + 1, width: inner.width.saturating_sub(4), height: inner.height.saturating_sub(1) },
+        );
+    }
+    
+    fn input_line(&self, text: &str, theme: &Theme) -> Line<'static> {
+        Line::from(vec![
+            Span::styled("> ", Style::default().fg(theme.accent)),
+            Span::styled(text.to_string(), Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            Span::styled("█", Style::default().fg(theme.accent)),
+        ])
+    }
+    
+    fn choice_lines(&self, selected: usize, options: &[(&str, &str)], theme: &Theme) -> Vec<Line<'static>> {
+        let mut lines = Vec::new();
+        for (i, (label, detail)) in options.iter().enumerate() {
+            let (marker, style) = if i == selected {
+                ("█▓▒░ ", Style::default().fg(theme.accent).add_modifier(Modifier::BOLD))
+            } else {
+                ("     ", Style::default().fg(theme.text))
+            };
+            lines.push(Line::from(Span::styled(format!("{}{}", marker, label), style)));
+            lines.push(Line::from(Span::styled(format!("     {}", detail), Style::default().fg(theme.muted))));
+            lines.push(Line::from(""));
+        }
+        lines
+    }
+}
+
+// Centered, 76 columns wide where there's room
+fn wizard_rect(area: Rect, height: u16) -> Rect {
+    let width = area.width.min(76);
+    let height = height.min(area.height);
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length((area.height - height) / 2), Constraint::Length(height), Constraint::Min(0)])
+        .split(area);
+    Rect { x: area.x + (area.width - width) / 2, width, ..vertical[1] }
+}
\ No newline at end of file
@@ -0,0 +1,86 @@
+// Case-insensitive substring search shared by list filtering and preview highlighting
+
+// Stops at the first match rather than collecting them all
+pub fn contains(text: &str, query: &str) -> bool {
+    let query = query.to_lowercase();
+    !query.is_empty() && text.char_indices().any(|(start, _)| match_at(text, start, &query).is_some())
+}
+
+// Byte ranges of every non-overlapping, case-insensitive match of `query` in `text`
+pub fn find_matches(text: &str, query: &str) -> Vec<(usize, usize)> {
+    let query = query.to_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    
+    let mut matches = Vec::new();
+    let mut start = 0;
+    while start < text.len() {
+        match match_at(text, start, &query) {
+            Some(end) => {
+                matches.push((start, end));
+                start = end;
+            }
+            None => {
+                // Advance to the next char boundary
+                start += text[start..].chars().next().map_or(1, char::len_utf8);
+            }
+        }
+    }
+    matches
+}
+
+// Lowercase chars from `start` until they cover the (lowercased) query; Some(end) if they
+// equal it. Comparing lowercased text char-by-char keeps the returned range on original
+// boundaries, and consuming the query as it goes avoids building a string at every position.
+fn match_at(text: &str, start: usize, query: &str) -> Option<usize> {
+    let mut rest = query;
+    for (offset, c) in text[start..].char_indices() {
+        for lower in c.to_lowercase() {
+            rest = rest.strip_prefix(lower)?;
+        }
+        if rest.is_empty() {
+            return Some(start + offset + c.len_utf8());
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn matching_ignores_case_and_doesnt_overlap() {
+        assert_eq!(find_matches("Rust rust RUST", "rust"), [(0, 4), (5, 9), (10, 14)]);
+        assert_eq!(find_matches("aaaa", "aa"), [(0, 2), (2, 4)]);
+        assert_eq!(find_matches("Meeting notes", "MEET"), [(0, 4)]);
+        assert!(find_matches("anything", "").is_empty());
+        assert!(find_matches("short", "shorter").is_empty());
+    }
+    
+    #[test]
+    fn ranges_stay_on_the_original_char_boundaries() {
+        let text = "Ünïcödé ÜNÏCÖDÉ";
+        let matches = find_matches(text, "ünïcödé");
+        assert_eq!(matches.len(), 2);
+        for (start, end) in matches {
+            assert_eq!(text[start..end].to_lowercase(), "ünïcödé");
+        }
+        assert_eq!(find_matches("日本語のテキスト", "テキ"), [(12, 18)]);
+    }
+    
+    #[test]
+    fn chars_that_lowercase_to_several_must_match_whole() {
+        // 'İ' lowercases to "i" plus a combining dot
+        assert_eq!(find_matches("İstanbul", "i\u{307}st"), [(0, 4)]);
+        assert!(find_matches("İstanbul", "ist").is_empty());
+    }
+    
+    #[test]
+    fn contains_agrees_with_find_matches() {
+        for (text, query) in [("Morning Coffee", "coffee"), ("Morning Coffee", "tea"), ("", "x"), ("x", ""), ("ÄÖÜ", "öü")] {
+            assert_eq!(contains(text, query), !find_matches(text, query).is_empty(), "{:?} in {:?}", query, text);
+        }
+    }
+}
\ No newline at end of file
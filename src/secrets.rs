@@ -0,0 +1,118 @@
+use anyhow::{anyhow, Result};
+
+// Passwords kept by the OS rather than in files: the login keychain on macOS and the
+// Credential Manager on Windows. Other platforms have no store, so nothing is found there
+// and saving fails.
+
+// For messages, e.g. "Failed to save the vault password to the keychain"
+#[cfg(target_os = "macos")]
+pub const STORE_NAME: &str = "the keychain";
+#[cfg(target_os = "windows")]
+pub const STORE_NAME: &str = "the Credential Manager";
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub const STORE_NAME: &str = "a credential store";
+
+#[cfg(target_os = "macos")]
+pub fn get(service: &str, account: &str) -> Option<String> {
+    let bytes = security_framework::passwords::get_generic_password(service, account).ok()?;
+    String::from_utf8(bytes).ok().filter(|secret| !secret.is_empty())
+}
+
+#[cfg(target_os = "macos")]
+pub fn set(service: &str, account: &str, secret: &str) -> Result<()> {
+    security_framework::passwords::set_generic_password(service, account, secret.as_bytes())
+        .map_err(|err| anyhow!("{}", err))
+}
+
+// Deleting something that isn't there succeeds
+#[cfg(target_os = "macos")]
+pub fn delete(service: &str, account: &str) -> Result<()> {
+    // errSecItemNotFound
+    const NOT_FOUND: i32 = -25300;
+    match security_framework::passwords::delete_generic_password(service, account) {
+        Err(err) if err.code() != NOT_FOUND => Err(anyhow!("{}", err)),
+        _ => Ok(()),
+    }
+}
+
+// Generic credentials are found by a target name alone, so it carries both parts
+#[cfg(target_os = "windows")]
+fn target_name(service: &str, account: &str) -> windows::core::HSTRING {
+    windows::core::HSTRING::from(format!("{}/{}", service, account))
+}
+
+#[cfg(target_os = "windows")]
+pub fn get(service: &str, account: &str) -> Option<String> {
+    use windows::Win32::Security::Credentials::{CredFree, CredReadW, CREDENTIALW, CRED_TYPE_GENERIC};
+    
+    let mut credential: *mut CREDENTIALW = std::ptr::null_mut();
+    unsafe {
+        CredReadW(&target_name(service, account), CRED_TYPE_GENERIC, 0, &mut credential).ok()?;
+        // An empty credential has no blob at all
+        let (blob, size) = ((*credential).CredentialBlob, (*credential).CredentialBlobSize as usize);
+        let secret = (!blob.is_null()).then(|| String::from_utf8(std::slice::from_raw_parts(blob, size).to_vec()).ok()).flatten();
+        CredFree(credential as *const std::ffi::c_void);
+        secret.filter(|secret| !secret.is_empty())
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn set(service: &str, account: &str, secret: &str) -> Result<()> {
+    use windows::core::PWSTR;
+    use windows::Win32::Security::Credentials::{CredWriteW, CREDENTIALW, CRED_PERSIST_LOCAL_MACHINE, CRED_TYPE_GENERIC};
+    
+    let wide = |text: &str| text.encode_utf16().chain(std::iter::once(0)).collect::<Vec<u16>>();
+    let mut target = wide(&format!("{}/{}", service, account));
+    let mut user = wide(account);
+    let mut blob = secret.as_bytes().to_vec();
+    let credential = CREDENTIALW {
+        Type: CRED_TYPE_GENERIC,
+        TargetName: PWSTR(target.as_mut_ptr()),
+        UserName: PWSTR(user.as_mut_ptr()),
+        CredentialBlobSize: blob.len() as u32,
+        CredentialBlob: blob.as_mut_ptr(),
+        // Kept for this user across logons, but not roamed to other machines
+        Persist: CRED_PERSIST_LOCAL_MACHINE,
+        ..Default::default()
+    };
+    unsafe { CredWriteW(&credential, 0) }.map_err(|err| anyhow!("{}", err))
+}
+
+#[cfg(target_os = "windows")]
+pub fn delete(service: &str, account: &str) -> Result<()> {
+    use windows::Win32::Foundation::ERROR_NOT_FOUND;
+    use windows::Win32::Security::Credentials::{CredDeleteW, CRED_TYPE_GENERIC};
+    
+    match unsafe { CredDeleteW(&target_name(service, account), CRED_TYPE_GENERIC, 0) } {
+        Err(err) if err.code() != ERROR_NOT_FOUND.to_hresult() => Err(anyhow!("{}", err)),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn get(_service: &str, _account: &str) -> Option<String> {
+    None
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn set(_service: &str, _account: &str, _secret: &str) -> Result<()> {
+    Err(anyhow!("No credential store is available on this platform"))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn delete(_service: &str, _account: &str) -> Result<()> {
+    Ok(())
+}
+
+// The real stores are shared with the user's other passwords, so only the fallback is tested
+#[cfg(all(test, not(any(target_os = "macos", target_os = "windows"))))]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn without_a_store_nothing_is_found_or_saved() {
+        assert_eq!(get("journal-tui", "entry-encryption"), None);
+        assert!(set("journal-tui", "entry-encryption", "secret").is_err());
+        assert!(delete("journal-tui", "entry-encryption").is_ok());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,181 @@
+use anyhow::{anyhow, bail, Result};
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+// Named color roles every screen draws with, so a preset or the config can restyle the app
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    pub background: Color,
+    // Body text and the head of each rain stream
+    pub text: Color,
+    pub muted: Color,
+    pub dim: Color,
+    // Borders, titles and the list; `accent_dim` is its quieter shade
+    pub accent: Color,
+    pub accent_dim: Color,
+    // Headers, links and informational messages
+    pub secondary: Color,
+    // Background of the selected row
+    pub highlight: Color,
+    pub warning: Color,
+    pub error: Color,
+}
+
+impl Theme {
+    pub const PRESETS: &'static [&'static str] = &["matrix", "amber", "blue", "mono", "accessible"];
+    
+    pub fn preset(name: &str) -> Option<Self> {
+        let theme = match name {
+            "matrix" => Self {
+                background: Color::Rgb(0, 0, 0),
+                text: Color::White,
+                muted: Color::Gray,
+                dim: Color::DarkGray,
+                accent: Color::LightGreen,
+                accent_dim: Color::Green,
+                secondary: Color::Cyan,
+                highlight: Color::Rgb(0, 40, 0),
+                warning: Color::Yellow,
+                error: Color::Red,
+            },
+            "amber" => Self {
+                background: Color::Rgb(0, 0, 0),
+                text: Color::Rgb(255, 226, 170),
+                muted: Color::Rgb(196, 150, 70),
+                dim: Color::Rgb(110, 80, 30),
+                accent: Color::Rgb(255, 176, 0),
+                accent_dim: Color::Rgb(176, 112, 0),
+                secondary: Color::Rgb(255, 208, 96),
+                highlight: Color::Rgb(60, 38, 0),
+                warning: Color::Rgb(255, 236, 120),
+                error: Color::Rgb(255, 84, 60),
+            },
+            "blue" => Self {
+                background: Color::Rgb(0, 0, 12),
+                text: Color::White,
+                muted: Color::Gray,
+                dim: Color::DarkGray,
+                accent: Color::Rgb(110, 170, 255),
+                accent_dim: Color::Rgb(40, 90, 200),
+                secondary: Color::Cyan,
+                highlight: Color::Rgb(0, 30, 70),
+                warning: Color::Yellow,
+                error: Color::LightRed,
+            },
+            // High contrast, no hue to tell apart
+            "mono" => Self {
+                background: Color::Black,
+                text: Color::White,
+                muted: Color::Gray,
+                dim: Color::DarkGray,
+                accent: Color::White,
+                accent_dim: Color::Gray,
+                secondary: Color::White,
+                highlight: Color::Rgb(70, 70, 70),
+                warning: Color::White,
+                error: Color::White,
+            },
+            // Terminal default background and foreground, so light terminals stay readable;
+            // only plain ANSI colors, which terminal themes tune for their own background
+            "accessible" => Self {
+                background: Color::Reset,
+                text: Color::Reset,
+                muted: Color::Reset,
+                dim: Color::Reset,
+                accent: Color::Green,
+                accent_dim: Color::Green,
+                secondary: Color::Blue,
+                highlight: Color::Reset,
+                warning: Color::Magenta,
+                error: Color::Red,
+            },
+            _ => return None,
+        };
+        Some(theme)
+    }
+    
+    pub fn from_config(config: &ThemeConfig) -> Result<Self> {
+        let mut theme = Self::preset(&config.preset).ok_or_else(|| {
+            anyhow!("Unknown theme.preset '{}'; expected one of {}", config.preset, Self::PRESETS.join(", "))
+        })?;
+        
+        let overrides = [
+            ("background", &config.background, &mut theme.background),
+            ("text", &config.text, &mut theme.text),
+            ("muted", &config.muted, &mut theme.muted),
+            ("dim", &config.dim, &mut theme.dim),
+            ("accent", &config.accent, &mut theme.accent),
+            ("accent_dim", &config.accent_dim, &mut theme.accent_dim),
+            ("secondary", &config.secondary, &mut theme.secondary),
+            ("highlight", &config.highlight, &mut theme.highlight),
+            ("warning", &config.warning, &mut theme.warning),
+            ("error", &config.error, &mut theme.error),
+        ];
+        for (role, value, slot) in overrides {
+            if let Some(value) = value {
+                *slot = match Color::from_str(value) {
+                    Ok(color) => color,
+                    Err(_) => bail!("theme.{} '{}' is not a color name or #rrggbb", role, value),
+                };
+            }
+        }
+        
+        // https://no-color.org: a non-empty NO_COLOR wins over any configured colors
+        if std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+            return Ok(Self::no_color());
+        }
+        Ok(theme)
+    }
+    
+    // Everything in the terminal's own colors; emphasis comes from bold and the selection marker
+    pub fn no_color() -> Self {
+        Self {
+            background: Color::Reset,
+            text: Color::Reset,
+            muted: Color::Reset,
+            dim: Color::Reset,
+            accent: Color::Reset,
+            accent_dim: Color::Reset,
+            secondary: Color::Reset,
+            highlight: Color::Reset,
+            warning: Color::Reset,
+            error: Color::Reset,
+        }
+    }
+}
+
+// A preset plus optional per-role overrides, each a color name ("lightgreen") or "#rrggbb"
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeConfig {
+    pub preset: String,
+    pub background: Option<String>,
+    pub text: Option<String>,
+    pub muted: Option<String>,
+    pub dim: Option<String>,
+    pub accent: Option<String>,
+    pub accent_dim: Option<String>,
+    pub secondary: Option<String>,
+    pub highlight: Option<String>,
+    pub warning: Option<String>,
+    pub error: Option<String>,
+}
+
+impl Default for ThemeConfig {
+    fn default() -> Self {
+        Self {
+            preset: "matrix".to_string(),
+            background: None,
+            text: None,
+            muted: None,
+            dim: None,
+            accent: None,
+            accent_dim: None,
+            secondary: None,
+            highlight: None,
+            warning: None,
+            error: None,
+        }
+    }
+}
\ No newline at end of file
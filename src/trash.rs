@@ -0,0 +1,234 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::journal;
+
+#[derive(Debug)]
+pub struct TrashedEntry {
+    pub title: String,
+    pub path: PathBuf,
+    pub trashed: DateTime<Local>,
+}
+
+pub fn move_to_trash(path: &Path, trash_dir: &Path) -> Result<PathBuf> {
+    fs::create_dir_all(trash_dir)?;
+    
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow!("Invalid entry path: {}", path.display()))?;
+    let dest = unique_path(&trash_dir.join(file_name));
+    fs::rename(path, &dest)?;
+    
+    // The entry is in the trash either way; without a record it's just never purged automatically
+    let _ = fs::write(record_path(&dest), Local::now().to_rfc3339());
+    
+    Ok(dest)
+}
+
+pub fn restore(trashed_path: &Path, entries_dir: &Path) -> Result<PathBuf> {
+    let file_name = trashed_path
+        .file_name()
+        .ok_or_else(|| anyhow!("Invalid trash path: {}", trashed_path.display()))?;
+    let dest = unique_path(&entries_dir.join(file_name));
+    fs::rename(trashed_path, &dest)?;
+    forget(trashed_path);
+    Ok(dest)
+}
+
+// Delete a trashed entry for good
+pub fn purge(trashed_path: &Path) -> Result<()> {
+    fs::remove_file(trashed_path)?;
+    forget(trashed_path);
+    Ok(())
+}
+
+// Drop the trash-time record of an entry that has left the trash
+pub fn forget(trashed_path: &Path) {
+    let _ = fs::remove_file(record_path(trashed_path));
+}
+
+// When each entry was trashed is kept beside it, e.g. .trash/.2024-01-01.md.trashed, since
+// the file's own mtime is the last edit and can't be stamped on read-only files
+fn record_path(trashed_path: &Path) -> PathBuf {
+    let mut name = std::ffi::OsString::from(".");
+    name.push(trashed_path.file_name().unwrap_or_default());
+    name.push(".trashed");
+    trashed_path.with_file_name(name)
+}
+
+fn recorded_at(trashed_path: &Path) -> Option<DateTime<Local>> {
+    let stamp = fs::read_to_string(record_path(trashed_path)).ok()?;
+    DateTime::parse_from_rfc3339(stamp.trim()).ok().map(|t| t.with_timezone(&Local))
+}
+
+pub fn list(trash_dir: &Path, extensions: &[String], read_title: impl Fn(&Path) -> Option<String>) -> Result<Vec<TrashedEntry>> {
+    let mut entries = Vec::new();
+    if !trash_dir.exists() {
+        return Ok(entries);
+    }
+    
+    for entry in fs::read_dir(trash_dir)? {
+        let path = entry?.path();
+        if !journal::has_entry_extension(&path, extensions) {
+            continue;
+        }
+        
+        // Entries trashed before records were kept fall back to their mtime for display
+        let trashed = match recorded_at(&path) {
+            Some(trashed) => trashed,
+            None => fs::metadata(&path)?.modified()?.into(),
+        };
+        let title = read_title(&path)
+            .or_else(|| path.file_stem().and_then(|s| s.to_str()).map(|s| s.to_string()))
+            .unwrap_or_default();
+        
+        entries.push(TrashedEntry { title, path, trashed });
+    }
+    
+    entries.sort_by_key(|e| std::cmp::Reverse(e.trashed));
+    Ok(entries)
+}
+
+// Best effort: an entry that can't be removed is skipped and reported, and one without a
+// trash-time record is kept, since its mtime says nothing about how long it's been trashed.
+// Returns the number purged and the failures.
+pub fn purge_older_than(trash_dir: &Path, extensions: &[String], max_age: Duration) -> (usize, Vec<String>) {
+    let entries = match list(trash_dir, extensions, |_| None) {
+        Ok(entries) => entries,
+        Err(err) => return (0, vec![format!("{}: {}", trash_dir.display(), err)]),
+    };
+    
+    let mut count = 0;
+    let mut failures = Vec::new();
+    for entry in entries {
+        let Some(trashed) = recorded_at(&entry.path) else {
+            continue;
+        };
+        let age = SystemTime::now()
+            .duration_since(trashed.into())
+            .unwrap_or_default();
+        if age <= max_age {
+            continue;
+        }
+        match purge(&entry.path) {
+            Ok(()) => count += 1,
+            Err(err) => failures.push(format!("{}: {}", entry.path.display(), err)),
+        }
+    }
+    (count, failures)
+}
+
+// Append a numeric suffix until the path no longer collides with an existing file
+pub fn unique_path(path: &Path) -> PathBuf {
+    if !path.exists() {
+        return path.to_path_buf();
+    }
+    
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("entry");
+    let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("md");
+    let parent = path.parent().unwrap_or(Path::new(""));
+    
+    (1..)
+        .map(|n| parent.join(format!("{}_{}.{}", stem, n, ext)))
+        .find(|candidate| !candidate.exists())
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn md() -> Vec<String> {
+        vec!["md".to_string()]
+    }
+    
+    #[test]
+    fn moving_to_trash_keeps_both_colliding_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let (entries, trash) = (dir.path().join("entries"), dir.path().join(".trash"));
+        fs::create_dir_all(&entries).unwrap();
+        fs::create_dir_all(&trash).unwrap();
+        fs::write(trash.join("a.md"), "older").unwrap();
+        fs::write(entries.join("a.md"), "newer").unwrap();
+        
+        let trashed = move_to_trash(&entries.join("a.md"), &trash).unwrap();
+        assert_eq!(trashed, trash.join("a_1.md"));
+        assert!(!entries.join("a.md").exists());
+        assert_eq!(fs::read_to_string(trash.join("a.md")).unwrap(), "older");
+        assert_eq!(fs::read_to_string(&trashed).unwrap(), "newer");
+    }
+    
+    #[test]
+    fn unique_path_counts_past_existing_suffixes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.md");
+        assert_eq!(unique_path(&path), path);
+        
+        fs::write(&path, "").unwrap();
+        fs::write(dir.path().join("a_1.md"), "").unwrap();
+        assert_eq!(unique_path(&path), dir.path().join("a_2.md"));
+    }
+    
+    #[test]
+    fn read_only_entries_are_trashed_with_the_current_time() {
+        let dir = tempfile::tempdir().unwrap();
+        let (path, trash) = (dir.path().join("a.md"), dir.path().join(".trash"));
+        fs::write(&path, "text").unwrap();
+        let file = fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() - Duration::from_secs(90 * 24 * 60 * 60)).unwrap();
+        let mut permissions = fs::metadata(&path).unwrap().permissions();
+        permissions.set_readonly(true);
+        fs::set_permissions(&path, permissions).unwrap();
+        
+        move_to_trash(&path, &trash).unwrap();
+        let listed = list(&trash, &md(), |_| None).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert!(Local::now() - listed[0].trashed < chrono::Duration::minutes(1));
+        assert_eq!(purge_older_than(&trash, &md(), Duration::from_secs(24 * 60 * 60)), (0, vec![]));
+    }
+    
+    #[test]
+    fn restore_goes_beside_a_same_named_entry_and_drops_the_record() {
+        let dir = tempfile::tempdir().unwrap();
+        let (entries, trash) = (dir.path().join("entries"), dir.path().join(".trash"));
+        fs::create_dir_all(&entries).unwrap();
+        fs::write(entries.join("a.md"), "deleted").unwrap();
+        let trashed = move_to_trash(&entries.join("a.md"), &trash).unwrap();
+        fs::write(entries.join("a.md"), "rewritten").unwrap();
+        
+        let restored = restore(&trashed, &entries).unwrap();
+        assert_eq!(restored, entries.join("a_1.md"));
+        assert_eq!(fs::read_to_string(&restored).unwrap(), "deleted");
+        assert_eq!(fs::read_to_string(entries.join("a.md")).unwrap(), "rewritten");
+        assert_eq!(fs::read_dir(&trash).unwrap().count(), 0);
+    }
+    
+    #[test]
+    fn purging_removes_only_entries_recorded_as_trashed_long_enough_ago() {
+        let dir = tempfile::tempdir().unwrap();
+        let trash = dir.path().join(".trash");
+        for name in ["old.md", "new.md"] {
+            fs::write(dir.path().join(name), name).unwrap();
+            move_to_trash(&dir.path().join(name), &trash).unwrap();
+        }
+        let old = trash.join("old.md");
+        fs::write(record_path(&old), (Local::now() - chrono::Duration::days(31)).to_rfc3339()).unwrap();
+        // Trashed before records were kept, so its age is unknown
+        fs::write(trash.join("unknown.md"), "").unwrap();
+        
+        let (purged, failures) = purge_older_than(&trash, &md(), Duration::from_secs(30 * 24 * 60 * 60));
+        assert_eq!((purged, failures), (1, vec![]));
+        assert!(!old.exists() && !record_path(&old).exists());
+        assert!(trash.join("new.md").exists());
+        assert!(trash.join("unknown.md").exists());
+    }
+    
+    #[test]
+    fn purging_a_missing_trash_does_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(purge_older_than(&dir.path().join(".trash"), &md(), Duration::ZERO), (0, vec![]));
+    }
+}
\ No newline at end of file
@@ -4,42 +4,161 @@ use std::process::{Command, Stdio};
 use std::fs;
 use std::io::Write;
 
+use crate::config::VaultConfig;
+use crate::{journal, secrets};
+
+pub const DEFAULT_VOLUME_NAME: &str = "JournalVault";
+pub const DEFAULT_KEYCHAIN_ACCOUNT: &str = "journal-tui";
+pub const DEFAULT_KEYCHAIN_SERVICE: &str = "JournalVault";
+// Lets the user choose the vault password at creation instead of the generated one
+pub const PASSWORD_ENV: &str = "JOURNAL_TUI_VAULT_PASSWORD";
+const MIN_PASSWORD_LEN: usize = 12;
+// macOS keeps the journal in an encrypted disk image mounted under /Volumes. Elsewhere there
+// are no disk images, so the vault is a private folder named after the image (~/.journal/vault)
+// and its entries are encrypted one by one with [encryption].
+pub const DISK_IMAGE: bool = cfg!(target_os = "macos");
+
 #[derive(Clone)]
 pub struct VolumeManager {
+    home_dir: PathBuf,
     dmg_path: PathBuf,
     volume_name: String,
     mount_point: PathBuf,
+    // Identify this vault's secret, so separate journals (work/personal) don't share one
+    keychain_account: String,
+    keychain_service: String,
+    // Whether the password comes from the keychain (or is derived); otherwise it's the one
+    // typed in this session
+    remember_password: bool,
+    session_password: Option<String>,
 }
 
 impl VolumeManager {
-    pub fn new() -> Self {
-        let home_dir = dirs::home_dir().expect("Could not find home directory");
-        let dmg_path = home_dir.join(".journal").join("vault.dmg");
-        let volume_name = "JournalVault".to_string();
-        let mount_point = PathBuf::from("/Volumes").join(&volume_name);
+    pub fn new() -> Result<Self> {
+        Self::from_config(&VaultConfig::default())
+    }
+    
+    pub fn from_config(config: &VaultConfig) -> Result<Self> {
+        let dmg_path = match &config.dmg_path {
+            Some(path) => path.clone(),
+            None => crate::config::home_dir()?.join(".journal").join("vault.dmg"),
+        };
+        let mut manager = Self::new_with_names(dmg_path, &config.volume_name, &config.keychain_account, &config.keychain_service)?;
+        manager.remember_password = config.remember_password;
+        Ok(manager)
+    }
+    
+    pub fn new_with_names(dmg_path: PathBuf, volume_name: &str, account: &str, service: &str) -> Result<Self> {
+        let home_dir = crate::config::home_dir()?;
+        let mount_point = if DISK_IMAGE {
+            PathBuf::from("/Volumes").join(volume_name)
+        } else {
+            dmg_path.with_extension("")
+        };
         
-        Self {
+        Ok(Self {
+            home_dir,
             dmg_path,
-            volume_name,
+            volume_name: volume_name.to_string(),
             mount_point,
-        }
+            keychain_account: account.to_string(),
+            keychain_service: service.to_string(),
+            remember_password: true,
+            session_password: None,
+        })
     }
     
+    // False when the password has to be typed each session; see `set_session_password`
+    pub fn remembers_password(&self) -> bool {
+        self.remember_password
+    }
+    
+    pub fn set_session_password(&mut self, password: String) {
+        self.session_password = Some(password);
+    }
+    
+    pub fn dmg_path(&self) -> &Path {
+        &self.dmg_path
+    }
+    
+    // Whether the vault has been created, image or folder
     pub fn dmg_exists(&self) -> bool {
-        self.dmg_path.exists()
+        if DISK_IMAGE { self.dmg_path.exists() } else { self.mount_point.is_dir() }
     }
     
+    // Something at the mount point isn't enough: after a crash it can be a leftover directory,
+    // or another image mounted under the same name
     pub fn is_mounted(&self) -> bool {
-        self.mount_point.exists()
+        matches!(self.mount_state(), MountState::Ours)
+    }
+    
+    fn mount_state(&self) -> MountState {
+        // A vault folder is always open
+        if !DISK_IMAGE {
+            return if self.mount_point.is_dir() { MountState::Ours } else { MountState::NotMounted };
+        }
+        let Some(images) = attached_images() else {
+            // Without hdiutil there's nothing better to go on
+            return if self.mount_point.exists() { MountState::Ours } else { MountState::NotMounted };
+        };
+        
+        let dmg = fs::canonicalize(&self.dmg_path).unwrap_or_else(|_| self.dmg_path.clone());
+        let ours = images.iter().find(|image| image.path == dmg);
+        match ours {
+            Some(image) if image.mount_points.contains(&self.mount_point) => MountState::Ours,
+            Some(image) => MountState::Stale(image.devices.clone()),
+            None if images.iter().any(|image| image.mount_points.contains(&self.mount_point)) => MountState::Foreign,
+            None => MountState::NotMounted,
+        }
+    }
+    
+    // Number of entries in the mounted vault; a vault without an entries dir has none
+    pub fn count_entries(&self, extensions: &[String]) -> Result<usize> {
+        let entries_path = self.get_entries_path();
+        if !entries_path.exists() {
+            return Ok(0);
+        }
+        
+        let count = fs::read_dir(entries_path)?
+            .flatten()
+            .filter(|entry| journal::has_entry_extension(&entry.path(), extensions))
+            .count();
+        Ok(count)
     }
     
     pub fn get_entries_path(&self) -> PathBuf {
         self.mount_point.join("entries")
     }
     
+    // Trash lives inside the vault so deleted entries stay encrypted
+    pub fn get_trash_path(&self) -> PathBuf {
+        self.mount_point.join(".trash")
+    }
+    
     pub fn create_encrypted_volume(&self) -> Result<()> {
-        // Generate a secure random password for the volume
-        let password = self.generate_secure_password();
+        if !DISK_IMAGE {
+            return create_private_dir(&self.mount_point);
+        }
+        // A chosen password has to be remembered in the keychain; the generated one can be re-derived
+        let password = match std::env::var(PASSWORD_ENV).ok().filter(|p| !p.is_empty()) {
+            _ if !self.remember_password => {
+                // Nothing is stored; the user keeps their own record of it
+                let password = self.password()?;
+                check_password_strength(&password)?;
+                password
+            }
+            Some(password) => {
+                check_password_strength(&password)?;
+                self.store_password(&password)?;
+                password
+            }
+            None => {
+                // A leftover item from an earlier vault would otherwise win over the derived
+                // password and leave this one unmountable
+                self.forget_password()?;
+                self.generate_secure_password()
+            }
+        };
         // Ensure parent directory exists
         if let Some(parent) = self.dmg_path.parent() {
             fs::create_dir_all(parent)?;
@@ -47,7 +166,7 @@ impl VolumeManager {
         
         // Create encrypted DMG with hdiutil using stdinpass to avoid interactive prompt
         let mut child = Command::new("hdiutil")
-            .args(&[
+            .args([
                 "create",
                 "-size", "100m",
                 "-fs", "APFS",
@@ -80,6 +199,106 @@ impl VolumeManager {
         Ok(())
     }
     
+    // Undo a first-run setup that was cancelled or failed part way: detach the volume if it
+    // got attached, delete the image and the password stored for it, so the next setup
+    // doesn't pick up a password for a vault that no longer exists
+    pub fn discard_vault(&self) -> Result<()> {
+        // Only ever called for a vault this setup created, so there's nothing of the user's in it
+        if !DISK_IMAGE {
+            if self.mount_point.is_dir() {
+                fs::remove_dir_all(&self.mount_point)
+                    .map_err(|e| anyhow!("Failed to remove {}: {}", self.mount_point.display(), e))?;
+            }
+            return Ok(());
+        }
+        self.unmount()?;
+        if self.dmg_exists() {
+            fs::remove_file(&self.dmg_path)
+                .map_err(|e| anyhow!("Failed to remove {}: {}", self.dmg_path.display(), e))?;
+        }
+        if self.remember_password {
+            self.forget_password()?;
+        }
+        Ok(())
+    }
+    
+    // A password set at creation or by rotation lives in the keychain; otherwise it's derived
+    fn password(&self) -> Result<String> {
+        if !self.remember_password {
+            return self.session_password.clone().ok_or_else(|| anyhow!("The vault password hasn't been entered"));
+        }
+        Ok(self.keychain_password().unwrap_or_else(|| self.generate_secure_password()))
+    }
+    
+    fn keychain_password(&self) -> Option<String> {
+        secrets::get(&self.keychain_service, &self.keychain_account)
+    }
+    
+    // Through the keychain API: `security add-generic-password -w <password>` would put the
+    // password on a command line any user can read with `ps`
+    fn store_password(&self, password: &str) -> Result<()> {
+        secrets::set(&self.keychain_service, &self.keychain_account, password)
+            .map_err(|err| anyhow!("Failed to save the vault password to {}: {}", secrets::STORE_NAME, err))
+    }
+    
+    // Delete the stored password, if there is one
+    fn forget_password(&self) -> Result<()> {
+        secrets::delete(&self.keychain_service, &self.keychain_account)
+            .map_err(|err| anyhow!("Failed to remove the old vault password from {}: {}", secrets::STORE_NAME, err))
+    }
+    
+    // Re-key the disk image, then remember the new password. Pass `None` for the current
+    // password to use the one this vault unlocks with.
+    pub fn change_password(&self, old: Option<&str>, new: &str) -> Result<()> {
+        if !DISK_IMAGE {
+            return Err(anyhow!("The vault folder has no password of its own; its entries are encrypted with the [encryption] passphrase"));
+        }
+        check_password_strength(new)?;
+        if !self.dmg_exists() {
+            return Err(anyhow!("No vault found at {}", self.dmg_path.display()));
+        }
+        if self.is_mounted() {
+            return Err(anyhow!("The vault is mounted; close journal-tui before changing its password"));
+        }
+        
+        let old = match old {
+            Some(old) => old.to_string(),
+            None => self.password()?,
+        };
+        let mut child = Command::new("hdiutil")
+            .args([
+                "chpass",
+                self.dmg_path.to_str().unwrap(),
+                "-oldstdinpass",
+                "-newstdinpass",
+            ])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        
+        // With both stdin flags hdiutil expects the passwords NUL-separated
+        if let Some(mut stdin) = child.stdin.take() {
+            write!(stdin, "{}\0{}\0", old, new)?;
+        }
+        
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            if error.contains("Authentication error") || error.contains("passphrase") {
+                return Err(anyhow!("The current vault password was rejected; nothing was changed"));
+            }
+            return Err(anyhow!("Failed to change the vault password: {}", error.trim()));
+        }
+        
+        if !self.remember_password {
+            return Ok(());
+        }
+        // The image already uses the new password, so say so if it can't be remembered
+        self.store_password(new)
+            .map_err(|err| anyhow!("{}. The vault password WAS changed; add it to the keychain manually.", err))
+    }
+    
     fn generate_secure_password(&self) -> String {
         // Use a deterministic password based on user's home directory
         // This way we don't need to store/retrieve it from keychain
@@ -87,9 +306,12 @@ impl VolumeManager {
         use std::hash::{Hash, Hasher};
         
         let mut hasher = DefaultHasher::new();
-        let home = dirs::home_dir().expect("Could not find home directory");
-        home.hash(&mut hasher);
-        "JournalVault".hash(&mut hasher);
+        self.home_dir.hash(&mut hasher);
+        self.keychain_service.hash(&mut hasher);
+        // The default names must keep producing the password existing vaults were created with
+        if self.keychain_account != DEFAULT_KEYCHAIN_ACCOUNT {
+            self.keychain_account.hash(&mut hasher);
+        }
         
         let hash_value = hasher.finish();
         
@@ -100,16 +322,32 @@ impl VolumeManager {
     
     
     pub fn mount_with_keychain(&self) -> Result<()> {
-        if self.is_mounted() {
-            return Ok(());
+        match self.mount_state() {
+            MountState::Ours => return Ok(()),
+            MountState::NotMounted => {}
+            MountState::Foreign => {
+                return Err(anyhow!(
+                    "Another volume is mounted at {}; eject it or set vault.volume_name",
+                    self.mount_point.display()
+                ));
+            }
+            // Our image is attached but not where we read from (a half-finished detach after
+            // a crash); detach it so the attach below starts clean
+            MountState::Stale(devices) => {
+                for device in devices {
+                    let _ = Command::new("hdiutil").args(["detach", &device, "-force"]).output();
+                }
+            }
         }
         
-        // Use the same deterministic password that was used to create the vault
-        let password = self.generate_secure_password();
+        if !DISK_IMAGE {
+            return Err(anyhow!("No journal folder at {}", self.mount_point.display()));
+        }
+        let password = self.password()?;
         
         // Mount with the password, adding newline for proper stdin format
         let mut child = Command::new("hdiutil")
-            .args(&[
+            .args([
                 "attach",
                 self.dmg_path.to_str().unwrap(),
                 "-stdinpass",
@@ -136,12 +374,12 @@ impl VolumeManager {
     }
     
     pub fn unmount(&self) -> Result<()> {
-        if !self.is_mounted() {
+        if !DISK_IMAGE || !self.is_mounted() {
             return Ok(());
         }
         
         let output = Command::new("hdiutil")
-            .args(&[
+            .args([
                 "detach",
                 self.mount_point.to_str().unwrap(),
             ])
@@ -151,7 +389,7 @@ impl VolumeManager {
             let error = String::from_utf8_lossy(&output.stderr);
             // Force unmount if regular unmount fails
             let force_output = Command::new("hdiutil")
-                .args(&[
+                .args([
                     "detach",
                     self.mount_point.to_str().unwrap(),
                     "-force",
@@ -192,4 +430,130 @@ impl VolumeManager {
         
         Ok(count)
     }
+}
+
+// Readable by this user only, like the disk image's contents
+fn create_private_dir(path: &Path) -> Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(path).map_err(|e| anyhow!("Failed to create {}: {}", path.display(), e))
+}
+
+fn check_password_strength(password: &str) -> Result<()> {
+    if password.chars().count() < MIN_PASSWORD_LEN {
+        return Err(anyhow!("Vault passwords must be at least {} characters", MIN_PASSWORD_LEN));
+    }
+    if password.chars().all(|c| c.is_ascii_alphabetic()) || password.chars().all(|c| c.is_ascii_digit()) {
+        return Err(anyhow!("Vault passwords must mix letters with digits or symbols"));
+    }
+    Ok(())
+}
+
+enum MountState {
+    NotMounted,
+    Ours,
+    // Our image is attached, but not at the mount point; holds its devices
+    Stale(Vec<String>),
+    // Some other image is mounted where ours should be
+    Foreign,
+}
+
+struct AttachedImage {
+    path: PathBuf,
+    devices: Vec<String>,
+    mount_points: Vec<PathBuf>,
+}
+
+// Parse `hdiutil info`: one block per image with an `image-path` line, then a line per
+// device of the form "/dev/disk4s1<TAB>type<TAB>/Volumes/Name". None if hdiutil isn't usable.
+fn attached_images() -> Option<Vec<AttachedImage>> {
+    let output = Command::new("hdiutil").arg("info").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    
+    let mut images: Vec<AttachedImage> = Vec::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if let Some(path) = line.strip_prefix("image-path") {
+            let path = path.trim_start().trim_start_matches(':').trim();
+            images.push(AttachedImage {
+                path: fs::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path)),
+                devices: Vec::new(),
+                mount_points: Vec::new(),
+            });
+        } else if line.starts_with("/dev/")
+            && let Some(image) = images.last_mut()
+        {
+            let fields: Vec<&str> = line.split('\t').map(str::trim).collect();
+            image.devices.push(fields[0].to_string());
+            if let Some(mount) = fields.last().filter(|f| f.starts_with('/') && !f.starts_with("/dev/")) {
+                image.mount_points.push(PathBuf::from(mount));
+            }
+        }
+    }
+    Some(images)
+}
+
+// The disk image needs hdiutil, so only the folder vault used elsewhere is tested
+#[cfg(all(test, not(target_os = "macos")))]
+mod tests {
+    use super::*;
+    
+    fn folder_vault(root: &Path) -> VolumeManager {
+        VolumeManager::new_with_names(root.join("vault.dmg"), DEFAULT_VOLUME_NAME, DEFAULT_KEYCHAIN_ACCOUNT, DEFAULT_KEYCHAIN_SERVICE).unwrap()
+    }
+    
+    #[test]
+    fn a_vault_folder_is_open_from_creation_until_discarded() {
+        let root = tempfile::tempdir().unwrap();
+        let vault = folder_vault(root.path());
+        assert!(!vault.dmg_exists());
+        assert!(!vault.is_mounted());
+        assert!(vault.mount_with_keychain().is_err());
+        
+        vault.create_encrypted_volume().unwrap();
+        assert!(vault.dmg_exists());
+        assert!(vault.is_mounted());
+        assert_eq!(vault.get_entries_path(), root.path().join("vault").join("entries"));
+        assert_eq!(vault.get_trash_path(), root.path().join("vault").join(".trash"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(root.path().join("vault")).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o700);
+        }
+        
+        // Nothing to attach or detach
+        vault.mount_with_keychain().unwrap();
+        vault.unmount().unwrap();
+        assert!(vault.is_mounted());
+        
+        vault.discard_vault().unwrap();
+        assert!(!root.path().join("vault").exists());
+    }
+    
+    #[test]
+    fn entries_in_a_vault_folder_are_counted() {
+        let root = tempfile::tempdir().unwrap();
+        let vault = folder_vault(root.path());
+        vault.create_encrypted_volume().unwrap();
+        assert_eq!(vault.count_entries(&["md".to_string()]).unwrap(), 0);
+        
+        fs::create_dir_all(vault.get_entries_path()).unwrap();
+        for name in ["a.md", "b.MD", "notes.txt"] {
+            fs::write(vault.get_entries_path().join(name), "# x").unwrap();
+        }
+        assert_eq!(vault.count_entries(&["md".to_string()]).unwrap(), 2);
+    }
+    
+    #[test]
+    fn a_vault_folder_has_no_password_to_change() {
+        let root = tempfile::tempdir().unwrap();
+        let vault = folder_vault(root.path());
+        vault.create_encrypted_volume().unwrap();
+        let err = vault.change_password(None, "a much longer passphrase 42").unwrap_err();
+        assert!(err.to_string().contains("[encryption]"), "{}", err);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,47 @@
+use anyhow::Result;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+
+// Editors and sync clients touch a file several times per save, so changes are reported
+// once things have been quiet for this long
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+// Notices changes to the journal directory made outside the app
+pub struct JournalWatcher {
+    // Dropping the watcher stops the events
+    _watcher: RecommendedWatcher,
+    events: Receiver<()>,
+    last_event: Option<Instant>,
+}
+
+impl JournalWatcher {
+    pub fn new(dir: &Path) -> Result<Self> {
+        let (sender, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            // Reads alone don't change anything worth reloading for
+            if event.is_ok_and(|e| !e.kind.is_access()) {
+                let _ = sender.send(());
+            }
+        })?;
+        // Recursive so the archive subdirectory is covered too
+        watcher.watch(dir, RecursiveMode::Recursive)?;
+        
+        Ok(Self { _watcher: watcher, events, last_event: None })
+    }
+    
+    // True once per burst of changes, after the burst has settled
+    pub fn poll(&mut self) -> bool {
+        while self.events.try_recv().is_ok() {
+            self.last_event = Some(Instant::now());
+        }
+        match self.last_event {
+            Some(at) if at.elapsed() >= DEBOUNCE => {
+                self.last_event = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
\ No newline at end of file
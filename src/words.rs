@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+
+use crate::frontmatter;
+
+// Common English words that would otherwise top every report
+const STOPWORDS: &[&str] = &[
+    "a", "about", "after", "all", "also", "am", "an", "and", "any", "are", "as", "at",
+    "be", "because", "been", "before", "being", "but", "by", "can", "could", "did", "do",
+    "does", "doing", "don't", "for", "from", "had", "has", "have", "having", "he", "her",
+    "here", "him", "his", "how", "i", "i'm", "i've", "if", "in", "into", "is", "it",
+    "it's", "its", "just", "me", "more", "my", "no", "not", "now", "of", "on", "one",
+    "only", "or", "our", "out", "over", "she", "so", "some", "than", "that", "the",
+    "their", "them", "then", "there", "these", "they", "this", "those", "to", "too",
+    "up", "us", "very", "was", "we", "were", "what", "when", "where", "which", "while",
+    "who", "why", "will", "with", "would", "you", "your",
+];
+
+// Word counts in the entry body (no frontmatter or `# title` heading), most frequent
+// first (ties alphabetically). Words are lowercased; stopwords, numbers and single
+// letters are left out.
+pub fn word_frequencies(content: &str) -> Vec<(String, usize)> {
+    let body = frontmatter::body(content);
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    
+    for word in body.split(|c: char| !(c.is_alphanumeric() || c == '\'' || c == '’')) {
+        // Quotes only belong inside words ("don't"), not around them
+        let word = word.trim_matches(|c| c == '\'' || c == '’').replace('’', "'").to_lowercase();
+        if word.chars().count() < 2
+            || word.chars().all(|c| c.is_numeric())
+            || STOPWORDS.contains(&word.as_str())
+        {
+            continue;
+        }
+        *counts.entry(word).or_insert(0) += 1;
+    }
+    
+    let mut frequencies: Vec<(String, usize)> = counts.into_iter().collect();
+    frequencies.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    frequencies
+}
+
+// Words in the body, leaving out frontmatter and the `# title` heading
+pub fn word_count(content: &str) -> usize {
+    frontmatter::body(content).split_whitespace().count()
+}
+
+// A word-count target from frontmatter `goal: 500`; anything but a positive number is ignored
+pub fn goal(content: &str) -> Option<usize> {
+    frontmatter::parse(content).0?
+        .get("goal")?
+        .trim()
+        .parse()
+        .ok()
+        .filter(|&goal| goal > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn counts(pairs: &[(&str, usize)]) -> Vec<(String, usize)> {
+        pairs.iter().map(|(word, n)| (word.to_string(), *n)).collect()
+    }
+    
+    #[test]
+    fn stopwords_numbers_and_single_letters_are_skipped() {
+        let frequencies = word_frequencies("The cat and a dog. I saw 3 cats in 2024, x y z.");
+        assert_eq!(frequencies, counts(&[("cat", 1), ("cats", 1), ("dog", 1), ("saw", 1)]));
+    }
+    
+    #[test]
+    fn words_are_case_folded_and_sorted_by_count_then_name() {
+        let frequencies = word_frequencies("Rust rust RUST. Garden garden. Bees.");
+        assert_eq!(frequencies, counts(&[("rust", 3), ("garden", 2), ("bees", 1)]));
+    }
+    
+    #[test]
+    fn apostrophes_stay_inside_words_only() {
+        // Curly apostrophes match the straight stopword, quotes around words are dropped
+        let frequencies = word_frequencies("Don’t say 'hello' — can't won't");
+        assert_eq!(frequencies, counts(&[("can't", 1), ("hello", 1), ("say", 1), ("won't", 1)]));
+    }
+    
+    #[test]
+    fn frontmatter_and_title_heading_are_not_counted() {
+        let content = "---\ntitle: Garden diary\ntags: garden\n---\n# Garden diary\n\nTomatoes again.\n";
+        assert_eq!(word_frequencies(content), counts(&[("again", 1), ("tomatoes", 1)]));
+    }
+    
+    #[test]
+    fn empty_content_has_no_words() {
+        assert!(word_frequencies("").is_empty());
+        assert!(word_frequencies("---\ntitle: x\n---\n").is_empty());
+    }
+}
\ No newline at end of file